@@ -1,8 +1,11 @@
+use crate::cli::envelope::RoomId;
 use crate::paths::AppHome;
 use eyre::Context;
 use eyre::Result;
 use eyre::bail;
+use facet::Facet;
 use std::collections::BTreeMap;
+use std::io::Write;
 use std::path::Path;
 use std::path::PathBuf;
 use tracing::debug;
@@ -18,6 +21,25 @@ const ROUTES_FILE: &str = "routes.tsv";
 const ROUTE_IDENTITIES_FILE: &str = "route_identities.tsv";
 const MEDIA_PLAYERS_FILE: &str = "media_players.tsv";
 const DEFAULT_MEDIA_PLAYER_FILE: &str = "default_media_player.txt";
+const CHAT_HISTORY_DIR: &str = "chat_history";
+const OUTBOX_FILE: &str = "outbox.tsv";
+const ROOMS_FILE: &str = "rooms.tsv";
+const SENDER_POLICY_FILE: &str = "sender_policy.txt";
+const BLOCKED_SENDERS_FILE: &str = "blocked_senders.tsv";
+const QUARANTINE_FILE: &str = "quarantine.tsv";
+const FILE_TRANSFERS_FILE: &str = "file_transfers.tsv";
+const PRESENCE_FILE: &str = "presence.tsv";
+const ROUTE_LIMITS_FILE: &str = "route_limits.tsv";
+const SENT_SEQUENCES_FILE: &str = "sent_sequences.tsv";
+const RECEIVED_SEQUENCES_FILE: &str = "received_sequences.tsv";
+const HOOKS_FILE: &str = "hooks.tsv";
+const ROUTE_ROTATION_FILE: &str = "route_rotation.tsv";
+const VEILID_CONFIG_FILE: &str = "veilid_config.json";
+
+/// Quarantined messages kept from one unknown sender; more are dropped.
+pub const MAX_QUARANTINE_PER_SENDER: usize = 20;
+/// Quarantined messages kept in total; more are dropped until some are reviewed.
+pub const MAX_QUARANTINE_TOTAL: usize = 500;
 
 #[derive(Clone, Debug, PartialEq)]
 pub struct ProfileHome {
@@ -53,6 +75,17 @@ impl ProfileHome {
     pub fn profile_veilid_dir(&self) -> PathBuf {
         self.profile_dir().join("veilid")
     }
+
+    #[must_use]
+    pub fn profile_downloads_dir(&self) -> PathBuf {
+        self.profile_dir().join("downloads")
+    }
+
+    /// Socket, log, and Veilid data of the profile's daemon.
+    #[must_use]
+    pub fn profile_daemon_dir(&self) -> PathBuf {
+        self.profile_dir().join("daemon")
+    }
 }
 
 #[derive(Clone, Debug, PartialEq, Eq)]
@@ -66,6 +99,104 @@ pub struct LocalRouteIdentity {
     pub name: String,
     pub keypair: KeyPair,
     pub record_key: RecordKey,
+    /// Keypair shared with senders so they can write into the route record's mailbox
+    /// subkeys. `None` for routes created without a mailbox.
+    pub mailbox_writer: Option<KeyPair>,
+    /// Safety settings used while listening on the route.
+    pub safety: RouteSafety,
+}
+
+/// Safety route settings for a route or a send; unset fields keep Veilid's defaults.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub struct RouteSafety {
+    /// Hops in the safety route. 0 sends without one, exposing this node to the peer.
+    pub hop_count: Option<u8>,
+    pub stability: Option<RouteStability>,
+    pub sequencing: Option<RouteSequencing>,
+}
+
+impl RouteSafety {
+    #[must_use]
+    pub fn is_default(self) -> bool {
+        self == Self::default()
+    }
+
+    /// These settings with each field given in `overrides` replaced.
+    #[must_use]
+    pub fn with_overrides(self, overrides: Self) -> Self {
+        Self {
+            hop_count: overrides.hop_count.or(self.hop_count),
+            stability: overrides.stability.or(self.stability),
+            sequencing: overrides.sequencing.or(self.sequencing),
+        }
+    }
+}
+
+/// Whether routes are chosen for speed or for staying up.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum RouteStability {
+    LowLatency,
+    Reliable,
+}
+
+impl RouteStability {
+    #[must_use]
+    pub fn as_str(self) -> &'static str {
+        match self {
+            Self::LowLatency => "low-latency",
+            Self::Reliable => "reliable",
+        }
+    }
+}
+
+impl std::str::FromStr for RouteStability {
+    type Err = eyre::Error;
+
+    fn from_str(value: &str) -> Result<Self, Self::Err> {
+        match value.trim() {
+            "low-latency" => Ok(Self::LowLatency),
+            "reliable" => Ok(Self::Reliable),
+            other => bail!(
+                "Invalid stability '{}'. Use low-latency or reliable.",
+                other
+            ),
+        }
+    }
+}
+
+/// Whether messages must travel over ordered protocols such as TCP.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum RouteSequencing {
+    NoPreference,
+    PreferOrdered,
+    EnsureOrdered,
+}
+
+impl RouteSequencing {
+    #[must_use]
+    pub fn as_str(self) -> &'static str {
+        match self {
+            Self::NoPreference => "no-preference",
+            Self::PreferOrdered => "prefer-ordered",
+            Self::EnsureOrdered => "ensure-ordered",
+        }
+    }
+}
+
+impl std::str::FromStr for RouteSequencing {
+    type Err = eyre::Error;
+
+    fn from_str(value: &str) -> Result<Self, Self::Err> {
+        match value.trim() {
+            "no-preference" => Ok(Self::NoPreference),
+            "prefer-ordered" => Ok(Self::PreferOrdered),
+            "ensure-ordered" => Ok(Self::EnsureOrdered),
+            other => bail!(
+                "Invalid sequencing '{}'. Use no-preference, prefer-ordered, or ensure-ordered.",
+                other
+            ),
+        }
+    }
 }
 
 #[derive(Clone, Debug, PartialEq, Eq)]
@@ -74,6 +205,183 @@ pub struct KnownUserRouteEntry {
     pub record_key: RecordKey,
 }
 
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum MessageDirection {
+    Inbound,
+    Outbound,
+}
+
+impl MessageDirection {
+    #[must_use]
+    pub fn as_str(self) -> &'static str {
+        match self {
+            Self::Inbound => "inbound",
+            Self::Outbound => "outbound",
+        }
+    }
+}
+
+/// Inbound message rate limits applied while listening on a route.
+///
+/// Each limit is a token bucket refilled at the per-minute rate and holding up to the
+/// burst size. A rate of 0 disables that limit; both are disabled until set with
+/// `route limit`, and the default bursts apply once a rate is given.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct RouteRateLimits {
+    pub per_sender_per_minute: u32,
+    pub per_sender_burst: u32,
+    pub global_per_minute: u32,
+    pub global_burst: u32,
+}
+
+impl Default for RouteRateLimits {
+    fn default() -> Self {
+        Self {
+            per_sender_per_minute: 0,
+            per_sender_burst: 10,
+            global_per_minute: 0,
+            global_burst: 50,
+        }
+    }
+}
+
+/// When a listener replaces its private route before Veilid reports it dead.
+///
+/// A value of 0 disables that trigger. The old route stays allocated for the grace
+/// period after the replacement is published, so senders holding it can finish.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct RouteRotationPolicy {
+    pub interval_secs: u64,
+    pub after_messages: u32,
+    pub grace_secs: u64,
+}
+
+impl Default for RouteRotationPolicy {
+    fn default() -> Self {
+        Self {
+            interval_secs: 0,
+            after_messages: 0,
+            grace_secs: 60,
+        }
+    }
+}
+
+/// Overrides applied to the profile's Veilid configuration when a node starts.
+///
+/// Stored as JSON in the profile directory; unset fields keep Veilid's defaults, which
+/// join the public network.
+#[derive(Clone, Debug, Default, PartialEq, Eq, Facet)]
+pub struct ProfileVeilidConfig {
+    /// Bootstrap hosts or URLs used instead of Veilid's public bootstrap.
+    #[facet(default)]
+    pub bootstrap: Vec<String>,
+    /// Address the UDP, TCP, and WS listeners bind, like `127.0.0.1:5150`.
+    #[facet(default)]
+    pub listen_address: Option<String>,
+    /// Password that keeps the node on a private network of nodes sharing it.
+    #[facet(default)]
+    pub network_key: Option<String>,
+    /// Four-letter capability codes to turn off, like `TUNL` or `RLAY`.
+    #[facet(default)]
+    pub disable_capabilities: Vec<String>,
+}
+
+impl ProfileVeilidConfig {
+    #[must_use]
+    pub fn is_default(&self) -> bool {
+        self == &Self::default()
+    }
+
+    /// Whether the node joins a network other than the public one, through its own
+    /// bootstrap or a network key.
+    #[must_use]
+    pub fn is_private_network(&self) -> bool {
+        !self.bootstrap.is_empty() || self.network_key.is_some()
+    }
+}
+
+/// A shell command run for every chat message a listener accepts.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct HookEntry {
+    pub name: String,
+    pub command: String,
+    /// Seconds the command may run before it is killed.
+    pub timeout_secs: u64,
+}
+
+/// How a listener treats messages from senders that are not known users.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub enum SenderPolicy {
+    /// Print messages from anyone, labelled with the raw public key.
+    #[default]
+    AllowAll,
+    /// Drop messages from unknown senders.
+    KnownOnly,
+    /// Hold messages from unknown senders for review with `inbox quarantine`.
+    Quarantine,
+}
+
+impl SenderPolicy {
+    #[must_use]
+    pub fn as_str(self) -> &'static str {
+        match self {
+            Self::AllowAll => "allow-all",
+            Self::KnownOnly => "known-only",
+            Self::Quarantine => "quarantine",
+        }
+    }
+}
+
+impl std::str::FromStr for SenderPolicy {
+    type Err = eyre::Error;
+
+    fn from_str(value: &str) -> Result<Self, Self::Err> {
+        match value.trim() {
+            "allow-all" => Ok(Self::AllowAll),
+            "known-only" => Ok(Self::KnownOnly),
+            "quarantine" => Ok(Self::Quarantine),
+            other => bail!(
+                "Invalid sender policy '{}'. Use allow-all, known-only, or quarantine.",
+                other
+            ),
+        }
+    }
+}
+
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct QuarantineEntry {
+    pub id: u64,
+    pub received_at_ms: u64,
+    pub sender: PublicKey,
+    pub message_id: String,
+    pub body: String,
+}
+
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct ChatHistoryEntry {
+    pub timestamp_ms: u64,
+    pub direction: MessageDirection,
+    pub message_id: String,
+    pub body: String,
+}
+
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct OutboxEntry {
+    pub id: u64,
+    pub known_user: String,
+    pub queued_at_ms: u64,
+    pub attempts: u32,
+    pub body: String,
+}
+
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct RoomEntry {
+    pub name: String,
+    pub room_id: RoomId,
+    /// Known-user names, in the order they were added.
+    pub members: Vec<String>,
+}
+
 #[derive(Clone, Debug, PartialEq, Eq)]
 pub struct MediaPlayerEntry {
     pub key: String,
@@ -311,8 +619,10 @@ pub fn list_known_users(profile_home: &ProfileHome) -> Result<Vec<KnownUserEntry
 ///
 /// # Errors
 ///
-/// Returns an error if the known user already exists or known-user data cannot be persisted.
+/// Returns an error if the name is invalid, the known user already exists, or
+/// known-user data cannot be persisted.
 pub fn add_known_user(profile_home: &ProfileHome, name: &str, pubkey: PublicKey) -> Result<()> {
+    validate_known_user_name(name)?;
     let mut known_users = list_known_users(profile_home)?;
     if known_users.iter().any(|entry| entry.name == name) {
         bail!("Known user '{}' already exists.", name);
@@ -329,9 +639,10 @@ pub fn add_known_user(profile_home: &ProfileHome, name: &str, pubkey: PublicKey)
 ///
 /// # Errors
 ///
-/// Returns an error if the source known user does not exist, target name already exists,
-/// or known-user data cannot be persisted.
+/// Returns an error if the source known user does not exist, target name is invalid or
+/// already exists, or known-user data cannot be persisted.
 pub fn rename_known_user(profile_home: &ProfileHome, old_name: &str, new_name: &str) -> Result<()> {
+    validate_known_user_name(new_name)?;
     let mut known_users = list_known_users(profile_home)?;
     if known_users.iter().any(|entry| entry.name == new_name) {
         bail!("Known user '{}' already exists.", new_name);
@@ -343,10 +654,40 @@ pub fn rename_known_user(profile_home: &ProfileHome, old_name: &str, new_name: &
     new_name.clone_into(&mut known_user.name);
 
     known_users.sort_by(|a, b| a.name.cmp(&b.name));
-    write_known_users_file(&known_users_file(profile_home), &known_users)
+    write_known_users_file(&known_users_file(profile_home), &known_users)?;
+
+    let old_history = chat_history_file(profile_home, old_name)?;
+    if old_history.exists() {
+        std::fs::rename(old_history, chat_history_file(profile_home, new_name)?)?;
+    }
+
+    let mut outbox = list_outbox(profile_home)?;
+    if outbox.iter().any(|entry| entry.known_user == old_name) {
+        for entry in &mut outbox {
+            if entry.known_user == old_name {
+                new_name.clone_into(&mut entry.known_user);
+            }
+        }
+        write_outbox(profile_home, &outbox)?;
+    }
+
+    let mut rooms = list_rooms(profile_home)?;
+    if rooms
+        .iter()
+        .any(|room| room.members.iter().any(|member| member == old_name))
+    {
+        for member in rooms.iter_mut().flat_map(|room| room.members.iter_mut()) {
+            if member == old_name {
+                new_name.clone_into(member);
+            }
+        }
+        write_rooms(profile_home, &rooms)?;
+    }
+    Ok(())
 }
 
-/// Remove a known-user entry from a profile.
+/// Remove a known-user entry from a profile, along with their chat history, queued
+/// outbox messages, and room memberships.
 ///
 /// # Errors
 ///
@@ -358,7 +699,32 @@ pub fn remove_known_user(profile_home: &ProfileHome, name: &str) -> Result<()> {
     if known_users.len() == prior_len {
         bail!("Known user '{}' does not exist.", name);
     }
-    write_known_users_file(&known_users_file(profile_home), &known_users)
+    write_known_users_file(&known_users_file(profile_home), &known_users)?;
+
+    if let Ok(history) = chat_history_file(profile_home, name)
+        && history.exists()
+    {
+        std::fs::remove_file(history)?;
+    }
+
+    let mut outbox = list_outbox(profile_home)?;
+    let prior_outbox_len = outbox.len();
+    outbox.retain(|entry| entry.known_user != name);
+    if outbox.len() != prior_outbox_len {
+        write_outbox(profile_home, &outbox)?;
+    }
+
+    let mut rooms = list_rooms(profile_home)?;
+    if rooms
+        .iter()
+        .any(|room| room.members.iter().any(|member| member == name))
+    {
+        for room in &mut rooms {
+            room.members.retain(|member| member != name);
+        }
+        write_rooms(profile_home, &rooms)?;
+    }
+    Ok(())
 }
 
 /// Get a known user's public key by known-user name.
@@ -505,21 +871,15 @@ pub fn remove_known_user_route_keys(
 /// Returns an error if the route already exists or route identity data cannot be persisted.
 pub fn add_local_route_identity(
     profile_home: &ProfileHome,
-    name: &str,
-    keypair: &KeyPair,
-    record_key: &RecordKey,
+    identity: &LocalRouteIdentity,
 ) -> Result<()> {
-    validate_route_name(name)?;
+    validate_route_name(&identity.name)?;
     let mut identities = list_local_route_identities(profile_home)?;
-    if identities.iter().any(|route| route.name == name) {
-        bail!("Route '{}' already exists.", name);
+    if identities.iter().any(|route| route.name == identity.name) {
+        bail!("Route '{}' already exists.", identity.name);
     }
 
-    identities.push(LocalRouteIdentity {
-        name: name.to_owned(),
-        keypair: keypair.clone(),
-        record_key: record_key.clone(),
-    });
+    identities.push(identity.clone());
     identities.sort_by(|a, b| a.name.cmp(&b.name));
 
     write_local_route_identities(profile_home, &identities)
@@ -538,6 +898,20 @@ pub fn local_route_identity(
     Ok(identities.into_iter().find(|route| route.name == name))
 }
 
+/// Replace the safety settings of an existing route.
+///
+/// # Errors
+///
+/// Returns an error if the route does not exist or route data cannot be persisted.
+pub fn set_route_safety(profile_home: &ProfileHome, name: &str, safety: RouteSafety) -> Result<()> {
+    let mut identities = list_local_route_identities(profile_home)?;
+    let Some(identity) = identities.iter_mut().find(|route| route.name == name) else {
+        bail!("Route '{}' does not exist.", name);
+    };
+    identity.safety = safety;
+    write_local_route_identities(profile_home, &identities)
+}
+
 /// Remove a named local route identity for a profile.
 ///
 /// # Errors
@@ -552,120 +926,457 @@ pub fn remove_local_route_identity(profile_home: &ProfileHome, name: &str) -> Re
         bail!("Route '{}' does not exist.", name);
     }
 
-    write_local_route_identities(profile_home, &identities)
+    write_local_route_identities(profile_home, &identities)?;
+    let mut limits = list_route_rate_limits(profile_home)?;
+    if limits.remove(name).is_some() {
+        write_route_rate_limits(profile_home, &limits)?;
+    }
+    let mut policies = list_route_rotation_policies(profile_home)?;
+    if policies.remove(name).is_some() {
+        write_route_rotation_policies(profile_home, &policies)?;
+    }
+    Ok(())
 }
 
-/// List all named local route identities for a profile.
+/// Load the inbound rate limits for a route, falling back to the defaults.
 ///
 /// # Errors
 ///
-/// Returns an error if route identity data cannot be loaded or parsed.
-pub fn list_local_route_identities(profile_home: &ProfileHome) -> Result<Vec<LocalRouteIdentity>> {
+/// Returns an error if the limits file cannot be read or parsed.
+pub fn route_rate_limits(profile_home: &ProfileHome, route: &str) -> Result<RouteRateLimits> {
+    Ok(list_route_rate_limits(profile_home)?
+        .remove(route)
+        .unwrap_or_default())
+}
+
+/// Persist the inbound rate limits for an existing route.
+///
+/// # Errors
+///
+/// Returns an error if the route does not exist or the limits cannot be persisted.
+pub fn set_route_rate_limits(
+    profile_home: &ProfileHome,
+    route: &str,
+    route_limits: RouteRateLimits,
+) -> Result<()> {
+    if local_route_identity(profile_home, route)?.is_none() {
+        bail!("Route '{}' does not exist.", route);
+    }
+    let mut limits = list_route_rate_limits(profile_home)?;
+    let _ = limits.insert(route.to_owned(), route_limits);
+    write_route_rate_limits(profile_home, &limits)
+}
+
+fn list_route_rate_limits(profile_home: &ProfileHome) -> Result<BTreeMap<String, RouteRateLimits>> {
     ensure_profile_exists(profile_home)?;
-    let path = route_identities_file(profile_home);
+    let path = route_limits_file(profile_home);
     if !path.exists() {
-        return Ok(Vec::new());
+        return Ok(BTreeMap::new());
     }
 
-    let mut routes = Vec::new();
+    let mut limits = BTreeMap::new();
     for line in std::fs::read_to_string(&path)?.lines() {
-        let mut parts = line.splitn(3, '\t');
-        let Some(name) = parts.next() else {
-            continue;
-        };
-        let Some(keypair_text) = parts.next() else {
-            continue;
-        };
-        let Some(record_key_text) = parts.next() else {
+        let mut parts = line.splitn(5, '\t');
+        let (
+            Some(name),
+            Some(per_sender_per_minute),
+            Some(per_sender_burst),
+            Some(global_per_minute),
+            Some(global_burst),
+        ) = (
+            parts.next(),
+            parts.next(),
+            parts.next(),
+            parts.next(),
+            parts.next(),
+        )
+        else {
             continue;
         };
-
-        routes.push(LocalRouteIdentity {
-            name: name.to_owned(),
-            keypair: keypair_text.parse::<KeyPair>()?,
-            record_key: record_key_text.parse::<RecordKey>()?,
-        });
+        let _ = limits.insert(
+            name.to_owned(),
+            RouteRateLimits {
+                per_sender_per_minute: per_sender_per_minute.parse()?,
+                per_sender_burst: per_sender_burst.parse()?,
+                global_per_minute: global_per_minute.parse()?,
+                global_burst: global_burst.parse()?,
+            },
+        );
     }
-    routes.sort_by(|a, b| a.name.cmp(&b.name));
-    Ok(routes)
+    Ok(limits)
 }
 
-/// List configured media players for a profile.
+/// Load the rotation policy for a route, falling back to the default of rotating only
+/// dead routes.
 ///
 /// # Errors
 ///
-/// Returns an error if media-player data cannot be loaded.
-pub fn list_media_players(profile_home: &ProfileHome) -> Result<Vec<MediaPlayerEntry>> {
+/// Returns an error if the rotation file cannot be read or parsed.
+pub fn route_rotation_policy(
+    profile_home: &ProfileHome,
+    route: &str,
+) -> Result<RouteRotationPolicy> {
+    Ok(list_route_rotation_policies(profile_home)?
+        .remove(route)
+        .unwrap_or_default())
+}
+
+/// Persist the rotation policy for an existing route.
+///
+/// # Errors
+///
+/// Returns an error if the route does not exist or the policy cannot be persisted.
+pub fn set_route_rotation_policy(
+    profile_home: &ProfileHome,
+    route: &str,
+    policy: RouteRotationPolicy,
+) -> Result<()> {
+    if local_route_identity(profile_home, route)?.is_none() {
+        bail!("Route '{}' does not exist.", route);
+    }
+    let mut policies = list_route_rotation_policies(profile_home)?;
+    let _ = policies.insert(route.to_owned(), policy);
+    write_route_rotation_policies(profile_home, &policies)
+}
+
+fn list_route_rotation_policies(
+    profile_home: &ProfileHome,
+) -> Result<BTreeMap<String, RouteRotationPolicy>> {
     ensure_profile_exists(profile_home)?;
-    let path = media_players_file(profile_home);
-    debug!(
-        media_players_file = %path.display(),
-        "loading media players"
-    );
+    let path = route_rotation_file(profile_home);
     if !path.exists() {
-        debug!(
-            media_players_file = %path.display(),
-            "media players file does not exist"
-        );
-        return Ok(Vec::new());
+        return Ok(BTreeMap::new());
     }
 
-    let mut players = Vec::new();
+    let mut policies = BTreeMap::new();
     for line in std::fs::read_to_string(&path)?.lines() {
-        let Some((key, player_path)) = line.split_once('\t') else {
+        let mut parts = line.splitn(4, '\t');
+        let (Some(name), Some(interval_secs), Some(after_messages), Some(grace_secs)) =
+            (parts.next(), parts.next(), parts.next(), parts.next())
+        else {
             continue;
         };
-        if key.trim().is_empty() || player_path.trim().is_empty() {
-            continue;
-        }
-        players.push(MediaPlayerEntry {
-            key: key.to_owned(),
-            path: PathBuf::from(player_path),
-        });
+        let _ = policies.insert(
+            name.to_owned(),
+            RouteRotationPolicy {
+                interval_secs: interval_secs.parse()?,
+                after_messages: after_messages.parse()?,
+                grace_secs: grace_secs.parse()?,
+            },
+        );
     }
-    players.sort_by(|a, b| a.key.cmp(&b.key).then_with(|| a.path.cmp(&b.path)));
-    debug!(
-        media_players_file = %path.display(),
-        media_player_count = players.len(),
-        "loaded media players"
-    );
-    Ok(players)
+    Ok(policies)
 }
 
-/// Add or update a configured media player path.
+fn write_route_rotation_policies(
+    profile_home: &ProfileHome,
+    policies: &BTreeMap<String, RouteRotationPolicy>,
+) -> Result<()> {
+    let lines = policies
+        .iter()
+        .map(|(name, policy)| {
+            format!(
+                "{}\t{}\t{}\t{}",
+                name, policy.interval_secs, policy.after_messages, policy.grace_secs
+            )
+        })
+        .collect::<Vec<_>>();
+    std::fs::write(route_rotation_file(profile_home), lines.join("\n"))?;
+    Ok(())
+}
+
+/// Path of the profile's Veilid configuration overrides.
+#[must_use]
+pub fn veilid_config_file(profile_home: &ProfileHome) -> PathBuf {
+    profile_home.profile_dir().join(VEILID_CONFIG_FILE)
+}
+
+/// Load the profile's Veilid configuration overrides, or none when the file is missing.
 ///
 /// # Errors
 ///
-/// Returns an error if media-player data cannot be loaded or persisted.
-pub fn upsert_media_player(profile_home: &ProfileHome, key: &str, path: &Path) -> Result<()> {
-    validate_media_player_key(key)?;
-    let mut players = list_media_players(profile_home)?;
-    let path = path.to_path_buf();
-    debug!(
-        media_player_key = key,
-        media_player_path = %path.display(),
-        "upserting media player"
-    );
-
-    if let Some(existing) = players.iter_mut().find(|entry| entry.key == key) {
-        existing.path = path;
-    } else {
-        players.push(MediaPlayerEntry {
-            key: key.to_owned(),
-            path,
-        });
+/// Returns an error if the file cannot be read or is not valid configuration JSON.
+pub fn veilid_config(profile_home: &ProfileHome) -> Result<ProfileVeilidConfig> {
+    let path = veilid_config_file(profile_home);
+    if !path.exists() {
+        return Ok(ProfileVeilidConfig::default());
     }
+    let text = std::fs::read_to_string(&path)?;
+    facet_json::from_str(&text).map_err(|error| {
+        eyre::eyre!(
+            "Invalid Veilid configuration in {}: {error}",
+            path.display()
+        )
+    })
+}
 
-    players.sort_by(|a, b| a.key.cmp(&b.key).then_with(|| a.path.cmp(&b.path)));
-    write_media_players_file(profile_home, &players)
+/// Persist the profile's Veilid configuration overrides, removing the file when none
+/// are set.
+///
+/// # Errors
+///
+/// Returns an error if a capability code is malformed or the file cannot be written.
+pub fn set_veilid_config(profile_home: &ProfileHome, config: &ProfileVeilidConfig) -> Result<()> {
+    ensure_profile_exists(profile_home)?;
+    for code in &config.disable_capabilities {
+        if code.len() != 4
+            || !code
+                .chars()
+                .all(|character| character.is_ascii_alphanumeric())
+        {
+            bail!("Capability '{}' is not a four-letter code like TUNL.", code);
+        }
+    }
+    let path = veilid_config_file(profile_home);
+    if config.is_default() {
+        if path.exists() {
+            std::fs::remove_file(path)?;
+        }
+        return Ok(());
+    }
+    std::fs::write(path, facet_json::to_string_pretty(config)?)?;
+    Ok(())
 }
 
-/// Look up a configured media player by key.
+/// List the message hooks configured for a profile.
 ///
 /// # Errors
 ///
-/// Returns an error if media-player data cannot be loaded.
-pub fn media_player(profile_home: &ProfileHome, key: &str) -> Result<Option<MediaPlayerEntry>> {
+/// Returns an error if hook data cannot be read or parsed.
+pub fn list_hooks(profile_home: &ProfileHome) -> Result<Vec<HookEntry>> {
+    ensure_profile_exists(profile_home)?;
+    let path = hooks_file(profile_home);
+    if !path.exists() {
+        return Ok(Vec::new());
+    }
+
+    let mut hooks = Vec::new();
+    for line in std::fs::read_to_string(&path)?.lines() {
+        let mut parts = line.splitn(3, '\t');
+        let (Some(name), Some(timeout_secs), Some(command)) =
+            (parts.next(), parts.next(), parts.next())
+        else {
+            continue;
+        };
+        hooks.push(HookEntry {
+            name: name.to_owned(),
+            command: unescape_tsv_field(command),
+            timeout_secs: timeout_secs.parse()?,
+        });
+    }
+    Ok(hooks)
+}
+
+/// Add a message hook.
+///
+/// # Errors
+///
+/// Returns an error if the name or command is invalid, a hook with that name already
+/// exists, or hook data cannot be persisted.
+pub fn add_hook(profile_home: &ProfileHome, hook: HookEntry) -> Result<()> {
+    validate_hook_name(&hook.name)?;
+    if hook.command.trim().is_empty() {
+        bail!("Hook command cannot be empty.");
+    }
+    if hook.timeout_secs == 0 {
+        bail!("Hook timeout must be greater than 0.");
+    }
+    let mut hooks = list_hooks(profile_home)?;
+    if hooks.iter().any(|existing| existing.name == hook.name) {
+        bail!("Hook '{}' already exists.", hook.name);
+    }
+    hooks.push(hook);
+    hooks.sort_by(|a, b| a.name.cmp(&b.name));
+    write_hooks(profile_home, &hooks)
+}
+
+/// Remove a message hook.
+///
+/// # Errors
+///
+/// Returns an error if the hook does not exist or hook data cannot be persisted.
+pub fn remove_hook(profile_home: &ProfileHome, name: &str) -> Result<()> {
+    let mut hooks = list_hooks(profile_home)?;
+    let before = hooks.len();
+    hooks.retain(|hook| hook.name != name);
+    if hooks.len() == before {
+        bail!("Hook '{}' does not exist.", name);
+    }
+    write_hooks(profile_home, &hooks)
+}
+
+fn write_hooks(profile_home: &ProfileHome, hooks: &[HookEntry]) -> Result<()> {
+    let lines = hooks
+        .iter()
+        .map(|hook| {
+            format!(
+                "{}\t{}\t{}",
+                hook.name,
+                hook.timeout_secs,
+                escape_tsv_field(&hook.command)
+            )
+        })
+        .collect::<Vec<_>>();
+    std::fs::write(hooks_file(profile_home), lines.join("\n"))?;
+    Ok(())
+}
+
+fn write_route_rate_limits(
+    profile_home: &ProfileHome,
+    limits: &BTreeMap<String, RouteRateLimits>,
+) -> Result<()> {
+    let lines = limits
+        .iter()
+        .map(|(name, limits)| {
+            format!(
+                "{}\t{}\t{}\t{}\t{}",
+                name,
+                limits.per_sender_per_minute,
+                limits.per_sender_burst,
+                limits.global_per_minute,
+                limits.global_burst
+            )
+        })
+        .collect::<Vec<_>>();
+    std::fs::write(route_limits_file(profile_home), lines.join("\n"))?;
+    Ok(())
+}
+
+/// List all named local route identities for a profile.
+///
+/// # Errors
+///
+/// Returns an error if route identity data cannot be loaded or parsed.
+pub fn list_local_route_identities(profile_home: &ProfileHome) -> Result<Vec<LocalRouteIdentity>> {
+    ensure_profile_exists(profile_home)?;
+    let path = route_identities_file(profile_home);
+    if !path.exists() {
+        return Ok(Vec::new());
+    }
+
+    let mut routes = Vec::new();
+    for line in std::fs::read_to_string(&path)?.lines() {
+        let mut parts = line.splitn(7, '\t');
+        let Some(name) = parts.next() else {
+            continue;
+        };
+        let Some(keypair_text) = parts.next() else {
+            continue;
+        };
+        let Some(record_key_text) = parts.next() else {
+            continue;
+        };
+        let mailbox_writer = parts
+            .next()
+            .filter(|text| !text.is_empty())
+            .map(str::parse::<KeyPair>)
+            .transpose()?;
+        let safety = RouteSafety {
+            hop_count: parts
+                .next()
+                .filter(|text| !text.is_empty())
+                .map(str::parse)
+                .transpose()?,
+            stability: parts
+                .next()
+                .filter(|text| !text.is_empty())
+                .map(str::parse)
+                .transpose()?,
+            sequencing: parts
+                .next()
+                .filter(|text| !text.is_empty())
+                .map(str::parse)
+                .transpose()?,
+        };
+
+        routes.push(LocalRouteIdentity {
+            name: name.to_owned(),
+            keypair: keypair_text.parse::<KeyPair>()?,
+            record_key: record_key_text.parse::<RecordKey>()?,
+            mailbox_writer,
+            safety,
+        });
+    }
+    routes.sort_by(|a, b| a.name.cmp(&b.name));
+    Ok(routes)
+}
+
+/// List configured media players for a profile.
+///
+/// # Errors
+///
+/// Returns an error if media-player data cannot be loaded.
+pub fn list_media_players(profile_home: &ProfileHome) -> Result<Vec<MediaPlayerEntry>> {
+    ensure_profile_exists(profile_home)?;
+    let path = media_players_file(profile_home);
+    debug!(
+        media_players_file = %path.display(),
+        "loading media players"
+    );
+    if !path.exists() {
+        debug!(
+            media_players_file = %path.display(),
+            "media players file does not exist"
+        );
+        return Ok(Vec::new());
+    }
+
+    let mut players = Vec::new();
+    for line in std::fs::read_to_string(&path)?.lines() {
+        let Some((key, player_path)) = line.split_once('\t') else {
+            continue;
+        };
+        if key.trim().is_empty() || player_path.trim().is_empty() {
+            continue;
+        }
+        players.push(MediaPlayerEntry {
+            key: key.to_owned(),
+            path: PathBuf::from(player_path),
+        });
+    }
+    players.sort_by(|a, b| a.key.cmp(&b.key).then_with(|| a.path.cmp(&b.path)));
+    debug!(
+        media_players_file = %path.display(),
+        media_player_count = players.len(),
+        "loaded media players"
+    );
+    Ok(players)
+}
+
+/// Add or update a configured media player path.
+///
+/// # Errors
+///
+/// Returns an error if media-player data cannot be loaded or persisted.
+pub fn upsert_media_player(profile_home: &ProfileHome, key: &str, path: &Path) -> Result<()> {
+    validate_media_player_key(key)?;
+    let mut players = list_media_players(profile_home)?;
+    let path = path.to_path_buf();
+    debug!(
+        media_player_key = key,
+        media_player_path = %path.display(),
+        "upserting media player"
+    );
+
+    if let Some(existing) = players.iter_mut().find(|entry| entry.key == key) {
+        existing.path = path;
+    } else {
+        players.push(MediaPlayerEntry {
+            key: key.to_owned(),
+            path,
+        });
+    }
+
+    players.sort_by(|a, b| a.key.cmp(&b.key).then_with(|| a.path.cmp(&b.path)));
+    write_media_players_file(profile_home, &players)
+}
+
+/// Look up a configured media player by key.
+///
+/// # Errors
+///
+/// Returns an error if media-player data cannot be loaded.
+pub fn media_player(profile_home: &ProfileHome, key: &str) -> Result<Option<MediaPlayerEntry>> {
     validate_media_player_key(key)?;
     let players = list_media_players(profile_home)?;
     let found = players.into_iter().find(|entry| entry.key == key);
@@ -677,53 +1388,773 @@ pub fn media_player(profile_home: &ProfileHome, key: &str) -> Result<Option<Medi
     Ok(found)
 }
 
-/// Set the default media player key.
+/// Set the default media player key.
+///
+/// # Errors
+///
+/// Returns an error if the media player is unknown or default cannot be persisted.
+pub fn set_default_media_player(profile_home: &ProfileHome, key: &str) -> Result<()> {
+    validate_media_player_key(key)?;
+    if media_player(profile_home, key)?.is_none() {
+        bail!("Media player '{}' does not exist.", key);
+    }
+    std::fs::write(default_media_player_file(profile_home), format!("{key}\n"))?;
+    debug!(
+        media_player_key = key,
+        default_media_player_file = %default_media_player_file(profile_home).display(),
+        "set default media player"
+    );
+    Ok(())
+}
+
+/// Get the configured default media player key, if any.
+///
+/// # Errors
+///
+/// Returns an error if the default file cannot be read.
+pub fn default_media_player(profile_home: &ProfileHome) -> Result<Option<String>> {
+    ensure_profile_exists(profile_home)?;
+    let path = default_media_player_file(profile_home);
+    debug!(
+        default_media_player_file = %path.display(),
+        "loading default media player"
+    );
+    if !path.exists() {
+        debug!(
+            default_media_player_file = %path.display(),
+            "default media player file does not exist"
+        );
+        return Ok(None);
+    }
+
+    let key = std::fs::read_to_string(path)?.trim().to_owned();
+    if key.is_empty() {
+        return Ok(None);
+    }
+
+    validate_media_player_key(&key)?;
+    debug!(media_player_key = key, "loaded default media player");
+    Ok(Some(key))
+}
+
+/// Append a message to the chat history kept for a known user.
+///
+/// # Errors
+///
+/// Returns an error if the profile does not exist or the history file cannot be written.
+pub fn append_chat_history(
+    profile_home: &ProfileHome,
+    known_user: &str,
+    entry: &ChatHistoryEntry,
+) -> Result<()> {
+    ensure_profile_exists(profile_home)?;
+    std::fs::create_dir_all(chat_history_dir(profile_home))?;
+    let line = format!(
+        "{}\t{}\t{}\t{}\n",
+        entry.timestamp_ms,
+        entry.direction.as_str(),
+        entry.message_id,
+        escape_tsv_field(&entry.body)
+    );
+    let mut file = std::fs::OpenOptions::new()
+        .create(true)
+        .append(true)
+        .open(chat_history_file(profile_home, known_user)?)?;
+    file.write_all(line.as_bytes())?;
+    Ok(())
+}
+
+/// List the chat history kept for a known user, oldest first.
+///
+/// # Errors
+///
+/// Returns an error if the history file cannot be read or parsed.
+pub fn list_chat_history(
+    profile_home: &ProfileHome,
+    known_user: &str,
+) -> Result<Vec<ChatHistoryEntry>> {
+    ensure_profile_exists(profile_home)?;
+    let path = chat_history_file(profile_home, known_user)?;
+    if !path.exists() {
+        return Ok(Vec::new());
+    }
+
+    let mut entries = Vec::new();
+    for line in std::fs::read_to_string(&path)?.lines() {
+        let mut parts = line.splitn(4, '\t');
+        let (Some(timestamp), Some(direction), Some(message_id), Some(body)) =
+            (parts.next(), parts.next(), parts.next(), parts.next())
+        else {
+            continue;
+        };
+        let direction = match direction {
+            "inbound" => MessageDirection::Inbound,
+            "outbound" => MessageDirection::Outbound,
+            _ => continue,
+        };
+        entries.push(ChatHistoryEntry {
+            timestamp_ms: timestamp.parse::<u64>()?,
+            direction,
+            message_id: message_id.to_owned(),
+            body: unescape_tsv_field(body),
+        });
+    }
+    entries.sort_by_key(|entry| entry.timestamp_ms);
+    Ok(entries)
+}
+
+/// List messages queued for known users who were offline, oldest first.
+///
+/// # Errors
+///
+/// Returns an error if the outbox file cannot be read or parsed.
+pub fn list_outbox(profile_home: &ProfileHome) -> Result<Vec<OutboxEntry>> {
+    ensure_profile_exists(profile_home)?;
+    let path = outbox_file(profile_home);
+    if !path.exists() {
+        return Ok(Vec::new());
+    }
+
+    let mut entries = Vec::new();
+    for line in std::fs::read_to_string(&path)?.lines() {
+        let mut parts = line.splitn(5, '\t');
+        let (Some(id), Some(known_user), Some(queued_at_ms), Some(attempts), Some(body)) = (
+            parts.next(),
+            parts.next(),
+            parts.next(),
+            parts.next(),
+            parts.next(),
+        ) else {
+            continue;
+        };
+        entries.push(OutboxEntry {
+            id: id.parse::<u64>()?,
+            known_user: known_user.to_owned(),
+            queued_at_ms: queued_at_ms.parse::<u64>()?,
+            attempts: attempts.parse::<u32>()?,
+            body: unescape_tsv_field(body),
+        });
+    }
+    entries.sort_by_key(|entry| entry.id);
+    Ok(entries)
+}
+
+/// Queue a chat message for a known user until one of their routes is populated.
+///
+/// # Errors
+///
+/// Returns an error if outbox data cannot be loaded or persisted.
+pub fn enqueue_outbox(
+    profile_home: &ProfileHome,
+    known_user: &str,
+    body: &str,
+) -> Result<OutboxEntry> {
+    let mut entries = list_outbox(profile_home)?;
+    let entry = OutboxEntry {
+        id: entries.iter().map(|entry| entry.id).max().unwrap_or(0) + 1,
+        known_user: known_user.to_owned(),
+        queued_at_ms: u64::try_from(chrono::Utc::now().timestamp_millis()).unwrap_or_default(),
+        attempts: 0,
+        body: body.to_owned(),
+    };
+    entries.push(entry.clone());
+    write_outbox(profile_home, &entries)?;
+    Ok(entry)
+}
+
+/// Remove a queued message from the outbox.
+///
+/// # Errors
+///
+/// Returns an error if the entry does not exist or outbox data cannot be persisted.
+pub fn remove_outbox_entry(profile_home: &ProfileHome, id: u64) -> Result<()> {
+    let mut entries = list_outbox(profile_home)?;
+    let prior_len = entries.len();
+    entries.retain(|entry| entry.id != id);
+    if entries.len() == prior_len {
+        bail!("Queued message {} does not exist.", id);
+    }
+    write_outbox(profile_home, &entries)
+}
+
+/// Count a failed delivery attempt against a queued message.
+///
+/// # Errors
+///
+/// Returns an error if the entry does not exist or outbox data cannot be persisted.
+pub fn record_outbox_attempt(profile_home: &ProfileHome, id: u64) -> Result<()> {
+    let mut entries = list_outbox(profile_home)?;
+    let Some(entry) = entries.iter_mut().find(|entry| entry.id == id) else {
+        bail!("Queued message {} does not exist.", id);
+    };
+    entry.attempts = entry.attempts.saturating_add(1);
+    write_outbox(profile_home, &entries)
+}
+
+/// List chat rooms for a profile.
+///
+/// # Errors
+///
+/// Returns an error if room data cannot be read or parsed.
+pub fn list_rooms(profile_home: &ProfileHome) -> Result<Vec<RoomEntry>> {
+    ensure_profile_exists(profile_home)?;
+    let path = rooms_file(profile_home);
+    if !path.exists() {
+        return Ok(Vec::new());
+    }
+
+    let mut rooms = Vec::new();
+    for line in std::fs::read_to_string(&path)?.lines() {
+        let mut parts = line.split('\t');
+        let (Some(name), Some(room_id)) = (parts.next(), parts.next()) else {
+            continue;
+        };
+        rooms.push(RoomEntry {
+            name: name.to_owned(),
+            room_id: room_id.parse()?,
+            members: parts.map(str::to_owned).collect(),
+        });
+    }
+    Ok(rooms)
+}
+
+/// Load a chat room by name.
+///
+/// # Errors
+///
+/// Returns an error if room data cannot be read or parsed.
+pub fn room(profile_home: &ProfileHome, name: &str) -> Result<Option<RoomEntry>> {
+    Ok(list_rooms(profile_home)?
+        .into_iter()
+        .find(|room| room.name == name))
+}
+
+/// Create an empty chat room.
+///
+/// # Errors
+///
+/// Returns an error if the name is invalid, a room with that name or id already
+/// exists, or room data cannot be persisted.
+pub fn create_room(profile_home: &ProfileHome, name: &str, room_id: RoomId) -> Result<RoomEntry> {
+    validate_room_name(name)?;
+    let mut rooms = list_rooms(profile_home)?;
+    if rooms.iter().any(|room| room.name == name) {
+        bail!("Room '{}' already exists.", name);
+    }
+    if let Some(existing) = rooms.iter().find(|room| room.room_id == room_id) {
+        bail!(
+            "Room id {} is already used by room '{}'.",
+            room_id,
+            existing.name
+        );
+    }
+
+    let room = RoomEntry {
+        name: name.to_owned(),
+        room_id,
+        members: Vec::new(),
+    };
+    rooms.push(room.clone());
+    rooms.sort_by(|a, b| a.name.cmp(&b.name));
+    write_rooms(profile_home, &rooms)?;
+    Ok(room)
+}
+
+/// Add a known user to a chat room.
+///
+/// # Errors
+///
+/// Returns an error if the room or known user does not exist, the known user is
+/// already a member, or room data cannot be persisted.
+pub fn add_room_member(profile_home: &ProfileHome, room: &str, known_user: &str) -> Result<()> {
+    if known_user_public_key(profile_home, known_user)?.is_none() {
+        bail!("Known user '{}' does not exist.", known_user);
+    }
+    let mut rooms = list_rooms(profile_home)?;
+    let Some(entry) = rooms.iter_mut().find(|entry| entry.name == room) else {
+        bail!("Room '{}' does not exist.", room);
+    };
+    if entry.members.iter().any(|member| member == known_user) {
+        bail!("{} is already a member of room '{}'.", known_user, room);
+    }
+    entry.members.push(known_user.to_owned());
+    write_rooms(profile_home, &rooms)
+}
+
+/// Remove a known user from a chat room.
+///
+/// # Errors
+///
+/// Returns an error if the room does not exist, the known user is not a member, or
+/// room data cannot be persisted.
+pub fn remove_room_member(profile_home: &ProfileHome, room: &str, known_user: &str) -> Result<()> {
+    let mut rooms = list_rooms(profile_home)?;
+    let Some(entry) = rooms.iter_mut().find(|entry| entry.name == room) else {
+        bail!("Room '{}' does not exist.", room);
+    };
+    let prior_len = entry.members.len();
+    entry.members.retain(|member| member != known_user);
+    if entry.members.len() == prior_len {
+        bail!("{} is not a member of room '{}'.", known_user, room);
+    }
+    write_rooms(profile_home, &rooms)
+}
+
+/// Load the unknown-sender policy for a profile, defaulting to allow-all.
+///
+/// # Errors
+///
+/// Returns an error if the policy file cannot be read or holds an unknown policy.
+pub fn sender_policy(profile_home: &ProfileHome) -> Result<SenderPolicy> {
+    ensure_profile_exists(profile_home)?;
+    let path = sender_policy_file(profile_home);
+    if !path.exists() {
+        return Ok(SenderPolicy::default());
+    }
+    std::fs::read_to_string(path)?.parse()
+}
+
+/// Persist the unknown-sender policy for a profile.
+///
+/// # Errors
+///
+/// Returns an error if the policy cannot be persisted.
+pub fn set_sender_policy(profile_home: &ProfileHome, policy: SenderPolicy) -> Result<()> {
+    ensure_profile_exists(profile_home)?;
+    std::fs::write(
+        sender_policy_file(profile_home),
+        format!("{}\n", policy.as_str()),
+    )?;
+    Ok(())
+}
+
+/// List public keys whose messages are dropped without notice.
+///
+/// # Errors
+///
+/// Returns an error if the blocklist cannot be read or parsed.
+pub fn list_blocked_senders(profile_home: &ProfileHome) -> Result<Vec<PublicKey>> {
+    ensure_profile_exists(profile_home)?;
+    let path = blocked_senders_file(profile_home);
+    if !path.exists() {
+        return Ok(Vec::new());
+    }
+
+    std::fs::read_to_string(&path)?
+        .lines()
+        .filter(|line| !line.trim().is_empty())
+        .map(|line| Ok(line.trim().parse::<PublicKey>()?))
+        .collect()
+}
+
+/// Add a public key to the blocklist.
+///
+/// # Errors
+///
+/// Returns an error if the key is already blocked or the blocklist cannot be persisted.
+pub fn block_sender(profile_home: &ProfileHome, pubkey: &PublicKey) -> Result<()> {
+    let mut blocked = list_blocked_senders(profile_home)?;
+    if blocked.contains(pubkey) {
+        bail!("{} is already blocked.", pubkey);
+    }
+    blocked.push(pubkey.clone());
+    write_blocked_senders(profile_home, &blocked)
+}
+
+/// Remove a public key from the blocklist.
+///
+/// # Errors
+///
+/// Returns an error if the key is not blocked or the blocklist cannot be persisted.
+pub fn unblock_sender(profile_home: &ProfileHome, pubkey: &PublicKey) -> Result<()> {
+    let mut blocked = list_blocked_senders(profile_home)?;
+    let prior_len = blocked.len();
+    blocked.retain(|entry| entry != pubkey);
+    if blocked.len() == prior_len {
+        bail!("{} is not blocked.", pubkey);
+    }
+    write_blocked_senders(profile_home, &blocked)
+}
+
+/// List quarantined messages from unknown senders, oldest first.
+///
+/// # Errors
+///
+/// Returns an error if the quarantine file cannot be read or parsed.
+pub fn list_quarantine(profile_home: &ProfileHome) -> Result<Vec<QuarantineEntry>> {
+    ensure_profile_exists(profile_home)?;
+    let path = quarantine_file(profile_home);
+    if !path.exists() {
+        return Ok(Vec::new());
+    }
+
+    let mut entries = Vec::new();
+    for line in std::fs::read_to_string(&path)?.lines() {
+        let mut parts = line.splitn(5, '\t');
+        let (Some(id), Some(received_at_ms), Some(sender), Some(message_id), Some(body)) = (
+            parts.next(),
+            parts.next(),
+            parts.next(),
+            parts.next(),
+            parts.next(),
+        ) else {
+            continue;
+        };
+        entries.push(QuarantineEntry {
+            id: id.parse::<u64>()?,
+            received_at_ms: received_at_ms.parse::<u64>()?,
+            sender: sender.parse::<PublicKey>()?,
+            message_id: message_id.to_owned(),
+            body: unescape_tsv_field(body),
+        });
+    }
+    entries.sort_by_key(|entry| entry.id);
+    Ok(entries)
+}
+
+/// Hold a message from an unknown sender for review.
+///
+/// Returns `None` without storing anything when the sender already has
+/// [`MAX_QUARANTINE_PER_SENDER`] messages held or the quarantine holds
+/// [`MAX_QUARANTINE_TOTAL`].
 ///
 /// # Errors
 ///
-/// Returns an error if the media player is unknown or default cannot be persisted.
-pub fn set_default_media_player(profile_home: &ProfileHome, key: &str) -> Result<()> {
-    validate_media_player_key(key)?;
-    if media_player(profile_home, key)?.is_none() {
-        bail!("Media player '{}' does not exist.", key);
+/// Returns an error if quarantine data cannot be loaded or persisted.
+pub fn quarantine_message(
+    profile_home: &ProfileHome,
+    sender: &PublicKey,
+    received_at_ms: u64,
+    message_id: &str,
+    body: &str,
+) -> Result<Option<QuarantineEntry>> {
+    let entries = list_quarantine(profile_home)?;
+    let from_sender = entries
+        .iter()
+        .filter(|entry| entry.sender == *sender)
+        .count();
+    if entries.len() >= MAX_QUARANTINE_TOTAL || from_sender >= MAX_QUARANTINE_PER_SENDER {
+        return Ok(None);
     }
-    std::fs::write(default_media_player_file(profile_home), format!("{key}\n"))?;
-    debug!(
-        media_player_key = key,
-        default_media_player_file = %default_media_player_file(profile_home).display(),
-        "set default media player"
-    );
+    let entry = QuarantineEntry {
+        id: entries.iter().map(|entry| entry.id).max().unwrap_or(0) + 1,
+        received_at_ms,
+        sender: sender.clone(),
+        message_id: message_id.to_owned(),
+        body: body.to_owned(),
+    };
+    let mut file = std::fs::OpenOptions::new()
+        .create(true)
+        .append(true)
+        .open(quarantine_file(profile_home))?;
+    let separator = if entries.is_empty() { "" } else { "\n" };
+    write!(file, "{separator}{}", quarantine_line(&entry))?;
+    Ok(Some(entry))
+}
+
+/// Remove a quarantined message and return it.
+///
+/// # Errors
+///
+/// Returns an error if the entry does not exist or quarantine data cannot be persisted.
+pub fn take_quarantined_message(profile_home: &ProfileHome, id: u64) -> Result<QuarantineEntry> {
+    let mut entries = list_quarantine(profile_home)?;
+    let Some(index) = entries.iter().position(|entry| entry.id == id) else {
+        bail!("Quarantined message {} does not exist.", id);
+    };
+    let entry = entries.remove(index);
+    write_quarantine(profile_home, &entries)?;
+    Ok(entry)
+}
+
+/// The first chunk not yet confirmed for an outgoing file transfer, or 0 if none was
+/// started.
+///
+/// # Errors
+///
+/// Returns an error if the transfer progress file cannot be read or parsed.
+pub fn file_transfer_progress(
+    profile_home: &ProfileHome,
+    known_user: &str,
+    content_hash: &str,
+) -> Result<u32> {
+    Ok(list_file_transfers(profile_home)?
+        .into_iter()
+        .find(|(user, hash, _)| user == known_user && hash == content_hash)
+        .map_or(0, |(_, _, next_chunk)| next_chunk))
+}
+
+/// Record how far an outgoing file transfer got so it can be resumed.
+///
+/// # Errors
+///
+/// Returns an error if the transfer progress file cannot be read or persisted.
+pub fn set_file_transfer_progress(
+    profile_home: &ProfileHome,
+    known_user: &str,
+    content_hash: &str,
+    next_chunk: u32,
+) -> Result<()> {
+    let mut transfers = list_file_transfers(profile_home)?;
+    transfers.retain(|(user, hash, _)| !(user == known_user && hash == content_hash));
+    transfers.push((known_user.to_owned(), content_hash.to_owned(), next_chunk));
+    write_file_transfers(profile_home, &transfers)
+}
+
+/// Forget a finished outgoing file transfer.
+///
+/// # Errors
+///
+/// Returns an error if the transfer progress file cannot be read or persisted.
+pub fn clear_file_transfer_progress(
+    profile_home: &ProfileHome,
+    known_user: &str,
+    content_hash: &str,
+) -> Result<()> {
+    let mut transfers = list_file_transfers(profile_home)?;
+    transfers.retain(|(user, hash, _)| !(user == known_user && hash == content_hash));
+    write_file_transfers(profile_home, &transfers)
+}
+
+/// When each route record key was last seen publishing a route, in ms since the epoch.
+///
+/// # Errors
+///
+/// Returns an error if the presence file cannot be read or parsed.
+pub fn route_last_seen(profile_home: &ProfileHome) -> Result<BTreeMap<String, u64>> {
+    ensure_profile_exists(profile_home)?;
+    let path = presence_file(profile_home);
+    if !path.exists() {
+        return Ok(BTreeMap::new());
+    }
+
+    let mut last_seen = BTreeMap::new();
+    for line in std::fs::read_to_string(&path)?.lines() {
+        let Some((record_key, seen_at_ms)) = line.split_once('\t') else {
+            continue;
+        };
+        let _ = last_seen.insert(record_key.to_owned(), seen_at_ms.parse::<u64>()?);
+    }
+    Ok(last_seen)
+}
+
+/// Remember that a route record key was seen publishing a route at `seen_at_ms`.
+///
+/// # Errors
+///
+/// Returns an error if the presence file cannot be read or persisted.
+pub fn record_route_seen(
+    profile_home: &ProfileHome,
+    record_key: &RecordKey,
+    seen_at_ms: u64,
+) -> Result<()> {
+    record_routes_seen(profile_home, std::slice::from_ref(record_key), seen_at_ms)
+}
+
+/// Remember that several route record keys were seen publishing a route at
+/// `seen_at_ms`, rewriting the presence file once.
+///
+/// # Errors
+///
+/// Returns an error if the presence file cannot be read or persisted.
+pub fn record_routes_seen(
+    profile_home: &ProfileHome,
+    record_keys: &[RecordKey],
+    seen_at_ms: u64,
+) -> Result<()> {
+    if record_keys.is_empty() {
+        return Ok(());
+    }
+    let mut last_seen = route_last_seen(profile_home)?;
+    for record_key in record_keys {
+        let _ = last_seen.insert(record_key.to_string(), seen_at_ms);
+    }
+    let lines = last_seen
+        .iter()
+        .map(|(record_key, seen_at_ms)| format!("{record_key}\t{seen_at_ms}"))
+        .collect::<Vec<_>>();
+    std::fs::write(presence_file(profile_home), lines.join("\n"))?;
     Ok(())
 }
 
-/// Get the configured default media player key, if any.
+/// The sequence number of the last chat message sent to `recipient`, or 0 if none was.
 ///
 /// # Errors
 ///
-/// Returns an error if the default file cannot be read.
-pub fn default_media_player(profile_home: &ProfileHome) -> Result<Option<String>> {
+/// Returns an error if the sequence file cannot be read or parsed.
+pub fn last_sent_sequence(profile_home: &ProfileHome, recipient: &PublicKey) -> Result<u64> {
     ensure_profile_exists(profile_home)?;
-    let path = default_media_player_file(profile_home);
-    debug!(
-        default_media_player_file = %path.display(),
-        "loading default media player"
-    );
+    Ok(read_sequences(&sent_sequences_file(profile_home))?
+        .get(&recipient.to_string())
+        .copied()
+        .unwrap_or_default())
+}
+
+/// Remember the sequence number of the last chat message sent to `recipient`.
+///
+/// # Errors
+///
+/// Returns an error if the sequence file cannot be read or persisted.
+pub fn set_last_sent_sequence(
+    profile_home: &ProfileHome,
+    recipient: &PublicKey,
+    sequence: u64,
+) -> Result<()> {
+    ensure_profile_exists(profile_home)?;
+    let path = sent_sequences_file(profile_home);
+    let mut sequences = read_sequences(&path)?;
+    let _ = sequences.insert(recipient.to_string(), sequence);
+    write_sequences(&path, &sequences)
+}
+
+/// The highest chat sequence number received from each sender, keyed by public key.
+///
+/// # Errors
+///
+/// Returns an error if the sequence file cannot be read or parsed.
+pub fn received_sequences(profile_home: &ProfileHome) -> Result<BTreeMap<String, u64>> {
+    ensure_profile_exists(profile_home)?;
+    read_sequences(&received_sequences_file(profile_home))
+}
+
+/// Remember the highest chat sequence numbers received from a batch of senders, keyed
+/// by public key text.
+///
+/// A number lower than the one already stored for a sender is ignored.
+///
+/// # Errors
+///
+/// Returns an error if the sequence file cannot be read or persisted.
+pub fn set_received_sequences(
+    profile_home: &ProfileHome,
+    received: &BTreeMap<String, u64>,
+) -> Result<()> {
+    if received.is_empty() {
+        return Ok(());
+    }
+    let path = received_sequences_file(profile_home);
+    let mut sequences = received_sequences(profile_home)?;
+    for (sender, sequence) in received {
+        let stored = sequences.entry(sender.clone()).or_default();
+        *stored = (*stored).max(*sequence);
+    }
+    write_sequences(&path, &sequences)
+}
+
+fn read_sequences(path: &Path) -> Result<BTreeMap<String, u64>> {
     if !path.exists() {
-        debug!(
-            default_media_player_file = %path.display(),
-            "default media player file does not exist"
-        );
-        return Ok(None);
+        return Ok(BTreeMap::new());
     }
 
-    let key = std::fs::read_to_string(path)?.trim().to_owned();
-    if key.is_empty() {
-        return Ok(None);
+    let mut sequences = BTreeMap::new();
+    for line in std::fs::read_to_string(path)?.lines() {
+        let Some((public_key, sequence)) = line.split_once('\t') else {
+            continue;
+        };
+        let _ = sequences.insert(public_key.to_owned(), sequence.parse::<u64>()?);
     }
+    Ok(sequences)
+}
 
-    validate_media_player_key(&key)?;
-    debug!(media_player_key = key, "loaded default media player");
-    Ok(Some(key))
+fn write_sequences(path: &Path, sequences: &BTreeMap<String, u64>) -> Result<()> {
+    let lines = sequences
+        .iter()
+        .map(|(public_key, sequence)| format!("{public_key}\t{sequence}"))
+        .collect::<Vec<_>>();
+    std::fs::write(path, lines.join("\n"))?;
+    Ok(())
+}
+
+fn list_file_transfers(profile_home: &ProfileHome) -> Result<Vec<(String, String, u32)>> {
+    ensure_profile_exists(profile_home)?;
+    let path = file_transfers_file(profile_home);
+    if !path.exists() {
+        return Ok(Vec::new());
+    }
+
+    let mut transfers = Vec::new();
+    for line in std::fs::read_to_string(&path)?.lines() {
+        let mut parts = line.splitn(3, '\t');
+        let (Some(known_user), Some(content_hash), Some(next_chunk)) =
+            (parts.next(), parts.next(), parts.next())
+        else {
+            continue;
+        };
+        transfers.push((
+            known_user.to_owned(),
+            content_hash.to_owned(),
+            next_chunk.parse::<u32>()?,
+        ));
+    }
+    Ok(transfers)
+}
+
+fn write_file_transfers(
+    profile_home: &ProfileHome,
+    transfers: &[(String, String, u32)],
+) -> Result<()> {
+    let lines = transfers
+        .iter()
+        .map(|(known_user, content_hash, next_chunk)| {
+            format!("{known_user}\t{content_hash}\t{next_chunk}")
+        })
+        .collect::<Vec<_>>();
+    std::fs::write(file_transfers_file(profile_home), lines.join("\n"))?;
+    Ok(())
+}
+
+fn write_blocked_senders(profile_home: &ProfileHome, blocked: &[PublicKey]) -> Result<()> {
+    let lines = blocked.iter().map(ToString::to_string).collect::<Vec<_>>();
+    std::fs::write(blocked_senders_file(profile_home), lines.join("\n"))?;
+    Ok(())
+}
+
+fn write_quarantine(profile_home: &ProfileHome, entries: &[QuarantineEntry]) -> Result<()> {
+    let lines = entries.iter().map(quarantine_line).collect::<Vec<_>>();
+    std::fs::write(quarantine_file(profile_home), lines.join("\n"))?;
+    Ok(())
+}
+
+fn quarantine_line(entry: &QuarantineEntry) -> String {
+    format!(
+        "{}\t{}\t{}\t{}\t{}",
+        entry.id,
+        entry.received_at_ms,
+        entry.sender,
+        entry.message_id,
+        escape_tsv_field(&entry.body)
+    )
+}
+
+fn write_rooms(profile_home: &ProfileHome, rooms: &[RoomEntry]) -> Result<()> {
+    let lines = rooms
+        .iter()
+        .map(|room| {
+            let mut fields = vec![room.name.clone(), room.room_id.to_string()];
+            fields.extend(room.members.iter().cloned());
+            fields.join("\t")
+        })
+        .collect::<Vec<_>>();
+    std::fs::write(rooms_file(profile_home), lines.join("\n"))?;
+    Ok(())
+}
+
+fn write_outbox(profile_home: &ProfileHome, entries: &[OutboxEntry]) -> Result<()> {
+    let lines = entries
+        .iter()
+        .map(|entry| {
+            format!(
+                "{}\t{}\t{}\t{}\t{}",
+                entry.id,
+                entry.known_user,
+                entry.queued_at_ms,
+                entry.attempts,
+                escape_tsv_field(&entry.body)
+            )
+        })
+        .collect::<Vec<_>>();
+    std::fs::write(outbox_file(profile_home), lines.join("\n"))?;
+    Ok(())
 }
 
 fn write_local_route_identities(
@@ -732,7 +2163,46 @@ fn write_local_route_identities(
 ) -> Result<()> {
     let lines = routes
         .iter()
-        .map(|route| format!("{}\t{}\t{}", route.name, route.keypair, route.record_key))
+        .map(|route| {
+            let mut fields = vec![
+                route.name.clone(),
+                route.keypair.to_string(),
+                route.record_key.to_string(),
+            ];
+            if route.mailbox_writer.is_some() || !route.safety.is_default() {
+                fields.push(
+                    route
+                        .mailbox_writer
+                        .as_ref()
+                        .map(ToString::to_string)
+                        .unwrap_or_default(),
+                );
+            }
+            if !route.safety.is_default() {
+                fields.push(
+                    route
+                        .safety
+                        .hop_count
+                        .map(|hops| hops.to_string())
+                        .unwrap_or_default(),
+                );
+                fields.push(
+                    route
+                        .safety
+                        .stability
+                        .map(|stability| stability.as_str().to_owned())
+                        .unwrap_or_default(),
+                );
+                fields.push(
+                    route
+                        .safety
+                        .sequencing
+                        .map(|sequencing| sequencing.as_str().to_owned())
+                        .unwrap_or_default(),
+                );
+            }
+            fields.join("\t")
+        })
         .collect::<Vec<_>>();
     std::fs::write(route_identities_file(profile_home), lines.join("\n"))?;
     Ok(())
@@ -804,6 +2274,39 @@ fn write_known_users_file(path: &Path, known_users: &[KnownUserEntry]) -> Result
     Ok(())
 }
 
+fn escape_tsv_field(value: &str) -> String {
+    let mut out = String::with_capacity(value.len());
+    for character in value.chars() {
+        match character {
+            '\\' => out.push_str("\\\\"),
+            '\t' => out.push_str("\\t"),
+            '\n' => out.push_str("\\n"),
+            '\r' => out.push_str("\\r"),
+            other => out.push(other),
+        }
+    }
+    out
+}
+
+fn unescape_tsv_field(value: &str) -> String {
+    let mut out = String::with_capacity(value.len());
+    let mut characters = value.chars();
+    while let Some(character) = characters.next() {
+        if character != '\\' {
+            out.push(character);
+            continue;
+        }
+        match characters.next() {
+            Some('t') => out.push('\t'),
+            Some('n') => out.push('\n'),
+            Some('r') => out.push('\r'),
+            Some(other) => out.push(other),
+            None => out.push('\\'),
+        }
+    }
+    out
+}
+
 fn profiles_root(app_home: &AppHome) -> PathBuf {
     app_home.file_path(PROFILES_DIR)
 }
@@ -836,6 +2339,65 @@ fn default_media_player_file(profile_home: &ProfileHome) -> PathBuf {
     profile_home.profile_dir().join(DEFAULT_MEDIA_PLAYER_FILE)
 }
 
+fn outbox_file(profile_home: &ProfileHome) -> PathBuf {
+    profile_home.profile_dir().join(OUTBOX_FILE)
+}
+
+fn rooms_file(profile_home: &ProfileHome) -> PathBuf {
+    profile_home.profile_dir().join(ROOMS_FILE)
+}
+
+fn sender_policy_file(profile_home: &ProfileHome) -> PathBuf {
+    profile_home.profile_dir().join(SENDER_POLICY_FILE)
+}
+
+fn blocked_senders_file(profile_home: &ProfileHome) -> PathBuf {
+    profile_home.profile_dir().join(BLOCKED_SENDERS_FILE)
+}
+
+fn quarantine_file(profile_home: &ProfileHome) -> PathBuf {
+    profile_home.profile_dir().join(QUARANTINE_FILE)
+}
+
+fn route_limits_file(profile_home: &ProfileHome) -> PathBuf {
+    profile_home.profile_dir().join(ROUTE_LIMITS_FILE)
+}
+
+fn route_rotation_file(profile_home: &ProfileHome) -> PathBuf {
+    profile_home.profile_dir().join(ROUTE_ROTATION_FILE)
+}
+
+fn hooks_file(profile_home: &ProfileHome) -> PathBuf {
+    profile_home.profile_dir().join(HOOKS_FILE)
+}
+
+fn sent_sequences_file(profile_home: &ProfileHome) -> PathBuf {
+    profile_home.profile_dir().join(SENT_SEQUENCES_FILE)
+}
+
+fn received_sequences_file(profile_home: &ProfileHome) -> PathBuf {
+    profile_home.profile_dir().join(RECEIVED_SEQUENCES_FILE)
+}
+
+fn presence_file(profile_home: &ProfileHome) -> PathBuf {
+    profile_home.profile_dir().join(PRESENCE_FILE)
+}
+
+fn file_transfers_file(profile_home: &ProfileHome) -> PathBuf {
+    profile_home.profile_dir().join(FILE_TRANSFERS_FILE)
+}
+
+fn chat_history_dir(profile_home: &ProfileHome) -> PathBuf {
+    profile_home.profile_dir().join(CHAT_HISTORY_DIR)
+}
+
+/// History file for a known user; names that could leave the history directory are
+/// rejected even if they predate [`validate_known_user_name`].
+fn chat_history_file(profile_home: &ProfileHome, known_user: &str) -> Result<PathBuf> {
+    validate_known_user_name(known_user)?;
+    Ok(chat_history_dir(profile_home).join(format!("{known_user}.tsv")))
+}
+
 fn write_media_players_file(
     profile_home: &ProfileHome,
     players: &[MediaPlayerEntry],
@@ -885,6 +2447,42 @@ fn validate_route_name(name: &str) -> Result<()> {
     Ok(())
 }
 
+fn validate_known_user_name(name: &str) -> Result<()> {
+    let trimmed = name.trim();
+    if trimmed.is_empty() {
+        bail!("Known user name cannot be empty.");
+    }
+    if trimmed.contains(['/', '\\', ':', '\t', '\n']) {
+        bail!("Known user name cannot contain path separators, tabs, or newlines.");
+    }
+    if trimmed.starts_with('.') {
+        bail!("Known user name cannot start with '.'.");
+    }
+    Ok(())
+}
+
+fn validate_room_name(name: &str) -> Result<()> {
+    let trimmed = name.trim();
+    if trimmed.is_empty() {
+        bail!("Room name cannot be empty.");
+    }
+    if trimmed.contains(['\t', '\n']) {
+        bail!("Room name cannot contain tabs or newlines.");
+    }
+    Ok(())
+}
+
+fn validate_hook_name(name: &str) -> Result<()> {
+    let trimmed = name.trim();
+    if trimmed.is_empty() {
+        bail!("Hook name cannot be empty.");
+    }
+    if trimmed.contains(['\t', '\n']) {
+        bail!("Hook name cannot contain tabs or newlines.");
+    }
+    Ok(())
+}
+
 fn validate_media_player_key(key: &str) -> Result<()> {
     let trimmed = key.trim();
     if trimmed.is_empty() {
@@ -895,3 +2493,112 @@ fn validate_media_player_key(key: &str) -> Result<()> {
     }
     Ok(())
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn temp_profile() -> Result<(tempfile::TempDir, ProfileHome)> {
+        let dir = tempfile::tempdir()?;
+        let app_home = AppHome(dir.path().to_path_buf());
+        create_profile(&app_home, "alice")?;
+        let profile_home = profile_home(&app_home, "alice")?;
+        Ok((dir, profile_home))
+    }
+
+    fn sample_key() -> PublicKey {
+        format!("VLD0:{}", "A".repeat(43))
+            .parse()
+            .expect("sample key should parse")
+    }
+
+    #[test]
+    fn known_user_names_cannot_escape_history_dir() -> Result<()> {
+        let (_dir, profile_home) = temp_profile()?;
+        for name in [
+            "../bob", "bob/x", "bob\\x", "..", ".", ".hidden", "c:bob", "",
+        ] {
+            add_known_user(&profile_home, name, sample_key())
+                .expect_err("unsafe known-user name should be rejected");
+            chat_history_file(&profile_home, name)
+                .expect_err("unsafe known-user name should not map to a file");
+        }
+        add_known_user(&profile_home, "bob.smith", sample_key())?;
+        rename_known_user(&profile_home, "bob.smith", "../bob")
+            .expect_err("rename to an unsafe name should be rejected");
+        Ok(())
+    }
+
+    #[test]
+    fn capability_codes_must_be_four_letters() -> Result<()> {
+        let (_dir, profile_home) = temp_profile()?;
+        for code in ["TUN", "TUNNEL", "TU-L", ""] {
+            let config = ProfileVeilidConfig {
+                disable_capabilities: vec![code.to_owned()],
+                ..ProfileVeilidConfig::default()
+            };
+            set_veilid_config(&profile_home, &config)
+                .expect_err("malformed capability code should be rejected");
+        }
+        assert!(!veilid_config_file(&profile_home).exists());
+
+        let config = ProfileVeilidConfig {
+            disable_capabilities: vec!["TUNL".to_owned(), "RLAY".to_owned()],
+            ..ProfileVeilidConfig::default()
+        };
+        set_veilid_config(&profile_home, &config)?;
+        assert_eq!(veilid_config(&profile_home)?, config);
+        Ok(())
+    }
+
+    #[test]
+    fn quarantine_is_capped_per_sender() -> Result<()> {
+        let (_dir, profile_home) = temp_profile()?;
+        let other: PublicKey = format!("VLD0:{}A", "B".repeat(42)).parse()?;
+        for index in 0..MAX_QUARANTINE_PER_SENDER {
+            let entry =
+                quarantine_message(&profile_home, &sample_key(), 1, &index.to_string(), "hi")?;
+            assert_eq!(entry.map(|entry| entry.id), Some(u64::try_from(index)? + 1));
+        }
+        assert_eq!(
+            quarantine_message(&profile_home, &sample_key(), 1, "over", "hi")?,
+            None
+        );
+        assert!(quarantine_message(&profile_home, &other, 1, "other", "a\tb\nc")?.is_some());
+
+        let entries = list_quarantine(&profile_home)?;
+        assert_eq!(entries.len(), MAX_QUARANTINE_PER_SENDER + 1);
+        assert_eq!(
+            entries.last().map(|entry| entry.body.as_str()),
+            Some("a\tb\nc")
+        );
+        Ok(())
+    }
+
+    #[test]
+    fn removing_known_user_clears_history_and_outbox() -> Result<()> {
+        let (_dir, profile_home) = temp_profile()?;
+        add_known_user(&profile_home, "bob", sample_key())?;
+        add_known_user(&profile_home, "carol", sample_key())?;
+        append_chat_history(
+            &profile_home,
+            "bob",
+            &ChatHistoryEntry {
+                timestamp_ms: 1,
+                direction: MessageDirection::Outbound,
+                message_id: "id".to_owned(),
+                body: "hi".to_owned(),
+            },
+        )?;
+        enqueue_outbox(&profile_home, "bob", "later")?;
+        enqueue_outbox(&profile_home, "carol", "later")?;
+
+        remove_known_user(&profile_home, "bob")?;
+
+        assert!(!chat_history_file(&profile_home, "bob")?.exists());
+        let outbox = list_outbox(&profile_home)?;
+        assert_eq!(outbox.len(), 1);
+        assert_eq!(outbox[0].known_user, "carol");
+        Ok(())
+    }
+}
@@ -0,0 +1,55 @@
+use crate::cli::InvokeContext;
+use crate::cli::ToArgs;
+use crate::cli::chat::history::ChatHistoryArgs;
+use crate::cli::chat::with::ChatWithArgs;
+use crate::cli::response::CliResponse;
+use arbitrary::Arbitrary;
+use eyre::Result;
+use facet::Facet;
+use figue as args;
+use std::ffi::OsString;
+
+#[derive(Facet, Arbitrary, Debug, PartialEq)]
+pub struct ChatArgs {
+    #[facet(args::subcommand)]
+    pub command: ChatCommand,
+}
+
+#[derive(Facet, Arbitrary, Debug, PartialEq)]
+#[repr(u8)]
+pub enum ChatCommand {
+    History(ChatHistoryArgs),
+    With(ChatWithArgs),
+}
+
+impl ChatArgs {
+    /// # Errors
+    ///
+    /// Returns an error if the selected chat subcommand fails.
+    pub async fn invoke(self, context: &InvokeContext) -> Result<CliResponse> {
+        Ok(match self.command {
+            ChatCommand::History(args) => args.invoke(context).await?.into(),
+            ChatCommand::With(args) => {
+                args.invoke(context).await?;
+                CliResponse::empty()
+            }
+        })
+    }
+}
+
+impl ToArgs for ChatArgs {
+    fn to_args(&self) -> Vec<OsString> {
+        let mut args = Vec::new();
+        match &self.command {
+            ChatCommand::History(history_args) => {
+                args.push("history".into());
+                args.extend(history_args.to_args());
+            }
+            ChatCommand::With(with_args) => {
+                args.push("with".into());
+                args.extend(with_args.to_args());
+            }
+        }
+        args
+    }
+}
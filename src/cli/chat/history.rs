@@ -0,0 +1,137 @@
+use crate::cli::InvokeContext;
+use crate::cli::ToArgs;
+use crate::cli::app_state;
+use crate::cli::app_state::MessageDirection;
+use arbitrary::Arbitrary;
+use chrono::DateTime;
+use chrono::Utc;
+use eyre::Result;
+use eyre::bail;
+use facet::Facet;
+use figue as args;
+use std::fmt;
+
+#[derive(Facet, Arbitrary, Debug, PartialEq)]
+pub struct ChatHistoryArgs {
+    #[facet(args::positional)]
+    pub known_user: String,
+
+    /// Only show messages newer than this: a duration such as `2h` or an RFC 3339 timestamp.
+    #[facet(args::named)]
+    pub since: Option<String>,
+
+    /// Only show the most recent N messages.
+    #[facet(args::named)]
+    pub limit: Option<usize>,
+}
+
+#[derive(Clone, Debug, PartialEq, Eq, Facet)]
+pub struct ChatHistoryItem {
+    timestamp: String,
+    direction: String,
+    message_id: String,
+    body: String,
+}
+
+#[derive(Clone, Debug, PartialEq, Eq, Facet)]
+pub struct ChatHistoryResponse {
+    known_user: String,
+    messages: Vec<ChatHistoryItem>,
+}
+
+impl fmt::Display for ChatHistoryResponse {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        if self.messages.is_empty() {
+            return write!(f, "No chat history with {}.", self.known_user);
+        }
+
+        for (index, message) in self.messages.iter().enumerate() {
+            if index > 0 {
+                writeln!(f)?;
+            }
+            let speaker = if message.direction == MessageDirection::Outbound.as_str() {
+                "you"
+            } else {
+                self.known_user.as_str()
+            };
+            write!(f, "[{}] {}> {}", message.timestamp, speaker, message.body)?;
+        }
+        Ok(())
+    }
+}
+
+impl ChatHistoryArgs {
+    #[expect(
+        clippy::unused_async,
+        reason = "command handlers use async invoke signature consistently"
+    )]
+    pub async fn invoke(self, context: &InvokeContext) -> Result<ChatHistoryResponse> {
+        let profile_home = context.profile_home();
+        if app_state::known_user_public_key(profile_home, &self.known_user)?.is_none() {
+            bail!("Known user '{}' does not exist.", self.known_user);
+        }
+
+        let since_ms = self.since.as_deref().map(parse_since).transpose()?;
+        let mut entries = app_state::list_chat_history(profile_home, &self.known_user)?;
+        if let Some(since_ms) = since_ms {
+            entries.retain(|entry| entry.timestamp_ms >= since_ms);
+        }
+        if let Some(limit) = self.limit {
+            entries = entries.split_off(entries.len().saturating_sub(limit));
+        }
+
+        Ok(ChatHistoryResponse {
+            known_user: self.known_user,
+            messages: entries
+                .into_iter()
+                .map(|entry| ChatHistoryItem {
+                    timestamp: format_timestamp_ms(entry.timestamp_ms),
+                    direction: entry.direction.as_str().to_owned(),
+                    message_id: entry.message_id,
+                    body: entry.body,
+                })
+                .collect(),
+        })
+    }
+}
+
+/// Resolve `--since` to a unix timestamp in milliseconds.
+fn parse_since(value: &str) -> Result<u64> {
+    if let Ok(duration) = humantime::parse_duration(value) {
+        let now = u64::try_from(Utc::now().timestamp_millis()).unwrap_or_default();
+        let ago = u64::try_from(duration.as_millis()).unwrap_or(u64::MAX);
+        return Ok(now.saturating_sub(ago));
+    }
+    if let Ok(timestamp) = DateTime::parse_from_rfc3339(value) {
+        return Ok(u64::try_from(timestamp.timestamp_millis()).unwrap_or_default());
+    }
+    bail!(
+        "Invalid --since value '{}'. Use a duration like 2h or an RFC 3339 timestamp.",
+        value
+    )
+}
+
+pub(crate) fn format_timestamp_ms(timestamp_ms: u64) -> String {
+    i64::try_from(timestamp_ms)
+        .ok()
+        .and_then(DateTime::<Utc>::from_timestamp_millis)
+        .map_or_else(
+            || timestamp_ms.to_string(),
+            |timestamp| timestamp.to_rfc3339(),
+        )
+}
+
+impl ToArgs for ChatHistoryArgs {
+    fn to_args(&self) -> Vec<std::ffi::OsString> {
+        let mut args: Vec<std::ffi::OsString> = vec![self.known_user.clone().into()];
+        if let Some(since) = &self.since {
+            args.push("--since".into());
+            args.push(since.clone().into());
+        }
+        if let Some(limit) = self.limit {
+            args.push("--limit".into());
+            args.push(limit.to_string().into());
+        }
+        args
+    }
+}
@@ -0,0 +1,5 @@
+mod chat_cli;
+pub(crate) mod history;
+pub(crate) mod with;
+
+pub use chat_cli::*;
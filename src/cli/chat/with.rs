@@ -0,0 +1,280 @@
+use crate::cli::Cli;
+use crate::cli::InvokeContext;
+use crate::cli::ToArgs;
+use crate::cli::app_state;
+use crate::cli::app_state::ProfileHome;
+use crate::cli::envelope::Extensions;
+use crate::cli::events::EventOutput;
+use crate::cli::key::KeyArgs;
+use crate::cli::key::KeyCommand;
+use crate::cli::key::key_gen::KeyGenArgs;
+use crate::cli::route::RouteArgs;
+use crate::cli::route::RouteCommand;
+use crate::cli::route::add::RouteAddArgs;
+use crate::cli::route::listen::InboundHandler;
+use crate::cli::route::listen::InboundMessage;
+use crate::cli::route::listen::PublishedRoute;
+use crate::cli::route::listen::load_route_identity;
+use crate::cli::route::listen::route_update_callback;
+use crate::cli::route::listen::wait_for_public_internet_ready;
+use crate::cli::safety;
+use crate::cli::send::chat::ChatSession;
+use crate::cli::send::prompt::ChatPrompt;
+use crate::cli::send::prompt::PromptAction;
+use crate::cli::veilid_runtime::shutdown_api;
+use crate::cli::veilid_runtime::start_api_for_profile;
+use arbitrary::Arbitrary;
+use eyre::Result;
+use eyre::bail;
+use facet::Facet;
+use figue as args;
+use std::collections::HashSet;
+use std::io::BufRead;
+use std::io::Write;
+use std::sync::Arc;
+use std::sync::Mutex;
+use std::sync::atomic::AtomicBool;
+use std::time::Duration;
+use tokio::sync::mpsc;
+use veilid_core::RouteId;
+
+#[derive(Facet, Arbitrary, Debug, PartialEq)]
+pub struct ChatWithArgs {
+    #[facet(args::positional)]
+    pub known_user: String,
+
+    /// Local route to publish while chatting; may be omitted when you have exactly one.
+    #[facet(args::named)]
+    pub route: Option<String>,
+
+    #[facet(args::named)]
+    pub retry: Option<usize>,
+
+    /// Use `app_call` and wait for a delivery receipt for each message.
+    #[facet(args::named, default)]
+    pub ack: bool,
+
+    /// Save reply routes attached to incoming messages instead of only suggesting it.
+    #[facet(args::named, default)]
+    pub save_reply_routes: bool,
+}
+
+impl ChatWithArgs {
+    /// Publish our route and send to the known user's routes from one Veilid instance,
+    /// printing both directions as a single transcript.
+    ///
+    /// Sending and receiving run concurrently, so inbound messages are handled and
+    /// acknowledged while a send is still waiting for its own receipt.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the key, known user, or route identity is missing, or
+    /// Veilid cannot be started or the route cannot be published.
+    #[expect(
+        clippy::too_many_lines,
+        reason = "chat session combines listener setup, sender setup, and the interleaved loop"
+    )]
+    pub async fn invoke(self, context: &InvokeContext) -> Result<()> {
+        let retry_attempts = self.retry.unwrap_or(1);
+        if retry_attempts == 0 {
+            bail!("--retry must be greater than 0.");
+        }
+
+        let profile_home = context.profile_home();
+        let my_keypair = app_state::load_keypair(profile_home)?.ok_or_else(|| {
+            eyre::eyre!(
+                "You have no key. Run '{}' first.",
+                Cli::display_invocation(&crate::cli::Command::Key(KeyArgs {
+                    command: KeyCommand::Gen(KeyGenArgs),
+                }))
+            )
+        })?;
+        let Some(known_user_key) =
+            app_state::known_user_public_key(profile_home, &self.known_user)?
+        else {
+            bail!("Known user '{}' does not exist.", self.known_user);
+        };
+        let keys = app_state::route_keys_for_known_user(profile_home, &self.known_user)?;
+        if keys.is_empty() {
+            bail!("No route record keys configured for {}.", self.known_user);
+        }
+        let identity = load_route_identity(
+            profile_home,
+            &select_route_name(profile_home, self.route.as_deref())?,
+        )?;
+
+        let public_internet_ready = Arc::new(AtomicBool::new(false));
+        let dead_routes = Arc::new(Mutex::new(HashSet::<RouteId>::new()));
+        let (inbound_tx, mut inbound_rx) = mpsc::unbounded_channel::<InboundMessage>();
+        let callback = route_update_callback(
+            Arc::clone(&public_internet_ready),
+            inbound_tx,
+            Arc::clone(&dead_routes),
+        );
+
+        let api = start_api_for_profile(profile_home, true, callback).await?;
+        if let Err(error) =
+            wait_for_public_internet_ready(&api, &public_internet_ready, EventOutput::Text).await
+        {
+            shutdown_api(api).await;
+            return Err(error);
+        }
+
+        let mut inbound_handler = InboundHandler::load(&api, profile_home, &my_keypair)?;
+        inbound_handler.save_reply_routes = self.save_reply_routes;
+        inbound_handler.route = Some(identity.name.clone());
+        inbound_handler.limit_route(
+            &identity.name,
+            app_state::route_rate_limits(profile_home, &identity.name)?,
+        );
+        let router = safety::routing_context(&api, identity.safety)?;
+        let rotation = app_state::route_rotation_policy(profile_home, &identity.name)?;
+        let mut published = PublishedRoute::publish(&api, identity, EventOutput::Text).await?;
+        published.rotation = rotation;
+        println!(
+            "Chatting with {} on route '{}'. Type /help for commands or /quit to stop.",
+            self.known_user, published.identity.name
+        );
+        for inbound in published.drain_mailbox().await {
+            inbound_handler.handle(inbound).await;
+        }
+
+        let mut session = ChatSession {
+            profile_home,
+            known_user: self.known_user.clone(),
+            api: &api,
+            router: &router,
+            keypair: &my_keypair,
+            recipient: known_user_key,
+            keys,
+            retry_attempts,
+            acknowledge: self.ack,
+            extensions: Extensions {
+                reply_route: Some(published.identity.record_key.clone()),
+                ..Extensions::default()
+            },
+            cached_route_id: None,
+            output: EventOutput::Text,
+        };
+
+        let mut prompt = ChatPrompt::default();
+        let mut lines = spawn_stdin_lines();
+        print_prompt();
+        let result = {
+            let sending = async {
+                while let Some(line) = lines.recv().await {
+                    let text = line.trim_end_matches(['\r', '\n']);
+                    if !text.is_empty()
+                        && prompt.handle_line(context, &mut session, text).await?
+                            == PromptAction::Quit
+                    {
+                        break;
+                    }
+                    print_prompt();
+                }
+                Ok::<(), eyre::Report>(())
+            };
+            let receiving = async {
+                loop {
+                    tokio::select! {
+                        Some(inbound) = inbound_rx.recv() => {
+                            println!();
+                            if inbound_handler.handle(inbound).await {
+                                published.record_message();
+                            }
+                            print_prompt();
+                        }
+                        () = tokio::time::sleep(Duration::from_millis(250)) => {
+                            if let Err(error) = published.rotate_if_due(&api, &dead_routes).await {
+                                return Err::<(), _>(error);
+                            }
+                        }
+                    }
+                }
+            };
+            tokio::select! {
+                _ = tokio::signal::ctrl_c() => {
+                    println!("Ctrl+C detected.");
+                    Ok(())
+                }
+                result = sending => result,
+                result = receiving => result,
+            }
+        };
+
+        inbound_handler.save_sequences();
+        if let Some(summary) = inbound_handler.dropped_summary() {
+            println!("{summary}");
+        }
+        session.release_route();
+        published.unpublish(&api).await;
+        shutdown_api(api).await;
+        result
+    }
+}
+
+/// Pick the local route to publish: the one named, or the only one configured.
+fn select_route_name(profile_home: &ProfileHome, route: Option<&str>) -> Result<String> {
+    if let Some(route) = route {
+        return Ok(route.to_owned());
+    }
+
+    let routes = app_state::list_local_route_identities(profile_home)?;
+    match routes.as_slice() {
+        [only] => Ok(only.name.clone()),
+        [] => bail!(
+            "You have no routes. Create one with '{}'.",
+            Cli::display_invocation(&RouteArgs {
+                command: RouteCommand::Add(RouteAddArgs {
+                    name: "<name>".to_owned(),
+                    listen: false,
+                    mailbox: false,
+                }),
+            })
+        ),
+        _ => bail!("You have several routes; choose one with --route <name>."),
+    }
+}
+
+/// Read stdin lines on a dedicated thread so a pending read never blocks inbound output.
+pub(crate) fn spawn_stdin_lines() -> mpsc::UnboundedReceiver<String> {
+    let (line_tx, line_rx) = mpsc::unbounded_channel();
+    std::thread::spawn(move || {
+        for line in std::io::stdin().lock().lines() {
+            let Ok(line) = line else {
+                break;
+            };
+            if line_tx.send(line).is_err() {
+                break;
+            }
+        }
+    });
+    line_rx
+}
+
+fn print_prompt() {
+    let mut out = std::io::stdout();
+    let _ = write!(out, "CHAT> ");
+    let _ = out.flush();
+}
+
+impl ToArgs for ChatWithArgs {
+    fn to_args(&self) -> Vec<std::ffi::OsString> {
+        let mut args = vec![self.known_user.clone().into()];
+        if let Some(route) = &self.route {
+            args.push("--route".into());
+            args.push(route.clone().into());
+        }
+        if let Some(retry) = self.retry {
+            args.push("--retry".into());
+            args.push(retry.to_string().into());
+        }
+        if self.ack {
+            args.push("--ack".into());
+        }
+        if self.save_reply_routes {
+            args.push("--save-reply-routes".into());
+        }
+        args
+    }
+}
@@ -0,0 +1,188 @@
+//! Signing and encryption helpers for message envelopes exchanged over private routes.
+//!
+//! Envelope bodies are sealed as a `str16` nonce followed by the AEAD ciphertext. The
+//! key is a shared secret derived from the sender's profile secret and the recipient's
+//! profile public key, so either side can recompute it. The encoded envelope is then
+//! signed with the sender's profile keypair.
+
+use crate::cli::envelope;
+use crate::cli::envelope::Envelope;
+use crate::cli::envelope::Extensions;
+use crate::cli::envelope::MessageId;
+use crate::cli::envelope::MessageKind;
+use eyre::Result;
+use eyre::bail;
+use veilid_core::CRYPTO_KIND_VLD0;
+use veilid_core::KeyPair;
+use veilid_core::Nonce;
+use veilid_core::PublicKey;
+use veilid_core::VeilidAPI;
+
+/// Domain separator used when deriving chat shared secrets.
+const CHAT_SECRET_DOMAIN: &[u8] = b"vetchricore-chat";
+
+/// A sealed payload ready to hand to `app_message`, along with the id it was assigned.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct SealedMessage {
+    pub message_id: MessageId,
+    pub timestamp_ms: u64,
+    pub payload: Vec<u8>,
+}
+
+/// Seal a chat body for `recipient` in a freshly numbered envelope carrying `extensions`.
+///
+/// # Errors
+///
+/// Returns an error if the VLD0 cryptosystem is unavailable, or key agreement,
+/// encryption, or signing fails.
+pub async fn seal_chat_message(
+    api: &VeilidAPI,
+    keypair: &KeyPair,
+    recipient: &PublicKey,
+    body: &str,
+    extensions: Extensions,
+) -> Result<SealedMessage> {
+    seal_message(
+        api,
+        keypair,
+        recipient,
+        MessageKind::Chat,
+        body.as_bytes(),
+        extensions,
+    )
+    .await
+}
+
+/// Seal a delivery receipt for `message_id` back to the message's sender.
+///
+/// # Errors
+///
+/// Returns an error if the VLD0 cryptosystem is unavailable, or key agreement,
+/// encryption, or signing fails.
+pub async fn seal_receipt(
+    api: &VeilidAPI,
+    keypair: &KeyPair,
+    sender: &PublicKey,
+    message_id: MessageId,
+) -> Result<SealedMessage> {
+    seal_message(
+        api,
+        keypair,
+        sender,
+        MessageKind::Receipt,
+        &message_id.0,
+        Extensions::default(),
+    )
+    .await
+}
+
+/// Encrypt `body` for `recipient`, wrap it in an envelope, and sign the result.
+///
+/// # Errors
+///
+/// Returns an error if the VLD0 cryptosystem is unavailable, or key agreement,
+/// encryption, or signing fails.
+pub async fn seal_message(
+    api: &VeilidAPI,
+    keypair: &KeyPair,
+    recipient: &PublicKey,
+    kind: MessageKind,
+    body: &[u8],
+    extensions: Extensions,
+) -> Result<SealedMessage> {
+    let crypto = api.crypto()?;
+    let vcrypto = crypto
+        .get_async(CRYPTO_KIND_VLD0)
+        .ok_or_else(|| eyre::eyre!("VLD0 cryptosystem unavailable"))?;
+
+    let sender = keypair.key();
+    let sender_text = sender.to_string();
+    let shared_secret = vcrypto
+        .generate_shared_secret(recipient, &keypair.secret(), CHAT_SECRET_DOMAIN)
+        .await?;
+    let nonce = vcrypto.random_nonce().await;
+    let ciphertext = vcrypto
+        .encrypt_aead(body, &nonce, &shared_secret, Some(sender_text.as_bytes()))
+        .await?;
+
+    let nonce_text = nonce.to_string();
+    let nonce_len = u16::try_from(nonce_text.len())?;
+    let mut sealed_body = Vec::with_capacity(2 + nonce_text.len() + ciphertext.len());
+    sealed_body.extend_from_slice(&nonce_len.to_be_bytes());
+    sealed_body.extend_from_slice(nonce_text.as_bytes());
+    sealed_body.extend_from_slice(&ciphertext);
+
+    let message_id = MessageId::from_slice(&vcrypto.random_bytes(16).await)?;
+    let envelope = Envelope {
+        kind,
+        message_id,
+        sender,
+        timestamp_ms: u64::try_from(chrono::Utc::now().timestamp_millis()).unwrap_or_default(),
+        extensions,
+        body: sealed_body,
+    };
+    let signature = vcrypto
+        .sign(&keypair.key(), &keypair.secret(), &envelope.encode()?)
+        .await?;
+
+    Ok(SealedMessage {
+        message_id,
+        timestamp_ms: envelope.timestamp_ms,
+        payload: envelope.encode_signed(&signature)?,
+    })
+}
+
+/// Decode an envelope, verify it against the sender key it claims, then decrypt its
+/// body with the local profile keypair.
+///
+/// The returned envelope's `body` holds the plaintext.
+///
+/// # Errors
+///
+/// Returns an error if the envelope is malformed or of an unsupported version, the VLD0
+/// cryptosystem is unavailable, the signature does not match the claimed sender, or the
+/// body cannot be decrypted.
+pub async fn open_message(api: &VeilidAPI, keypair: &KeyPair, payload: &[u8]) -> Result<Envelope> {
+    let decoded = envelope::decode_signed(payload)?;
+    let mut envelope = decoded.envelope;
+    let sender_text = envelope.sender.to_string();
+
+    let crypto = api.crypto()?;
+    let vcrypto = crypto
+        .get_async(CRYPTO_KIND_VLD0)
+        .ok_or_else(|| eyre::eyre!("VLD0 cryptosystem unavailable"))?;
+    if !vcrypto
+        .verify(&envelope.sender, decoded.signed, &decoded.signature)
+        .await?
+    {
+        bail!("signature from {} did not verify", sender_text);
+    }
+
+    let Some((nonce_len, rest)) = envelope.body.split_first_chunk::<2>() else {
+        bail!("message from {} has a truncated body", sender_text);
+    };
+    let nonce_len = usize::from(u16::from_be_bytes(*nonce_len));
+    if rest.len() < nonce_len {
+        bail!("message from {} has a truncated body", sender_text);
+    }
+    let (nonce_bytes, ciphertext) = rest.split_at(nonce_len);
+    let nonce = std::str::from_utf8(nonce_bytes)?.parse::<Nonce>()?;
+
+    let shared_secret = vcrypto
+        .generate_shared_secret(&envelope.sender, &keypair.secret(), CHAT_SECRET_DOMAIN)
+        .await?;
+    let Ok(plaintext) = vcrypto
+        .decrypt_aead(
+            ciphertext,
+            &nonce,
+            &shared_secret,
+            Some(sender_text.as_bytes()),
+        )
+        .await
+    else {
+        bail!("message from {} could not be decrypted", sender_text);
+    };
+
+    envelope.body = plaintext;
+    Ok(envelope)
+}
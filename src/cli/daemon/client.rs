@@ -0,0 +1,204 @@
+//! Handing commands to a profile's daemon when one is running.
+//!
+//! Only commands that start Veilid and do one thing are handed over; notices they
+//! print on the daemon, such as send progress, are relayed back and printed here.
+//! Long-running and interactive commands such as `route listen` keep starting their
+//! own node, and the daemon refuses any other command it is sent.
+
+use crate::cli::Command;
+use crate::cli::InvokeContext;
+use crate::cli::app_state::ProfileHome;
+#[cfg(unix)]
+use crate::cli::daemon::protocol::DaemonReply;
+#[cfg(unix)]
+use crate::cli::daemon::protocol::DaemonRequest;
+#[cfg(unix)]
+use crate::cli::daemon::protocol::InvokeRequest;
+#[cfg(unix)]
+use crate::cli::daemon::protocol::socket_path;
+use crate::cli::events::EventOutput;
+use crate::cli::key::KeyArgs;
+use crate::cli::key::KeyCommand;
+use crate::cli::known_user::KnownUserArgs;
+use crate::cli::known_user::KnownUserCommand;
+use crate::cli::outbox::OutboxArgs;
+use crate::cli::outbox::OutboxCommand;
+use crate::cli::output_format::OutputFormat;
+use crate::cli::response::CliResponse;
+use crate::cli::room::RoomArgs;
+use crate::cli::room::RoomCommand;
+use crate::cli::route::RouteArgs;
+use crate::cli::route::RouteCommand;
+use crate::cli::send::SendArgs;
+use crate::cli::send::SendCommand;
+#[cfg(unix)]
+use eyre::Context;
+use eyre::Result;
+#[cfg(unix)]
+use std::io::ErrorKind;
+#[cfg(unix)]
+use tokio::io::AsyncBufReadExt;
+#[cfg(unix)]
+use tokio::io::AsyncWriteExt;
+#[cfg(unix)]
+use tokio::io::BufReader;
+#[cfg(unix)]
+use tokio::net::UnixStream;
+
+/// Whether `command` runs on the daemon when one is listening.
+#[must_use]
+pub(crate) fn runs_on_daemon(command: &Command, context: &InvokeContext) -> bool {
+    match command {
+        Command::Key(KeyArgs {
+            command: KeyCommand::Gen(_),
+        })
+        | Command::Route(RouteArgs {
+            command: RouteCommand::Remove(_),
+        })
+        | Command::Room(RoomArgs {
+            command: RoomCommand::Send(_),
+        })
+        | Command::Outbox(OutboxArgs {
+            command: OutboxCommand::Retry(_),
+        })
+        | Command::KnownUser(KnownUserArgs {
+            command: KnownUserCommand::Status(_),
+        })
+        | Command::Send(SendArgs {
+            command: SendCommand::File(_),
+            ..
+        }) => true,
+        Command::Route(RouteArgs {
+            command: RouteCommand::Add(args) | RouteCommand::New(args) | RouteCommand::Create(args),
+        }) => !args.listen,
+        Command::Send(SendArgs {
+            command: SendCommand::Chat(args),
+            ..
+        }) => args.message.is_some() && !EventOutput::for_context(context).is_json(),
+        _ => false,
+    }
+}
+
+/// An open connection to a profile's daemon.
+#[cfg(unix)]
+#[derive(Debug)]
+pub(crate) struct DaemonConnection {
+    stream: UnixStream,
+}
+
+#[cfg(unix)]
+impl DaemonConnection {
+    /// Connect to the profile's daemon, or `None` when no daemon is listening.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the socket exists but cannot be connected to for a reason
+    /// other than nothing listening on it.
+    pub async fn connect(profile_home: &ProfileHome) -> Result<Option<Self>> {
+        match UnixStream::connect(socket_path(profile_home)).await {
+            Ok(stream) => Ok(Some(Self { stream })),
+            Err(error)
+                if matches!(
+                    error.kind(),
+                    ErrorKind::NotFound | ErrorKind::ConnectionRefused
+                ) =>
+            {
+                Ok(None)
+            }
+            Err(error) => Err(error).wrap_err("failed to connect to the daemon"),
+        }
+    }
+
+    /// Send one request and wait for the daemon's reply, printing notices relayed
+    /// before it to `notes`.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the socket fails or the reply cannot be read.
+    pub async fn request(self, request: &DaemonRequest, notes: EventOutput) -> Result<DaemonReply> {
+        let mut stream = BufReader::new(self.stream);
+        let mut line = facet_json::to_string(request)?;
+        line.push('\n');
+        stream.get_mut().write_all(line.as_bytes()).await?;
+
+        loop {
+            let mut reply = String::new();
+            let _ = stream.read_line(&mut reply).await?;
+            if reply.is_empty() {
+                eyre::bail!("The daemon closed the connection without replying.");
+            }
+            let reply: DaemonReply = facet_json::from_str(reply.trim_end())
+                .map_err(|error| eyre::eyre!("Unreadable reply from the daemon: {error}"))?;
+            match reply.note {
+                Some(note) => notes.note(note),
+                None => return Ok(reply),
+            }
+        }
+    }
+
+    /// Run `command` on the daemon, rendering its response in `render`.
+    ///
+    /// # Errors
+    ///
+    /// Returns the command's error, or an error if the daemon cannot be reached.
+    pub async fn forward(
+        self,
+        command: Command,
+        context: &InvokeContext,
+        render: OutputFormat,
+    ) -> Result<CliResponse> {
+        let reply = self
+            .request(
+                &DaemonRequest::Invoke(InvokeRequest {
+                    command,
+                    output_format: context.output_format(),
+                    render,
+                }),
+                EventOutput::for_context(context),
+            )
+            .await?;
+        if let Some(error) = reply.error {
+            return Err(eyre::eyre!(error));
+        }
+        Ok(reply
+            .output
+            .map_or_else(CliResponse::empty, CliResponse::rendered))
+    }
+}
+
+/// Never constructed; there is no daemon without Unix domain sockets.
+#[cfg(not(unix))]
+#[derive(Debug)]
+pub(crate) struct DaemonConnection;
+
+#[cfg(not(unix))]
+impl DaemonConnection {
+    /// Always `None`; the daemon needs Unix domain sockets.
+    ///
+    /// # Errors
+    ///
+    /// Never returns an error.
+    #[expect(
+        clippy::unused_async,
+        reason = "matches the Unix signature so callers need no cfg"
+    )]
+    pub async fn connect(_profile_home: &ProfileHome) -> Result<Option<Self>> {
+        Ok(None)
+    }
+
+    /// # Errors
+    ///
+    /// Always returns an error; no connection can exist.
+    #[expect(
+        clippy::unused_async,
+        reason = "matches the Unix signature so callers need no cfg"
+    )]
+    pub async fn forward(
+        self,
+        _command: Command,
+        _context: &InvokeContext,
+        _render: OutputFormat,
+    ) -> Result<CliResponse> {
+        eyre::bail!("The daemon needs Unix domain sockets and is only available on Unix.")
+    }
+}
@@ -0,0 +1,78 @@
+use crate::cli::InvokeContext;
+use crate::cli::ToArgs;
+use crate::cli::daemon::start::DaemonStartArgs;
+use crate::cli::daemon::status::DaemonStatusArgs;
+use crate::cli::daemon::stop::DaemonStopArgs;
+use crate::cli::response::CliResponse;
+use arbitrary::Arbitrary;
+use eyre::Result;
+use facet::Facet;
+use figue as args;
+use std::ffi::OsString;
+
+#[derive(Facet, Arbitrary, Debug, PartialEq)]
+pub struct DaemonArgs {
+    #[facet(args::subcommand)]
+    pub command: DaemonCommand,
+}
+
+#[derive(Facet, Arbitrary, Debug, PartialEq)]
+#[repr(u8)]
+pub enum DaemonCommand {
+    /// Start a daemon that keeps this profile's Veilid node attached between commands.
+    Start(DaemonStartArgs),
+    /// Stop the profile's daemon.
+    Stop(DaemonStopArgs),
+    /// Show whether the profile's daemon is running and its attachment state.
+    Status(DaemonStatusArgs),
+}
+
+impl DaemonArgs {
+    /// # Errors
+    ///
+    /// Returns an error if the selected daemon subcommand fails.
+    #[cfg(unix)]
+    pub async fn invoke(self, context: &InvokeContext) -> Result<CliResponse> {
+        Ok(match self.command {
+            DaemonCommand::Start(args) => match args.invoke(context).await? {
+                Some(response) => response.into(),
+                None => CliResponse::empty(),
+            },
+            DaemonCommand::Stop(args) => args.invoke(context).await?.into(),
+            DaemonCommand::Status(args) => args.invoke(context).await?.into(),
+        })
+    }
+
+    /// # Errors
+    ///
+    /// Always returns an error; the daemon listens on a Unix domain socket.
+    #[cfg(not(unix))]
+    #[expect(
+        clippy::unused_async,
+        reason = "command handlers use async invoke signature consistently"
+    )]
+    pub async fn invoke(self, _context: &InvokeContext) -> Result<CliResponse> {
+        eyre::bail!("The daemon needs Unix domain sockets and is only available on Unix.")
+    }
+}
+
+impl ToArgs for DaemonArgs {
+    fn to_args(&self) -> Vec<OsString> {
+        let mut args = Vec::new();
+        match &self.command {
+            DaemonCommand::Start(start_args) => {
+                args.push("start".into());
+                args.extend(start_args.to_args());
+            }
+            DaemonCommand::Stop(stop_args) => {
+                args.push("stop".into());
+                args.extend(stop_args.to_args());
+            }
+            DaemonCommand::Status(status_args) => {
+                args.push("status".into());
+                args.extend(status_args.to_args());
+            }
+        }
+        args
+    }
+}
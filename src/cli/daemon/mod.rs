@@ -0,0 +1,11 @@
+pub(crate) mod client;
+mod daemon_cli;
+#[cfg(unix)]
+pub(crate) mod protocol;
+#[cfg(unix)]
+pub(crate) mod server;
+pub(crate) mod start;
+pub(crate) mod status;
+pub(crate) mod stop;
+
+pub use daemon_cli::*;
@@ -0,0 +1,59 @@
+//! Requests a profile's daemon answers over its Unix socket.
+//!
+//! Each connection carries one request and one reply, each a single line of JSON.
+//! While an invoked command runs, the notices it prints arrive first, each as a reply
+//! line carrying only `note`.
+
+use crate::cli::Command;
+use crate::cli::app_state::ProfileHome;
+use crate::cli::daemon::status::DaemonStatusResponse;
+use crate::cli::output_format::OutputFormat;
+use crate::cli::output_format::OutputFormatArg;
+use facet::Facet;
+use std::path::PathBuf;
+
+const SOCKET_FILE: &str = "daemon.sock";
+const LOG_FILE: &str = "daemon.log";
+
+/// The socket the profile's daemon listens on.
+#[must_use]
+pub fn socket_path(profile_home: &ProfileHome) -> PathBuf {
+    profile_home.profile_daemon_dir().join(SOCKET_FILE)
+}
+
+/// Where a daemon started in the background writes its output.
+#[must_use]
+pub fn log_path(profile_home: &ProfileHome) -> PathBuf {
+    profile_home.profile_daemon_dir().join(LOG_FILE)
+}
+
+#[derive(Debug, Facet)]
+#[repr(u8)]
+pub enum DaemonRequest {
+    /// Run a command on the daemon's node and reply with its rendered response.
+    Invoke(InvokeRequest),
+    /// Report the daemon's state.
+    Status,
+    /// Shut the daemon down after replying.
+    Stop,
+}
+
+#[derive(Debug, Facet)]
+pub struct InvokeRequest {
+    pub command: Command,
+    /// The client's `--output-format`, for commands that check it themselves.
+    pub output_format: Option<OutputFormatArg>,
+    /// The format to render the response in, resolved against the client's stdout.
+    pub render: OutputFormat,
+}
+
+#[derive(Debug, Default, Facet)]
+pub struct DaemonReply {
+    /// Rendered response of an invoked command; `None` when it had nothing to print.
+    pub output: Option<String>,
+    /// Error of an invoked command or a request the daemon could not read.
+    pub error: Option<String>,
+    pub status: Option<DaemonStatusResponse>,
+    /// A notice printed by the command while it runs; the final reply follows.
+    pub note: Option<String>,
+}
@@ -0,0 +1,241 @@
+//! The daemon's side of the socket: one attached Veilid node serving commands.
+//!
+//! Requests are handled one at a time, so each command has the node to itself and
+//! receives its updates until it finishes. A client gets ten seconds to send its
+//! request line, so an idle connection cannot hold up the others. Only commands the
+//! client would hand over are run, and the socket is readable and writable by its
+//! owner alone.
+
+use crate::cli::Cli;
+use crate::cli::InvokeContext;
+use crate::cli::daemon::client::runs_on_daemon;
+use crate::cli::daemon::protocol::DaemonReply;
+use crate::cli::daemon::protocol::DaemonRequest;
+use crate::cli::daemon::protocol::InvokeRequest;
+use crate::cli::daemon::protocol::socket_path;
+use crate::cli::daemon::status::DaemonStatusResponse;
+use crate::cli::events;
+use crate::cli::veilid_runtime;
+use eyre::Context;
+use eyre::Result;
+use std::fs::Permissions;
+use std::os::unix::fs::PermissionsExt;
+use std::time::Duration;
+use std::time::Instant;
+use tokio::io::AsyncBufReadExt;
+use tokio::io::AsyncReadExt;
+use tokio::io::AsyncWriteExt;
+use tokio::io::BufReader;
+use tokio::net::UnixListener;
+use tokio::net::UnixStream;
+use tokio::sync::mpsc;
+use tracing::info;
+use tracing::warn;
+use veilid_core::VeilidAPI;
+
+/// Longest a client may take to send its request line.
+const REQUEST_READ_TIMEOUT: Duration = Duration::from_secs(10);
+/// Longest request line accepted, including the newline.
+const MAX_REQUEST_BYTES: u64 = 1024 * 1024;
+
+/// Counters reported by `daemon status`.
+#[derive(Clone, Copy, Debug)]
+struct ServeStats {
+    started: Instant,
+    commands_served: u64,
+}
+
+/// Start the daemon's node and serve requests until stopped or interrupted.
+///
+/// # Errors
+///
+/// Returns an error if Veilid cannot be started or the socket cannot be bound.
+pub(crate) async fn serve(context: &InvokeContext) -> Result<()> {
+    let profile_home = context.profile_home();
+    let api = veilid_runtime::start_daemon_node(profile_home).await?;
+
+    let socket_path = socket_path(profile_home);
+    // A daemon that did not shut down cleanly leaves its socket file behind.
+    let _ = std::fs::remove_file(&socket_path);
+    // The directory is private before the socket exists, so it is never reachable by
+    // other users, whatever the umask.
+    let daemon_dir = profile_home.profile_daemon_dir();
+    let listener = match std::fs::create_dir_all(&daemon_dir)
+        .and_then(|()| std::fs::set_permissions(&daemon_dir, Permissions::from_mode(0o700)))
+        .and_then(|()| UnixListener::bind(&socket_path))
+        .and_then(|listener| {
+            std::fs::set_permissions(&socket_path, Permissions::from_mode(0o600))?;
+            Ok(listener)
+        })
+        .wrap_err_with(|| format!("failed to bind {}", socket_path.display()))
+    {
+        Ok(listener) => listener,
+        Err(error) => {
+            api.shutdown().await;
+            return Err(error);
+        }
+    };
+    info!(socket = %socket_path.display(), profile = profile_home.profile(), "daemon listening");
+
+    let mut stats = ServeStats {
+        started: Instant::now(),
+        commands_served: 0,
+    };
+    loop {
+        tokio::select! {
+            _ = tokio::signal::ctrl_c() => {
+                break;
+            }
+            accepted = listener.accept() => {
+                let stream = match accepted {
+                    Ok((stream, _address)) => stream,
+                    Err(error) => {
+                        warn!(%error, "failed to accept daemon connection");
+                        continue;
+                    }
+                };
+                match handle_connection(context, &api, stream, &mut stats).await {
+                    Ok(true) => break,
+                    Ok(false) => {}
+                    Err(error) => warn!(%error, "daemon connection failed"),
+                }
+            }
+        }
+    }
+
+    let _ = std::fs::remove_file(&socket_path);
+    api.shutdown().await;
+    info!(profile = profile_home.profile(), "daemon stopped");
+    Ok(())
+}
+
+/// Answer the one request on a connection, returning whether the daemon should stop.
+async fn handle_connection(
+    context: &InvokeContext,
+    api: &VeilidAPI,
+    stream: UnixStream,
+    stats: &mut ServeStats,
+) -> Result<bool> {
+    let mut stream = BufReader::new(stream);
+    let mut line = String::new();
+    let read = tokio::time::timeout(
+        REQUEST_READ_TIMEOUT,
+        (&mut stream).take(MAX_REQUEST_BYTES).read_line(&mut line),
+    )
+    .await;
+    let request = match read {
+        Ok(Ok(_)) if line.ends_with('\n') => facet_json::from_str::<DaemonRequest>(line.trim_end())
+            .map_err(|error| format!("Unreadable request: {error}")),
+        Ok(Ok(_)) => Err(format!(
+            "Request was incomplete or over {MAX_REQUEST_BYTES} bytes."
+        )),
+        Ok(Err(error)) => return Err(error.into()),
+        Err(_elapsed) => Err("Timed out waiting for a request.".to_owned()),
+    };
+
+    let (reply, stop) = match request {
+        Ok(DaemonRequest::Invoke(request)) => {
+            let (note_tx, mut note_rx) = mpsc::unbounded_channel();
+            events::capture_notes(Some(note_tx));
+            let invoking = invoke(context, request, stats);
+            tokio::pin!(invoking);
+            let reply = loop {
+                tokio::select! {
+                    reply = &mut invoking => break reply,
+                    Some(note) = note_rx.recv() => {
+                        // A client that went away still lets the command finish.
+                        let _ = write_reply(stream.get_mut(), &note_reply(note)).await;
+                    }
+                }
+            };
+            events::capture_notes(None);
+            while let Ok(note) = note_rx.try_recv() {
+                let _ = write_reply(stream.get_mut(), &note_reply(note)).await;
+            }
+            (reply, false)
+        }
+        Ok(DaemonRequest::Status) => {
+            let status = DaemonStatusResponse::describe(
+                context.profile_home(),
+                api,
+                stats.started,
+                stats.commands_served,
+            )
+            .await;
+            (
+                DaemonReply {
+                    status: Some(status),
+                    ..DaemonReply::default()
+                },
+                false,
+            )
+        }
+        Ok(DaemonRequest::Stop) => (DaemonReply::default(), true),
+        Err(error) => (
+            DaemonReply {
+                error: Some(error),
+                ..DaemonReply::default()
+            },
+            false,
+        ),
+    };
+
+    write_reply(stream.get_mut(), &reply).await?;
+    Ok(stop)
+}
+
+/// Write one reply line to the client.
+async fn write_reply(stream: &mut UnixStream, reply: &DaemonReply) -> Result<()> {
+    let mut line = facet_json::to_string(reply)?;
+    line.push('\n');
+    stream.write_all(line.as_bytes()).await?;
+    Ok(())
+}
+
+fn note_reply(note: String) -> DaemonReply {
+    DaemonReply {
+        note: Some(note),
+        ..DaemonReply::default()
+    }
+}
+
+/// Run a client's command on the shared node and render its response.
+async fn invoke(
+    context: &InvokeContext,
+    request: InvokeRequest,
+    stats: &mut ServeStats,
+) -> DaemonReply {
+    let InvokeRequest {
+        command,
+        output_format,
+        render,
+    } = request;
+    let context = context.clone().with_output_format(output_format);
+    if !runs_on_daemon(&command, &context) {
+        warn!(command = %Cli::display_invocation(&command), "refused command");
+        return DaemonReply {
+            error: Some(format!(
+                "'{}' does not run on the daemon.",
+                Cli::display_invocation(&command)
+            )),
+            ..DaemonReply::default()
+        };
+    }
+    info!(command = %Cli::display_invocation(&command), "running command");
+
+    // Boxed because the daemon is itself started by a command.
+    let result = Box::pin(command.invoke(&context)).await;
+    veilid_runtime::end_daemon_command();
+    stats.commands_served += 1;
+
+    match result.and_then(|response| response.render(render)) {
+        Ok(output) => DaemonReply {
+            output,
+            ..DaemonReply::default()
+        },
+        Err(error) => DaemonReply {
+            error: Some(format!("{error:#}")),
+            ..DaemonReply::default()
+        },
+    }
+}
@@ -0,0 +1,160 @@
+#[cfg(unix)]
+use crate::cli::Command;
+#[cfg(unix)]
+use crate::cli::InvokeContext;
+use crate::cli::ToArgs;
+#[cfg(unix)]
+use crate::cli::daemon::DaemonArgs;
+#[cfg(unix)]
+use crate::cli::daemon::DaemonCommand;
+#[cfg(unix)]
+use crate::cli::daemon::client::DaemonConnection;
+#[cfg(unix)]
+use crate::cli::daemon::protocol::log_path;
+#[cfg(unix)]
+use crate::cli::daemon::server::serve;
+#[cfg(unix)]
+use crate::cli::global_args::GlobalArgs;
+use arbitrary::Arbitrary;
+#[cfg(unix)]
+use eyre::Context;
+#[cfg(unix)]
+use eyre::Result;
+#[cfg(unix)]
+use eyre::bail;
+use facet::Facet;
+use figue as args;
+use std::fmt;
+#[cfg(unix)]
+use std::os::unix::process::CommandExt;
+#[cfg(unix)]
+use std::process::Stdio;
+#[cfg(unix)]
+use std::time::Duration;
+#[cfg(unix)]
+use std::time::Instant;
+
+/// How long `daemon start` waits for the background daemon to accept connections.
+#[cfg(unix)]
+const STARTUP_TIMEOUT: Duration = Duration::from_secs(60);
+
+#[derive(Facet, Arbitrary, Debug, PartialEq, Default)]
+pub struct DaemonStartArgs {
+    /// Serve from this terminal until stopped instead of starting a background process.
+    #[facet(args::named, default)]
+    pub foreground: bool,
+}
+
+#[derive(Clone, Debug, PartialEq, Eq, Facet)]
+pub struct DaemonStartResponse {
+    profile: String,
+    pid: u32,
+    log: String,
+}
+
+impl fmt::Display for DaemonStartResponse {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "Started the daemon for {} (pid {}); it logs to {}",
+            self.profile, self.pid, self.log
+        )
+    }
+}
+
+impl DaemonStartArgs {
+    /// Start the profile's daemon in the background, or serve in the foreground.
+    ///
+    /// Returns `None` once a foreground daemon has stopped.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if a daemon is already running, the background process exits
+    /// or does not start listening in time, or Veilid cannot be started.
+    #[cfg(unix)]
+    pub async fn invoke(self, context: &InvokeContext) -> Result<Option<DaemonStartResponse>> {
+        let profile_home = context.profile_home();
+        if DaemonConnection::connect(profile_home).await?.is_some() {
+            bail!(
+                "A daemon is already running for {}.",
+                profile_home.profile()
+            );
+        }
+
+        if self.foreground {
+            serve(context).await?;
+            return Ok(None);
+        }
+        start_in_background(context).await.map(Some)
+    }
+}
+
+/// Run `daemon start --foreground` as a detached process and wait until it listens.
+#[cfg(unix)]
+async fn start_in_background(context: &InvokeContext) -> Result<DaemonStartResponse> {
+    let profile_home = context.profile_home();
+    std::fs::create_dir_all(profile_home.profile_daemon_dir())?;
+    let log_path = log_path(profile_home);
+    let log = std::fs::OpenOptions::new()
+        .create(true)
+        .append(true)
+        .open(&log_path)
+        .wrap_err_with(|| format!("failed to open {}", log_path.display()))?;
+
+    let mut args = GlobalArgs {
+        profile: Some(profile_home.profile().to_owned()),
+        home_dir: Some(context.app_home().to_path_buf()),
+        cache_dir: Some(context.cache_home().to_path_buf()),
+        ..GlobalArgs::default()
+    }
+    .to_args();
+    args.extend(
+        Command::Daemon(DaemonArgs {
+            command: DaemonCommand::Start(DaemonStartArgs { foreground: true }),
+        })
+        .to_args(),
+    );
+    let mut child = std::process::Command::new(std::env::current_exe()?)
+        .args(args)
+        .stdin(Stdio::null())
+        .stdout(log.try_clone()?)
+        .stderr(log)
+        // Keep the daemon out of the terminal's process group so Ctrl+C there leaves it running.
+        .process_group(0)
+        .spawn()
+        .wrap_err("failed to start the daemon process")?;
+
+    let started = Instant::now();
+    while DaemonConnection::connect(profile_home).await?.is_none() {
+        if let Some(status) = child.try_wait()? {
+            bail!(
+                "The daemon exited during startup ({status}); see {}.",
+                log_path.display()
+            );
+        }
+        if started.elapsed() >= STARTUP_TIMEOUT {
+            bail!(
+                "The daemon did not start listening within {}s; see {}.",
+                STARTUP_TIMEOUT.as_secs(),
+                log_path.display()
+            );
+        }
+        tokio::time::sleep(Duration::from_millis(250)).await;
+    }
+
+    Ok(DaemonStartResponse {
+        profile: profile_home.profile().to_owned(),
+        pid: child.id(),
+        log: log_path.display().to_string(),
+    })
+}
+
+impl ToArgs for DaemonStartArgs {
+    fn to_args(&self) -> Vec<std::ffi::OsString> {
+        let mut args = Vec::new();
+        if self.foreground {
+            args.push("--foreground".into());
+        }
+        args
+    }
+}
@@ -0,0 +1,119 @@
+#[cfg(unix)]
+use crate::cli::InvokeContext;
+use crate::cli::ToArgs;
+#[cfg(unix)]
+use crate::cli::app_state::ProfileHome;
+#[cfg(unix)]
+use crate::cli::daemon::client::DaemonConnection;
+#[cfg(unix)]
+use crate::cli::daemon::protocol::DaemonRequest;
+#[cfg(unix)]
+use crate::cli::events::EventOutput;
+use arbitrary::Arbitrary;
+#[cfg(unix)]
+use eyre::Result;
+use facet::Facet;
+use std::fmt;
+use std::time::Duration;
+#[cfg(unix)]
+use std::time::Instant;
+#[cfg(unix)]
+use veilid_core::VeilidAPI;
+
+#[derive(Facet, Arbitrary, Debug, PartialEq, Default)]
+pub struct DaemonStatusArgs;
+
+#[derive(Clone, Debug, Default, PartialEq, Eq, Facet)]
+pub struct DaemonStatusResponse {
+    profile: String,
+    running: bool,
+    pid: Option<u32>,
+    uptime_secs: Option<u64>,
+    commands_served: Option<u64>,
+    attachment: Option<String>,
+    public_internet_ready: Option<bool>,
+}
+
+impl fmt::Display for DaemonStatusResponse {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        if !self.running {
+            return write!(f, "No daemon is running for {}.", self.profile);
+        }
+        write!(f, "Daemon for {} is running", self.profile)?;
+        if let Some(pid) = self.pid {
+            write!(f, " (pid {pid}")?;
+            if let Some(uptime_secs) = self.uptime_secs {
+                write!(
+                    f,
+                    ", up {}",
+                    humantime::format_duration(Duration::from_secs(uptime_secs))
+                )?;
+            }
+            write!(f, ")")?;
+        }
+        write!(f, ".")?;
+        if let Some(attachment) = &self.attachment {
+            let ready = if self.public_internet_ready == Some(true) {
+                "ready"
+            } else {
+                "not ready"
+            };
+            write!(f, "\nAttachment: {attachment}; public internet {ready}.")?;
+        }
+        if let Some(commands_served) = self.commands_served {
+            write!(f, "\nCommands served: {commands_served}.")?;
+        }
+        Ok(())
+    }
+}
+
+impl DaemonStatusResponse {
+    /// Describe the daemon running in this process.
+    #[cfg(unix)]
+    pub(crate) async fn describe(
+        profile_home: &ProfileHome,
+        api: &VeilidAPI,
+        started: Instant,
+        commands_served: u64,
+    ) -> Self {
+        let state = api.get_state().await.ok();
+        Self {
+            profile: profile_home.profile().to_owned(),
+            running: true,
+            pid: Some(std::process::id()),
+            uptime_secs: Some(started.elapsed().as_secs()),
+            commands_served: Some(commands_served),
+            attachment: state
+                .as_ref()
+                .map(|state| state.attachment.state.to_string()),
+            public_internet_ready: state.map(|state| state.attachment.public_internet_ready),
+        }
+    }
+}
+
+impl DaemonStatusArgs {
+    /// # Errors
+    ///
+    /// Returns an error if a daemon is listening but does not answer.
+    #[cfg(unix)]
+    pub async fn invoke(self, context: &InvokeContext) -> Result<DaemonStatusResponse> {
+        let profile_home = context.profile_home();
+        let Some(connection) = DaemonConnection::connect(profile_home).await? else {
+            return Ok(DaemonStatusResponse {
+                profile: profile_home.profile().to_owned(),
+                ..DaemonStatusResponse::default()
+            });
+        };
+        let reply = connection
+            .request(&DaemonRequest::Status, EventOutput::Text)
+            .await?;
+        if let Some(error) = reply.error {
+            return Err(eyre::eyre!(error));
+        }
+        reply
+            .status
+            .ok_or_else(|| eyre::eyre!("The daemon did not report its status."))
+    }
+}
+
+impl ToArgs for DaemonStatusArgs {}
@@ -0,0 +1,54 @@
+#[cfg(unix)]
+use crate::cli::InvokeContext;
+use crate::cli::ToArgs;
+#[cfg(unix)]
+use crate::cli::daemon::client::DaemonConnection;
+#[cfg(unix)]
+use crate::cli::daemon::protocol::DaemonRequest;
+#[cfg(unix)]
+use crate::cli::events::EventOutput;
+use arbitrary::Arbitrary;
+#[cfg(unix)]
+use eyre::Result;
+#[cfg(unix)]
+use eyre::bail;
+use facet::Facet;
+use std::fmt;
+
+#[derive(Facet, Arbitrary, Debug, PartialEq, Default)]
+pub struct DaemonStopArgs;
+
+#[derive(Clone, Debug, PartialEq, Eq, Facet)]
+pub struct DaemonStopResponse {
+    profile: String,
+}
+
+impl fmt::Display for DaemonStopResponse {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "Stopped the daemon for {}.", self.profile)
+    }
+}
+
+impl DaemonStopArgs {
+    /// # Errors
+    ///
+    /// Returns an error if no daemon is running or it does not answer.
+    #[cfg(unix)]
+    pub async fn invoke(self, context: &InvokeContext) -> Result<DaemonStopResponse> {
+        let profile_home = context.profile_home();
+        let Some(connection) = DaemonConnection::connect(profile_home).await? else {
+            bail!("No daemon is running for {}.", profile_home.profile());
+        };
+        let reply = connection
+            .request(&DaemonRequest::Stop, EventOutput::Text)
+            .await?;
+        if let Some(error) = reply.error {
+            return Err(eyre::eyre!(error));
+        }
+        Ok(DaemonStopResponse {
+            profile: profile_home.profile().to_owned(),
+        })
+    }
+}
+
+impl ToArgs for DaemonStopArgs {}
@@ -0,0 +1,606 @@
+//! Versioned binary envelope shared by every message sent over private routes.
+//!
+//! Wire layout (integers are big-endian, `str16` is a `u16` length followed by UTF-8):
+//!
+//! ```text
+//! version u8 | kind u8 | message id [16] | sender str16 | timestamp ms u64
+//!   | extension count u16 | (tag u8 | len u16 | bytes)* | body len u32 | body
+//!   | signature str16
+//! ```
+//!
+//! The signature covers every byte before it. Extensions carry optional fields; tags
+//! a peer does not recognise are skipped, so new fields can be added without a version
+//! bump. Payloads with an unknown version are rejected outright.
+//!
+//! Extension tags: `1` room id (16 bytes), `2` reply route record key (UTF-8), `3` file
+//! chunk header (see [`FileChunkHeader`]), `4` sequence number (`u64`).
+
+use eyre::Result;
+use eyre::bail;
+use std::fmt;
+use std::str::FromStr;
+use veilid_core::PublicKey;
+use veilid_core::RecordKey;
+use veilid_core::Signature;
+
+/// The only envelope version this build understands.
+pub const ENVELOPE_VERSION: u8 = 1;
+
+/// What an envelope's body contains.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum MessageKind {
+    Chat,
+    /// Reply to an `app_call` confirming which message id was received.
+    Receipt,
+    /// One piece of a file; the body is the chunk bytes and the file chunk extension
+    /// says where it belongs.
+    FileChunk,
+    /// A kind introduced by a newer peer; its body is not interpreted.
+    Unknown(u8),
+}
+
+impl MessageKind {
+    #[must_use]
+    pub fn to_byte(self) -> u8 {
+        match self {
+            Self::Chat => 1,
+            Self::Receipt => 2,
+            Self::FileChunk => 3,
+            Self::Unknown(value) => value,
+        }
+    }
+
+    #[must_use]
+    pub fn from_byte(value: u8) -> Self {
+        match value {
+            1 => Self::Chat,
+            2 => Self::Receipt,
+            3 => Self::FileChunk,
+            other => Self::Unknown(other),
+        }
+    }
+}
+
+impl fmt::Display for MessageKind {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::Chat => f.write_str("chat"),
+            Self::Receipt => f.write_str("receipt"),
+            Self::FileChunk => f.write_str("file chunk"),
+            Self::Unknown(value) => write!(f, "unknown({value})"),
+        }
+    }
+}
+
+/// Random 128-bit identifier assigned by the sender of a message.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash, PartialOrd, Ord)]
+pub struct MessageId(pub [u8; 16]);
+
+impl MessageId {
+    /// Build a message id from random bytes.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if `bytes` is not exactly 16 bytes long.
+    pub fn from_slice(bytes: &[u8]) -> Result<Self> {
+        let Ok(bytes) = <[u8; 16]>::try_from(bytes) else {
+            bail!("message id must be 16 bytes, got {}", bytes.len());
+        };
+        Ok(Self(bytes))
+    }
+}
+
+impl fmt::Display for MessageId {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write_hex(f, &self.0)
+    }
+}
+
+impl FromStr for MessageId {
+    type Err = eyre::Error;
+
+    fn from_str(value: &str) -> Result<Self, Self::Err> {
+        Ok(Self(parse_hex16(value, "message id")?))
+    }
+}
+
+/// 128-bit identifier shared by every member of a chat room.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash, PartialOrd, Ord)]
+pub struct RoomId(pub [u8; 16]);
+
+impl RoomId {
+    /// Generate a fresh room id from the thread-local cryptographic RNG.
+    #[must_use]
+    pub fn generate() -> Self {
+        Self(rand::random())
+    }
+}
+
+impl fmt::Display for RoomId {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write_hex(f, &self.0)
+    }
+}
+
+impl FromStr for RoomId {
+    type Err = eyre::Error;
+
+    fn from_str(value: &str) -> Result<Self, Self::Err> {
+        Ok(Self(parse_hex16(value, "room id")?))
+    }
+}
+
+/// Where a file chunk belongs in the file being transferred.
+///
+/// Encoded as `index u32 | count u32 | chunk size u32 | total size u64 | content hash
+/// str16 | file name str16`. The content hash identifies the transfer, so a resumed
+/// send lands in the same partial download.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct FileChunkHeader {
+    pub index: u32,
+    pub count: u32,
+    /// Size of every chunk except possibly the last.
+    pub chunk_size: u32,
+    pub total_size: u64,
+    pub content_hash: String,
+    pub file_name: String,
+}
+
+impl FileChunkHeader {
+    fn encode(&self) -> Result<Vec<u8>> {
+        let mut out = Vec::with_capacity(24 + self.content_hash.len() + self.file_name.len());
+        out.extend_from_slice(&self.index.to_be_bytes());
+        out.extend_from_slice(&self.count.to_be_bytes());
+        out.extend_from_slice(&self.chunk_size.to_be_bytes());
+        out.extend_from_slice(&self.total_size.to_be_bytes());
+        write_str16(&mut out, &self.content_hash, "content hash")?;
+        write_str16(&mut out, &self.file_name, "file name")?;
+        Ok(out)
+    }
+
+    fn decode(value: &[u8]) -> Result<Self> {
+        let mut reader = Reader::new(value);
+        let header = Self {
+            index: reader.u32()?,
+            count: reader.u32()?,
+            chunk_size: reader.u32()?,
+            total_size: reader.u64()?,
+            content_hash: reader.str16()?.to_owned(),
+            file_name: reader.str16()?.to_owned(),
+        };
+        if !reader.is_empty() {
+            bail!("file chunk extension has trailing bytes");
+        }
+        Ok(header)
+    }
+}
+
+/// Optional envelope fields, each carried as an extension TLV.
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct Extensions {
+    /// The room a chat message was sent to, if any.
+    pub room_id: Option<RoomId>,
+    /// Route record key the sender listens on, so the recipient can reply.
+    pub reply_route: Option<RecordKey>,
+    /// Placement of a [`MessageKind::FileChunk`] body within its file.
+    pub file_chunk: Option<FileChunkHeader>,
+    /// Position of a chat message among those the sender has sent to this recipient,
+    /// starting at 1, so the recipient can notice messages that never arrived.
+    pub sequence: Option<u64>,
+}
+
+const EXTENSION_ROOM_ID: u8 = 1;
+const EXTENSION_REPLY_ROUTE: u8 = 2;
+const EXTENSION_FILE_CHUNK: u8 = 3;
+const EXTENSION_SEQUENCE: u8 = 4;
+
+impl Extensions {
+    fn encode(&self) -> Result<Vec<(u8, Vec<u8>)>> {
+        let mut tlvs = Vec::new();
+        if let Some(room_id) = self.room_id {
+            tlvs.push((EXTENSION_ROOM_ID, room_id.0.to_vec()));
+        }
+        if let Some(reply_route) = &self.reply_route {
+            tlvs.push((EXTENSION_REPLY_ROUTE, reply_route.to_string().into_bytes()));
+        }
+        if let Some(file_chunk) = &self.file_chunk {
+            tlvs.push((EXTENSION_FILE_CHUNK, file_chunk.encode()?));
+        }
+        if let Some(sequence) = self.sequence {
+            tlvs.push((EXTENSION_SEQUENCE, sequence.to_be_bytes().to_vec()));
+        }
+        Ok(tlvs)
+    }
+
+    fn decode_one(&mut self, tag: u8, value: &[u8]) -> Result<()> {
+        match tag {
+            EXTENSION_ROOM_ID => {
+                let Ok(bytes) = <[u8; 16]>::try_from(value) else {
+                    bail!("room id extension must be 16 bytes, got {}", value.len());
+                };
+                self.room_id = Some(RoomId(bytes));
+            }
+            EXTENSION_REPLY_ROUTE => {
+                self.reply_route = Some(std::str::from_utf8(value)?.parse::<RecordKey>()?);
+            }
+            EXTENSION_FILE_CHUNK => {
+                self.file_chunk = Some(FileChunkHeader::decode(value)?);
+            }
+            EXTENSION_SEQUENCE => {
+                let Ok(bytes) = <[u8; 8]>::try_from(value) else {
+                    bail!("sequence extension must be 8 bytes, got {}", value.len());
+                };
+                self.sequence = Some(u64::from_be_bytes(bytes));
+            }
+            _ => {}
+        }
+        Ok(())
+    }
+}
+
+/// A message envelope before signing.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct Envelope {
+    pub kind: MessageKind,
+    pub message_id: MessageId,
+    pub sender: PublicKey,
+    pub timestamp_ms: u64,
+    pub extensions: Extensions,
+    pub body: Vec<u8>,
+}
+
+/// An envelope decoded from the wire along with its signature and the signed bytes.
+#[derive(Debug)]
+pub struct DecodedEnvelope<'a> {
+    pub envelope: Envelope,
+    pub signature: Signature,
+    pub signed: &'a [u8],
+}
+
+impl Envelope {
+    /// Encode every field covered by the signature.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if a field is longer than its length prefix can describe.
+    pub fn encode(&self) -> Result<Vec<u8>> {
+        let mut out = Vec::with_capacity(64 + self.body.len());
+        out.push(ENVELOPE_VERSION);
+        out.push(self.kind.to_byte());
+        out.extend_from_slice(&self.message_id.0);
+        write_str16(&mut out, &self.sender.to_string(), "sender")?;
+        out.extend_from_slice(&self.timestamp_ms.to_be_bytes());
+        let extensions = self.extensions.encode()?;
+        let Ok(extension_count) = u16::try_from(extensions.len()) else {
+            bail!("envelope has too many extensions ({})", extensions.len());
+        };
+        out.extend_from_slice(&extension_count.to_be_bytes());
+        for (tag, value) in &extensions {
+            let Ok(len) = u16::try_from(value.len()) else {
+                bail!(
+                    "extension {} is {} bytes, over the {} byte limit",
+                    tag,
+                    value.len(),
+                    u16::MAX
+                );
+            };
+            out.push(*tag);
+            out.extend_from_slice(&len.to_be_bytes());
+            out.extend_from_slice(value);
+        }
+        write_bytes32(&mut out, &self.body)?;
+        Ok(out)
+    }
+
+    /// Encode the envelope followed by its signature.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if a field is longer than its length prefix can describe.
+    pub fn encode_signed(&self, signature: &Signature) -> Result<Vec<u8>> {
+        let mut out = self.encode()?;
+        write_str16(&mut out, &signature.to_string(), "signature")?;
+        Ok(out)
+    }
+}
+
+/// Decode a signed envelope without verifying the signature.
+///
+/// # Errors
+///
+/// Returns an error if the version is unsupported or the payload is truncated or malformed.
+pub fn decode_signed(payload: &[u8]) -> Result<DecodedEnvelope<'_>> {
+    let mut reader = Reader::new(payload);
+    let version = reader.u8()?;
+    if version != ENVELOPE_VERSION {
+        bail!(
+            "unsupported envelope version {} (this build understands version {})",
+            version,
+            ENVELOPE_VERSION
+        );
+    }
+    let kind = MessageKind::from_byte(reader.u8()?);
+    let message_id = MessageId::from_slice(reader.take(16)?)?;
+    let sender = reader.str16()?.parse::<PublicKey>()?;
+    let timestamp_ms = reader.u64()?;
+    let extension_count = reader.u16()?;
+    let mut extensions = Extensions::default();
+    for _ in 0..extension_count {
+        let tag = reader.u8()?;
+        let len = reader.u16()?;
+        extensions.decode_one(tag, reader.take(usize::from(len))?)?;
+    }
+    let body = reader.bytes32()?.to_vec();
+    let signed = &payload[..reader.position()];
+    let signature = reader.str16()?.parse::<Signature>()?;
+    if !reader.is_empty() {
+        bail!("envelope has trailing bytes");
+    }
+
+    Ok(DecodedEnvelope {
+        envelope: Envelope {
+            kind,
+            message_id,
+            sender,
+            timestamp_ms,
+            extensions,
+            body,
+        },
+        signature,
+        signed,
+    })
+}
+
+fn write_hex(f: &mut fmt::Formatter<'_>, bytes: &[u8]) -> fmt::Result {
+    for byte in bytes {
+        write!(f, "{byte:02x}")?;
+    }
+    Ok(())
+}
+
+fn parse_hex16(value: &str, what: &str) -> Result<[u8; 16]> {
+    if value.len() != 32 || !value.is_ascii() {
+        bail!("Invalid {} '{}'.", what, value);
+    }
+    let mut bytes = [0u8; 16];
+    for (index, byte) in bytes.iter_mut().enumerate() {
+        *byte = u8::from_str_radix(&value[index * 2..index * 2 + 2], 16)
+            .map_err(|_parse| eyre::eyre!("Invalid {} '{}'.", what, value))?;
+    }
+    Ok(bytes)
+}
+
+fn write_str16(out: &mut Vec<u8>, value: &str, what: &str) -> Result<()> {
+    let Ok(len) = u16::try_from(value.len()) else {
+        bail!(
+            "{} is {} bytes, over the {} byte limit",
+            what,
+            value.len(),
+            u16::MAX
+        );
+    };
+    out.extend_from_slice(&len.to_be_bytes());
+    out.extend_from_slice(value.as_bytes());
+    Ok(())
+}
+
+fn write_bytes32(out: &mut Vec<u8>, value: &[u8]) -> Result<()> {
+    let Ok(len) = u32::try_from(value.len()) else {
+        bail!(
+            "body is {} bytes, over the {} byte limit",
+            value.len(),
+            u32::MAX
+        );
+    };
+    out.extend_from_slice(&len.to_be_bytes());
+    out.extend_from_slice(value);
+    Ok(())
+}
+
+struct Reader<'a> {
+    data: &'a [u8],
+    position: usize,
+}
+
+impl<'a> Reader<'a> {
+    fn new(data: &'a [u8]) -> Self {
+        Self { data, position: 0 }
+    }
+
+    fn position(&self) -> usize {
+        self.position
+    }
+
+    fn is_empty(&self) -> bool {
+        self.position >= self.data.len()
+    }
+
+    fn take(&mut self, len: usize) -> Result<&'a [u8]> {
+        let end = self.position.saturating_add(len);
+        let Some(slice) = self.data.get(self.position..end) else {
+            bail!("envelope is truncated");
+        };
+        self.position = end;
+        Ok(slice)
+    }
+
+    fn u8(&mut self) -> Result<u8> {
+        Ok(self.take(1)?[0])
+    }
+
+    fn u16(&mut self) -> Result<u16> {
+        let bytes = self.take(2)?;
+        Ok(u16::from_be_bytes([bytes[0], bytes[1]]))
+    }
+
+    fn u32(&mut self) -> Result<u32> {
+        let bytes = self.take(4)?;
+        Ok(u32::from_be_bytes([bytes[0], bytes[1], bytes[2], bytes[3]]))
+    }
+
+    fn u64(&mut self) -> Result<u64> {
+        let mut bytes = [0u8; 8];
+        bytes.copy_from_slice(self.take(8)?);
+        Ok(u64::from_be_bytes(bytes))
+    }
+
+    fn str16(&mut self) -> Result<&'a str> {
+        let len = self.u16()?;
+        Ok(std::str::from_utf8(self.take(usize::from(len))?)?)
+    }
+
+    fn bytes32(&mut self) -> Result<&'a [u8]> {
+        let len = self.u32()?;
+        self.take(len as usize)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn sender() -> PublicKey {
+        format!("VLD0:{}", "A".repeat(43))
+            .parse()
+            .expect("sample key should parse")
+    }
+
+    fn signature() -> Signature {
+        format!("VLD0:{}", "A".repeat(86))
+            .parse()
+            .expect("sample signature should parse")
+    }
+
+    fn envelope() -> Envelope {
+        Envelope {
+            kind: MessageKind::FileChunk,
+            message_id: MessageId([7; 16]),
+            sender: sender(),
+            timestamp_ms: 1_700_000_000_000,
+            extensions: Extensions {
+                room_id: Some(RoomId([9; 16])),
+                reply_route: None,
+                file_chunk: Some(FileChunkHeader {
+                    index: 2,
+                    count: 5,
+                    chunk_size: 4,
+                    total_size: 18,
+                    content_hash: "abc123".to_owned(),
+                    file_name: "notes é.txt".to_owned(),
+                }),
+                sequence: Some(42),
+            },
+            body: b"body".to_vec(),
+        }
+    }
+
+    #[test]
+    fn signed_envelope_round_trips() -> Result<()> {
+        let envelope = envelope();
+        let payload = envelope.encode_signed(&signature())?;
+        let decoded = decode_signed(&payload)?;
+        assert_eq!(decoded.envelope, envelope);
+        assert_eq!(decoded.signature, signature());
+        assert_eq!(decoded.signed, envelope.encode()?.as_slice());
+        Ok(())
+    }
+
+    #[test]
+    fn every_truncation_is_rejected() -> Result<()> {
+        let payload = envelope().encode_signed(&signature())?;
+        for len in 0..payload.len() {
+            decode_signed(&payload[..len]).expect_err("truncated payload should not decode");
+        }
+        Ok(())
+    }
+
+    #[test]
+    fn trailing_bytes_are_rejected() -> Result<()> {
+        let mut payload = envelope().encode_signed(&signature())?;
+        payload.push(0);
+        decode_signed(&payload).expect_err("payload should be rejected");
+        Ok(())
+    }
+
+    #[test]
+    fn malformed_signature_is_rejected() -> Result<()> {
+        let mut payload = envelope().encode()?;
+        write_str16(&mut payload, "VLD0:not a signature", "signature")?;
+        decode_signed(&payload).expect_err("payload should be rejected");
+        Ok(())
+    }
+
+    #[test]
+    fn tampering_changes_the_signed_bytes() -> Result<()> {
+        let original = envelope().encode()?;
+        let mut tampered = envelope();
+        tampered.body = b"bodz".to_vec();
+        let payload = tampered.encode_signed(&signature())?;
+        let decoded = decode_signed(&payload)?;
+        assert_ne!(decoded.signed, original.as_slice());
+        Ok(())
+    }
+
+    #[test]
+    fn unknown_version_is_rejected() -> Result<()> {
+        let mut payload = envelope().encode_signed(&signature())?;
+        payload[0] = ENVELOPE_VERSION + 1;
+        decode_signed(&payload).expect_err("payload should be rejected");
+        Ok(())
+    }
+
+    #[test]
+    fn unknown_extensions_are_skipped() -> Result<()> {
+        let mut payload = Vec::new();
+        payload.push(ENVELOPE_VERSION);
+        payload.push(MessageKind::Chat.to_byte());
+        payload.extend_from_slice(&[1; 16]);
+        write_str16(&mut payload, &sender().to_string(), "sender")?;
+        payload.extend_from_slice(&5u64.to_be_bytes());
+        payload.extend_from_slice(&1u16.to_be_bytes());
+        payload.push(200);
+        payload.extend_from_slice(&3u16.to_be_bytes());
+        payload.extend_from_slice(b"new");
+        write_bytes32(&mut payload, b"hi")?;
+        write_str16(&mut payload, &signature().to_string(), "signature")?;
+
+        let decoded = decode_signed(&payload)?;
+        assert_eq!(decoded.envelope.extensions, Extensions::default());
+        assert_eq!(decoded.envelope.body, b"hi");
+        Ok(())
+    }
+
+    #[test]
+    fn over_long_fields_are_errors_not_truncated() {
+        let mut envelope = envelope();
+        if let Some(file_chunk) = &mut envelope.extensions.file_chunk {
+            file_chunk.file_name = "é".repeat(40_000);
+        }
+        envelope
+            .encode()
+            .expect_err("over-long file name should not encode");
+
+        let mut out = Vec::new();
+        write_str16(&mut out, &"x".repeat(usize::from(u16::MAX) + 1), "field")
+            .expect_err("over-long field should not encode");
+        assert!(out.is_empty());
+    }
+
+    #[test]
+    fn generated_room_ids_differ() {
+        assert_ne!(RoomId::generate(), RoomId::generate());
+    }
+
+    #[test]
+    fn ids_round_trip_through_hex() -> Result<()> {
+        let id = MessageId([0xab; 16]);
+        assert_eq!(id.to_string().parse::<MessageId>()?, id);
+        "abc"
+            .parse::<RoomId>()
+            .expect_err("short id should not parse");
+        "zz".repeat(16)
+            .parse::<RoomId>()
+            .expect_err("non-hex id should not parse");
+        Ok(())
+    }
+}
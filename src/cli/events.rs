@@ -0,0 +1,149 @@
+//! JSON Lines output for long-running commands such as `route listen` and `send chat`.
+//!
+//! These commands print as things happen rather than returning one response. When a
+//! JSON output format is asked for explicitly, each event is written to stdout as one
+//! compact JSON object per line and human-readable notices move to stderr, so scripts
+//! can read stdout line by line.
+//!
+//! While the daemon runs a client's command, notices are captured and relayed to the
+//! client instead of being printed into the daemon's log.
+
+use crate::cli::InvokeContext;
+use crate::cli::output_format::OutputFormatArg;
+use facet::Facet;
+use std::fmt::Display;
+use std::sync::Mutex;
+use tokio::sync::mpsc::UnboundedSender;
+
+/// Where notices go instead of stdout or stderr; set while the daemon runs a command.
+static NOTE_SINK: Mutex<Option<UnboundedSender<String>>> = Mutex::new(None);
+
+/// Send every notice to `sink` instead of printing it, or print again with `None`.
+#[cfg(unix)]
+pub(crate) fn capture_notes(sink: Option<UnboundedSender<String>>) {
+    if let Ok(mut guard) = NOTE_SINK.lock() {
+        *guard = sink;
+    }
+}
+
+/// How a long-running command reports what happens.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub enum EventOutput {
+    /// Human-readable lines on stdout.
+    #[default]
+    Text,
+    /// One JSON object per event on stdout; notices on stderr.
+    JsonLines,
+}
+
+impl EventOutput {
+    /// JSON Lines when `--output-format json` or `pretty-json` was given.
+    ///
+    /// `auto` stays on text even when stdout is not a terminal, so existing scripts that
+    /// read listener output keep working.
+    #[must_use]
+    pub fn for_context(context: &InvokeContext) -> Self {
+        match context.output_format() {
+            Some(OutputFormatArg::Json | OutputFormatArg::PrettyJson) => Self::JsonLines,
+            _ => Self::Text,
+        }
+    }
+
+    #[must_use]
+    pub fn is_json(self) -> bool {
+        self == Self::JsonLines
+    }
+
+    /// Print a human-readable notice that is not one of the command's events.
+    pub fn note(self, text: impl Display) {
+        if let Ok(guard) = NOTE_SINK.lock()
+            && let Some(sink) = guard.as_ref()
+        {
+            let _ = sink.send(text.to_string());
+            return;
+        }
+        match self {
+            Self::Text => println!("{text}"),
+            Self::JsonLines => eprintln!("{text}"),
+        }
+    }
+
+    /// Print an event as a JSON line, or `text` when writing human-readable output.
+    pub fn event<T>(self, event: &T, text: impl Display)
+    where
+        T: for<'a> Facet<'a>,
+    {
+        match self {
+            Self::Text => println!("{text}"),
+            Self::JsonLines => match facet_json::to_string(event) {
+                Ok(line) => println!("{line}"),
+                Err(error) => eprintln!("Failed to serialize event: {error}"),
+            },
+        }
+    }
+}
+
+/// The listener published its route and is waiting for messages.
+#[derive(Clone, Debug, PartialEq, Eq, Facet)]
+pub struct ReadyEvent {
+    pub event: String,
+    pub route: String,
+    pub record_key: String,
+    pub profile: String,
+}
+
+/// The listener's private route died or was due for rotation, and a new one was
+/// published.
+#[derive(Clone, Debug, PartialEq, Eq, Facet)]
+pub struct RouteRepublishedEvent {
+    pub event: String,
+    pub route: String,
+    pub record_key: String,
+    /// `dead`, `interval`, or `messages`.
+    pub reason: String,
+}
+
+/// A chat message was received.
+#[derive(Clone, Debug, PartialEq, Eq, Facet)]
+pub struct MessageEvent {
+    pub event: String,
+    pub message_id: String,
+    pub sender: String,
+    /// The sender's known-user name, if they are one.
+    pub known_user: Option<String>,
+    /// The local route the message arrived on, when known.
+    pub route: Option<String>,
+    /// The room the message was sent to, by local name or id.
+    pub room: Option<String>,
+    pub sequence: Option<u64>,
+    pub timestamp_ms: u64,
+    pub body: String,
+}
+
+/// The listener stopped and marked its route offline.
+#[derive(Clone, Debug, PartialEq, Eq, Facet)]
+pub struct StoppedEvent {
+    pub event: String,
+    pub route: String,
+    pub messages: usize,
+}
+
+/// One line read by `send chat` in JSON mode.
+#[derive(Clone, Debug, PartialEq, Eq, Facet)]
+pub struct SendLineInput {
+    pub message: String,
+}
+
+/// The outcome of sending one message in JSON mode.
+#[derive(Clone, Debug, PartialEq, Eq, Facet)]
+pub struct SendResultEvent {
+    pub event: String,
+    /// Input line number, counted from 1; absent for `--message`.
+    pub line: Option<usize>,
+    pub ok: bool,
+    /// `sent`, `delivered`, `unconfirmed`, `mailboxed`, or `queued`.
+    pub delivery: Option<String>,
+    /// Outbox entry holding the message when it was queued.
+    pub outbox_id: Option<u64>,
+    pub error: Option<String>,
+}
@@ -0,0 +1,413 @@
+//! Chunked file transfer over private routes.
+//!
+//! Veilid app messages are capped well below the size of most files, so `send file`
+//! splits a file into [`FILE_CHUNK_SIZE`] pieces, each sealed in its own
+//! [`MessageKind::FileChunk`](crate::cli::envelope::MessageKind::FileChunk) envelope
+//! whose [`FileChunkHeader`] carries the file name, sizes, and content hash.
+//!
+//! Receivers write chunks into `downloads/.partial/<sender>_<hash>.part` under the
+//! profile and note each received index in a sibling `.chunks` file, so an interrupted
+//! transfer continues where it stopped. The first chunk's sizes and file name are kept
+//! in a `.header` file and later chunks must agree with them. Once every chunk is
+//! present the content hash is checked and the file is moved into `downloads`.
+//! Listeners only accept chunks from known users, and each sender may have at most
+//! [`MAX_OPEN_TRANSFERS_PER_SENDER`] unfinished transfers.
+
+use crate::cli::app_state::ProfileHome;
+use crate::cli::envelope::FileChunkHeader;
+use eyre::Result;
+use eyre::bail;
+use std::collections::BTreeSet;
+use std::io::BufReader;
+use std::io::Seek;
+use std::io::SeekFrom;
+use std::io::Write;
+use std::path::Path;
+use std::path::PathBuf;
+use veilid_core::CRYPTO_KIND_VLD0;
+use veilid_core::PublicKey;
+use veilid_core::VeilidAPI;
+
+/// Bytes of file data per chunk, leaving room for the envelope inside an app message.
+pub const FILE_CHUNK_SIZE: usize = 24 * 1024;
+/// Largest chunk size accepted from a sender.
+const MAX_CHUNK_SIZE: u32 = 30 * 1024;
+/// Largest file that can be sent or received.
+pub const MAX_FILE_SIZE: u64 = 4 * 1024 * 1024 * 1024;
+/// Subdirectory of the downloads folder holding incomplete transfers.
+const PARTIAL_DIR: &str = ".partial";
+/// Unfinished incoming transfers kept per sender; chunks of further files are refused.
+pub const MAX_OPEN_TRANSFERS_PER_SENDER: usize = 4;
+
+/// What happened to a received file chunk.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum ChunkOutcome {
+    /// The chunk was stored; `received` of `count` chunks are now present.
+    Stored { received: u32, count: u32 },
+    /// The chunk was already stored by an earlier attempt.
+    Duplicate,
+    /// The last chunk arrived and the file passed its hash check.
+    Completed { path: PathBuf },
+}
+
+/// Hash a file's contents the same way on both ends of a transfer, reading it in
+/// pieces rather than loading it whole.
+///
+/// # Errors
+///
+/// Returns an error if the VLD0 cryptosystem is unavailable or the file cannot be read.
+pub async fn content_hash(api: &VeilidAPI, path: &Path) -> Result<String> {
+    let crypto = api.crypto()?;
+    let vcrypto = crypto
+        .get_async(CRYPTO_KIND_VLD0)
+        .ok_or_else(|| eyre::eyre!("VLD0 cryptosystem unavailable"))?;
+    let mut reader = BufReader::new(std::fs::File::open(path)?);
+    Ok(vcrypto.generate_hash_reader(&mut reader).await?.to_string())
+}
+
+/// Store one chunk of an incoming file, finishing the download when it is the last
+/// one missing.
+///
+/// # Errors
+///
+/// Returns an error if the header is inconsistent with the chunk or with earlier chunks
+/// of the same transfer, the partial download cannot be written, or the finished file
+/// does not match its content hash. A file that fails the hash check is discarded; the
+/// sender restarts from the first chunk when its last one goes unconfirmed.
+pub async fn receive_chunk(
+    api: &VeilidAPI,
+    profile_home: &ProfileHome,
+    sender: &PublicKey,
+    header: &FileChunkHeader,
+    data: &[u8],
+) -> Result<ChunkOutcome> {
+    validate_header(header, data)?;
+
+    let downloads_dir = profile_home.profile_downloads_dir();
+    let partial_dir = downloads_dir.join(PARTIAL_DIR);
+    std::fs::create_dir_all(&partial_dir)?;
+    let sender = sender.to_string();
+    let stem = partial_stem(&sender, &header.content_hash);
+    let part_path = partial_dir.join(format!("{stem}.part"));
+    let chunks_path = partial_dir.join(format!("{stem}.chunks"));
+    let header_path = partial_dir.join(format!("{stem}.header"));
+    if !header_path.exists()
+        && open_transfers(&partial_dir, &sender)? >= MAX_OPEN_TRANSFERS_PER_SENDER
+    {
+        bail!(
+            "the sender already has {} unfinished transfers",
+            MAX_OPEN_TRANSFERS_PER_SENDER
+        );
+    }
+    check_consistent_header(&header_path, header)?;
+
+    let mut received = read_received_chunks(&chunks_path)?;
+    if received.contains(&header.index) {
+        return Ok(ChunkOutcome::Duplicate);
+    }
+
+    let mut part = std::fs::OpenOptions::new()
+        .create(true)
+        .truncate(false)
+        .write(true)
+        .open(&part_path)?;
+    part.seek(SeekFrom::Start(
+        u64::from(header.index) * u64::from(header.chunk_size),
+    ))?;
+    part.write_all(data)?;
+    part.flush()?;
+    drop(part);
+
+    let mut chunks_file = std::fs::OpenOptions::new()
+        .create(true)
+        .append(true)
+        .open(&chunks_path)?;
+    writeln!(chunks_file, "{}", header.index)?;
+    let _ = received.insert(header.index);
+
+    let received_count = u32::try_from(received.len())?;
+    if received_count < header.count {
+        return Ok(ChunkOutcome::Stored {
+            received: received_count,
+            count: header.count,
+        });
+    }
+
+    let actual_hash = content_hash(api, &part_path).await?;
+    if actual_hash != header.content_hash {
+        let _ = std::fs::remove_file(&part_path);
+        let _ = std::fs::remove_file(&chunks_path);
+        let _ = std::fs::remove_file(&header_path);
+        bail!(
+            "'{}' did not match its content hash and was discarded",
+            header.file_name
+        );
+    }
+
+    let destination = unique_destination(&downloads_dir, &sanitize_file_name(&header.file_name));
+    std::fs::rename(&part_path, &destination)?;
+    let _ = std::fs::remove_file(&chunks_path);
+    let _ = std::fs::remove_file(&header_path);
+    Ok(ChunkOutcome::Completed { path: destination })
+}
+
+/// Record the first chunk's header for a transfer, or check a later chunk against it.
+/// Count the unfinished transfers from `sender` in `partial_dir`, by their `.header` files.
+fn open_transfers(partial_dir: &Path, sender: &str) -> Result<usize> {
+    let prefix = partial_stem(sender, "");
+    let mut open = 0;
+    for entry in std::fs::read_dir(partial_dir)? {
+        let name = entry?.file_name();
+        let name = name.to_string_lossy();
+        if name.starts_with(&prefix) && name.ends_with(".header") {
+            open += 1;
+        }
+    }
+    Ok(open)
+}
+
+fn check_consistent_header(path: &Path, header: &FileChunkHeader) -> Result<()> {
+    let expected = format!(
+        "{}\t{}\t{}\t{}",
+        header.count, header.chunk_size, header.total_size, header.file_name
+    );
+    if path.exists() {
+        if std::fs::read_to_string(path)? != expected {
+            bail!(
+                "file chunk {} does not match earlier chunks of '{}'",
+                header.index,
+                header.file_name
+            );
+        }
+        return Ok(());
+    }
+    std::fs::write(path, expected)?;
+    Ok(())
+}
+
+fn validate_header(header: &FileChunkHeader, data: &[u8]) -> Result<()> {
+    if header.count == 0 || header.index >= header.count {
+        bail!(
+            "file chunk {} of {} is out of range",
+            header.index,
+            header.count
+        );
+    }
+    if header.chunk_size == 0 || header.chunk_size > MAX_CHUNK_SIZE {
+        bail!("file chunk size {} is not supported", header.chunk_size);
+    }
+    if header.total_size > MAX_FILE_SIZE {
+        bail!(
+            "file size {} is over the {} byte limit",
+            header.total_size,
+            MAX_FILE_SIZE
+        );
+    }
+    let full_chunks = u64::from(header.count - 1) * u64::from(header.chunk_size);
+    if header.total_size <= full_chunks
+        || header.total_size > full_chunks + u64::from(header.chunk_size)
+    {
+        bail!(
+            "file size {} does not match {} chunks of {} bytes",
+            header.total_size,
+            header.count,
+            header.chunk_size
+        );
+    }
+    let expected_len = if header.index + 1 == header.count {
+        header.total_size - full_chunks
+    } else {
+        u64::from(header.chunk_size)
+    };
+    if u64::try_from(data.len())? != expected_len {
+        bail!(
+            "file chunk {} holds {} bytes, expected {}",
+            header.index,
+            data.len(),
+            expected_len
+        );
+    }
+    Ok(())
+}
+
+fn read_received_chunks(path: &Path) -> Result<BTreeSet<u32>> {
+    if !path.exists() {
+        return Ok(BTreeSet::new());
+    }
+    std::fs::read_to_string(path)?
+        .lines()
+        .filter(|line| !line.trim().is_empty())
+        .map(|line| Ok(line.trim().parse::<u32>()?))
+        .collect()
+}
+
+/// A filesystem-safe name for a transfer's partial files, derived from its sender and
+/// hash so two senders of the same content never share a partial download.
+fn partial_stem(sender: &str, content_hash: &str) -> String {
+    format!("{sender}_{content_hash}")
+        .chars()
+        .map(|c| if c.is_ascii_alphanumeric() { c } else { '_' })
+        .collect()
+}
+
+/// Keep only the final component of a sender-supplied name, without path separators
+/// or characters Windows rejects.
+fn sanitize_file_name(file_name: &str) -> String {
+    let last = file_name.rsplit(['/', '\\']).next().unwrap_or_default();
+    let cleaned = last
+        .chars()
+        .filter(|c| !c.is_control() && !matches!(c, ':' | '*' | '?' | '"' | '<' | '>' | '|'))
+        .collect::<String>();
+    let cleaned = cleaned.trim().trim_matches('.').to_owned();
+    if cleaned.is_empty() {
+        "download".to_owned()
+    } else {
+        cleaned
+    }
+}
+
+/// `dir/name`, or `dir/name (n).ext` for the first `n` that is not taken.
+fn unique_destination(dir: &Path, file_name: &str) -> PathBuf {
+    let candidate = dir.join(file_name);
+    if !candidate.exists() {
+        return candidate;
+    }
+    let path = Path::new(file_name);
+    let stem = path.file_stem().map_or_else(
+        || file_name.to_owned(),
+        |stem| stem.to_string_lossy().into_owned(),
+    );
+    let extension = path
+        .extension()
+        .map(|ext| ext.to_string_lossy().into_owned());
+    (1u32..)
+        .map(|n| match &extension {
+            Some(extension) => dir.join(format!("{stem} ({n}).{extension}")),
+            None => dir.join(format!("{stem} ({n})")),
+        })
+        .find(|path| !path.exists())
+        .unwrap_or(candidate)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn header(index: u32, count: u32, total_size: u64) -> FileChunkHeader {
+        FileChunkHeader {
+            index,
+            count,
+            chunk_size: 4,
+            total_size,
+            content_hash: "VLD0:hash".to_owned(),
+            file_name: "notes.txt".to_owned(),
+        }
+    }
+
+    #[test]
+    fn valid_chunks_pass() -> Result<()> {
+        validate_header(&header(0, 3, 10), b"abcd")?;
+        validate_header(&header(2, 3, 10), b"ij")?;
+        validate_header(&header(0, 1, 4), b"abcd")?;
+        Ok(())
+    }
+
+    #[test]
+    fn out_of_range_index_is_rejected() {
+        validate_header(&header(3, 3, 10), b"ab").expect_err("index past count");
+        validate_header(&header(0, 0, 0), b"").expect_err("zero chunks");
+    }
+
+    #[test]
+    fn chunk_size_must_be_supported() {
+        let mut oversized = header(0, 1, 4);
+        oversized.chunk_size = MAX_CHUNK_SIZE + 1;
+        validate_header(&oversized, b"abcd").expect_err("chunk over the size limit");
+        let mut empty = header(0, 1, 4);
+        empty.chunk_size = 0;
+        validate_header(&empty, b"abcd").expect_err("zero chunk size");
+    }
+
+    #[test]
+    fn total_size_must_fit_chunk_count() {
+        validate_header(&header(0, 3, 8), b"abcd").expect_err("too small for three chunks");
+        validate_header(&header(0, 3, 13), b"abcd").expect_err("too large for three chunks");
+    }
+
+    #[test]
+    fn total_size_is_bounded() {
+        let mut huge = header(0, u32::MAX, MAX_FILE_SIZE + 1);
+        huge.chunk_size = MAX_CHUNK_SIZE;
+        validate_header(&huge, b"abcd").expect_err("file over the limit");
+    }
+
+    #[test]
+    fn chunk_length_must_match_header() {
+        validate_header(&header(0, 3, 10), b"abc").expect_err("short middle chunk");
+        validate_header(&header(2, 3, 10), b"ijk").expect_err("long last chunk");
+    }
+
+    #[test]
+    fn later_chunks_must_match_first_header() -> Result<()> {
+        let dir = tempfile::tempdir()?;
+        let path = dir.path().join("transfer.header");
+        check_consistent_header(&path, &header(0, 3, 10))?;
+        check_consistent_header(&path, &header(1, 3, 10))?;
+        check_consistent_header(&path, &header(2, 3, 11)).expect_err("changed total size");
+        let mut renamed = header(2, 3, 10);
+        renamed.file_name = "other.txt".to_owned();
+        check_consistent_header(&path, &renamed).expect_err("changed file name");
+        Ok(())
+    }
+
+    #[test]
+    fn partial_files_are_keyed_by_sender() {
+        let alice = partial_stem("VLD0:alice", "VLD0:hash");
+        let bob = partial_stem("VLD0:bob", "VLD0:hash");
+        assert_ne!(alice, bob);
+        assert!(alice.chars().all(|c| c.is_ascii_alphanumeric() || c == '_'));
+    }
+
+    #[test]
+    fn open_transfers_are_counted_per_sender() -> Result<()> {
+        let dir = tempfile::tempdir()?;
+        for hash in ["VLD0:one", "VLD0:two"] {
+            let stem = partial_stem("VLD0:alice", hash);
+            std::fs::write(dir.path().join(format!("{stem}.header")), "")?;
+            std::fs::write(dir.path().join(format!("{stem}.part")), "")?;
+        }
+        let stem = partial_stem("VLD0:bob", "VLD0:one");
+        std::fs::write(dir.path().join(format!("{stem}.header")), "")?;
+        assert_eq!(open_transfers(dir.path(), "VLD0:alice")?, 2);
+        assert_eq!(open_transfers(dir.path(), "VLD0:bob")?, 1);
+        assert_eq!(open_transfers(dir.path(), "VLD0:carol")?, 0);
+        Ok(())
+    }
+
+    #[test]
+    fn file_names_lose_paths_and_reserved_characters() {
+        assert_eq!(sanitize_file_name("../../etc/passwd"), "passwd");
+        assert_eq!(sanitize_file_name("C:\\Users\\me\\a.txt"), "a.txt");
+        assert_eq!(sanitize_file_name("what?<is>|this*.txt"), "whatisthis.txt");
+        assert_eq!(sanitize_file_name("tab\tname"), "tabname");
+        assert_eq!(sanitize_file_name(".."), "download");
+        assert_eq!(sanitize_file_name(" .hidden. "), "hidden");
+        assert_eq!(sanitize_file_name("dir/"), "download");
+        assert_eq!(sanitize_file_name(""), "download");
+    }
+
+    #[test]
+    fn taken_destinations_get_a_number() -> Result<()> {
+        let dir = tempfile::tempdir()?;
+        assert_eq!(
+            unique_destination(dir.path(), "a.txt"),
+            dir.path().join("a.txt")
+        );
+        std::fs::write(dir.path().join("a.txt"), "")?;
+        assert_eq!(
+            unique_destination(dir.path(), "a.txt"),
+            dir.path().join("a (1).txt")
+        );
+        Ok(())
+    }
+}
@@ -0,0 +1,74 @@
+use crate::cli::InvokeContext;
+use crate::cli::ToArgs;
+use crate::cli::app_state;
+use crate::cli::app_state::HookEntry;
+use crate::cli::hooks::DEFAULT_HOOK_TIMEOUT_SECS;
+use arbitrary::Arbitrary;
+use eyre::Result;
+use facet::Facet;
+use figue as args;
+use std::fmt;
+
+#[derive(Facet, Arbitrary, Debug, PartialEq)]
+pub struct HookAddArgs {
+    #[facet(args::positional)]
+    pub name: String,
+
+    /// Shell command run for each incoming chat message. The body is on stdin; sender,
+    /// route, and room are in `VETCHRICORE_*` environment variables.
+    #[facet(args::positional)]
+    pub command: String,
+
+    /// Seconds the command may run before it is killed (default 10).
+    #[facet(args::named)]
+    pub timeout: Option<u64>,
+}
+
+#[derive(Clone, Debug, PartialEq, Eq, Facet)]
+pub struct HookAddResponse {
+    name: String,
+    timeout_secs: u64,
+}
+
+impl fmt::Display for HookAddResponse {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "Added hook '{}' with a {}s timeout.",
+            self.name, self.timeout_secs
+        )
+    }
+}
+
+impl HookAddArgs {
+    #[expect(
+        clippy::unused_async,
+        reason = "command handlers use async invoke signature consistently"
+    )]
+    pub async fn invoke(self, context: &InvokeContext) -> Result<HookAddResponse> {
+        let timeout_secs = self.timeout.unwrap_or(DEFAULT_HOOK_TIMEOUT_SECS);
+        app_state::add_hook(
+            context.profile_home(),
+            HookEntry {
+                name: self.name.clone(),
+                command: self.command,
+                timeout_secs,
+            },
+        )?;
+        Ok(HookAddResponse {
+            name: self.name,
+            timeout_secs,
+        })
+    }
+}
+
+impl ToArgs for HookAddArgs {
+    fn to_args(&self) -> Vec<std::ffi::OsString> {
+        let mut args = vec![self.name.clone().into(), self.command.clone().into()];
+        if let Some(timeout) = self.timeout {
+            args.push("--timeout".into());
+            args.push(timeout.to_string().into());
+        }
+        args
+    }
+}
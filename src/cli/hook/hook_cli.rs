@@ -0,0 +1,59 @@
+use crate::cli::InvokeContext;
+use crate::cli::ToArgs;
+use crate::cli::hook::add::HookAddArgs;
+use crate::cli::hook::list::HookListArgs;
+use crate::cli::hook::remove::HookRemoveArgs;
+use crate::cli::response::CliResponse;
+use arbitrary::Arbitrary;
+use eyre::Result;
+use facet::Facet;
+use figue as args;
+use std::ffi::OsString;
+
+#[derive(Facet, Arbitrary, Debug, PartialEq)]
+pub struct HookArgs {
+    #[facet(args::subcommand)]
+    pub command: HookCommand,
+}
+
+#[derive(Facet, Arbitrary, Debug, PartialEq)]
+#[repr(u8)]
+pub enum HookCommand {
+    Add(HookAddArgs),
+    List(HookListArgs),
+    Remove(HookRemoveArgs),
+}
+
+impl HookArgs {
+    /// # Errors
+    ///
+    /// Returns an error if the selected hook subcommand fails.
+    pub async fn invoke(self, context: &InvokeContext) -> Result<CliResponse> {
+        Ok(match self.command {
+            HookCommand::Add(args) => args.invoke(context).await?.into(),
+            HookCommand::List(args) => args.invoke(context).await?.into(),
+            HookCommand::Remove(args) => args.invoke(context).await?.into(),
+        })
+    }
+}
+
+impl ToArgs for HookArgs {
+    fn to_args(&self) -> Vec<OsString> {
+        let mut args = Vec::new();
+        match &self.command {
+            HookCommand::Add(add_args) => {
+                args.push("add".into());
+                args.extend(add_args.to_args());
+            }
+            HookCommand::List(list_args) => {
+                args.push("list".into());
+                args.extend(list_args.to_args());
+            }
+            HookCommand::Remove(remove_args) => {
+                args.push("remove".into());
+                args.extend(remove_args.to_args());
+            }
+        }
+        args
+    }
+}
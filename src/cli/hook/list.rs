@@ -0,0 +1,64 @@
+use crate::cli::InvokeContext;
+use crate::cli::ToArgs;
+use crate::cli::app_state;
+use arbitrary::Arbitrary;
+use eyre::Result;
+use facet::Facet;
+use std::fmt;
+
+#[derive(Facet, Arbitrary, Debug, PartialEq, Default)]
+pub struct HookListArgs;
+
+#[derive(Clone, Debug, PartialEq, Eq, Facet)]
+pub struct HookListItem {
+    name: String,
+    command: String,
+    timeout_secs: u64,
+}
+
+#[derive(Clone, Debug, PartialEq, Eq, Facet)]
+pub struct HookListResponse {
+    hooks: Vec<HookListItem>,
+}
+
+impl fmt::Display for HookListResponse {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        if self.hooks.is_empty() {
+            return f.write_str("You have no hooks.");
+        }
+
+        for (index, hook) in self.hooks.iter().enumerate() {
+            if index > 0 {
+                writeln!(f)?;
+            }
+            write!(
+                f,
+                "{} ({}s): {}",
+                hook.name, hook.timeout_secs, hook.command
+            )?;
+        }
+        Ok(())
+    }
+}
+
+impl HookListArgs {
+    #[expect(
+        clippy::unused_async,
+        reason = "command handlers use async invoke signature consistently"
+    )]
+    pub async fn invoke(self, context: &InvokeContext) -> Result<HookListResponse> {
+        let hooks = app_state::list_hooks(context.profile_home())?;
+        Ok(HookListResponse {
+            hooks: hooks
+                .into_iter()
+                .map(|hook| HookListItem {
+                    name: hook.name,
+                    command: hook.command,
+                    timeout_secs: hook.timeout_secs,
+                })
+                .collect(),
+        })
+    }
+}
+
+impl ToArgs for HookListArgs {}
@@ -0,0 +1,6 @@
+pub(crate) mod add;
+mod hook_cli;
+pub(crate) mod list;
+pub(crate) mod remove;
+
+pub use hook_cli::*;
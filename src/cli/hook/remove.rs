@@ -0,0 +1,42 @@
+use crate::cli::InvokeContext;
+use crate::cli::ToArgs;
+use crate::cli::app_state;
+use arbitrary::Arbitrary;
+use eyre::Result;
+use facet::Facet;
+use figue as args;
+use std::fmt;
+
+#[derive(Facet, Arbitrary, Debug, PartialEq)]
+pub struct HookRemoveArgs {
+    #[facet(args::positional)]
+    pub name: String,
+}
+
+#[derive(Clone, Debug, PartialEq, Eq, Facet)]
+pub struct HookRemoveResponse {
+    name: String,
+}
+
+impl fmt::Display for HookRemoveResponse {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "Removed hook '{}'.", self.name)
+    }
+}
+
+impl HookRemoveArgs {
+    #[expect(
+        clippy::unused_async,
+        reason = "command handlers use async invoke signature consistently"
+    )]
+    pub async fn invoke(self, context: &InvokeContext) -> Result<HookRemoveResponse> {
+        app_state::remove_hook(context.profile_home(), &self.name)?;
+        Ok(HookRemoveResponse { name: self.name })
+    }
+}
+
+impl ToArgs for HookRemoveArgs {
+    fn to_args(&self) -> Vec<std::ffi::OsString> {
+        vec![self.name.clone().into()]
+    }
+}
@@ -0,0 +1,172 @@
+//! Shell hooks run for each chat message a listener accepts.
+//!
+//! Every hook configured with `hook add` is started through the platform shell with
+//! the message details in `VETCHRICORE_*` environment variables and the body on
+//! stdin. Hooks run in the background under a timeout, at most
+//! [`MAX_CONCURRENT_HOOKS`] at a time; a message arriving while every slot is busy
+//! skips its hooks. Failures are logged and never reach the listener.
+
+use crate::cli::app_state;
+use crate::cli::app_state::HookEntry;
+use crate::cli::app_state::ProfileHome;
+use eyre::Result;
+use eyre::bail;
+use std::process::Stdio;
+use std::sync::Arc;
+use std::time::Duration;
+use tokio::io::AsyncWriteExt;
+use tokio::process::Command;
+use tokio::sync::OwnedSemaphorePermit;
+use tokio::sync::Semaphore;
+use tracing::debug;
+use tracing::warn;
+
+/// Hook processes allowed to run at once across all hooks.
+pub const MAX_CONCURRENT_HOOKS: usize = 4;
+
+/// Seconds a hook may run when `hook add` is not given `--timeout`.
+pub const DEFAULT_HOOK_TIMEOUT_SECS: u64 = 10;
+
+/// The details of an accepted chat message handed to hooks.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct HookMessage {
+    pub message_id: String,
+    /// Public key text of the sender.
+    pub sender_pubkey: String,
+    /// Known-user name of the sender, when known.
+    pub known_user: Option<String>,
+    /// Local route the message arrived on.
+    pub route: Option<String>,
+    pub room: Option<String>,
+    pub timestamp_ms: u64,
+    pub body: String,
+}
+
+/// Runs a profile's hooks for inbound messages.
+#[derive(Clone, Debug)]
+pub struct HookRunner {
+    profile: String,
+    hooks: Arc<[HookEntry]>,
+    slots: Arc<Semaphore>,
+}
+
+impl HookRunner {
+    /// Snapshot the hooks configured for a profile.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if hook data cannot be read or parsed.
+    pub fn load(profile_home: &ProfileHome) -> Result<Self> {
+        Ok(Self {
+            profile: profile_home.profile().to_owned(),
+            hooks: app_state::list_hooks(profile_home)?.into(),
+            slots: Arc::new(Semaphore::new(MAX_CONCURRENT_HOOKS)),
+        })
+    }
+
+    /// Start every hook for `message` in the background.
+    ///
+    /// Returns immediately; hooks that cannot get a slot are skipped with a warning.
+    pub fn run(&self, message: &HookMessage) {
+        for hook in self.hooks.iter() {
+            let Ok(permit) = Arc::clone(&self.slots).try_acquire_owned() else {
+                warn!(
+                    hook = %hook.name,
+                    message_id = %message.message_id,
+                    "skipped hook; {MAX_CONCURRENT_HOOKS} hooks are already running"
+                );
+                continue;
+            };
+            let hook = hook.clone();
+            let profile = self.profile.clone();
+            let message = message.clone();
+            drop(tokio::spawn(async move {
+                run_hook(&hook, &profile, &message, permit).await;
+            }));
+        }
+    }
+}
+
+async fn run_hook(
+    hook: &HookEntry,
+    profile: &str,
+    message: &HookMessage,
+    _permit: OwnedSemaphorePermit,
+) {
+    let timeout = Duration::from_secs(hook.timeout_secs);
+    match tokio::time::timeout(timeout, spawn_and_wait(hook, profile, message)).await {
+        Ok(Ok(())) => debug!(hook = %hook.name, message_id = %message.message_id, "hook finished"),
+        Ok(Err(error)) => warn!(
+            hook = %hook.name,
+            message_id = %message.message_id,
+            %error,
+            "hook failed"
+        ),
+        Err(_) => warn!(
+            hook = %hook.name,
+            message_id = %message.message_id,
+            "hook killed after {}s timeout",
+            hook.timeout_secs
+        ),
+    }
+}
+
+/// Run one hook to completion; the child is killed if this future is dropped.
+async fn spawn_and_wait(hook: &HookEntry, profile: &str, message: &HookMessage) -> Result<()> {
+    let mut command = shell_command(&hook.command);
+    command
+        .env("VETCHRICORE_PROFILE", profile)
+        .env("VETCHRICORE_MESSAGE_ID", &message.message_id)
+        .env("VETCHRICORE_SENDER_PUBKEY", &message.sender_pubkey)
+        .env(
+            "VETCHRICORE_SENDER",
+            message
+                .known_user
+                .as_deref()
+                .unwrap_or(&message.sender_pubkey),
+        )
+        .env(
+            "VETCHRICORE_KNOWN_USER",
+            message.known_user.as_deref().unwrap_or_default(),
+        )
+        .env(
+            "VETCHRICORE_ROUTE",
+            message.route.as_deref().unwrap_or_default(),
+        )
+        .env(
+            "VETCHRICORE_ROOM",
+            message.room.as_deref().unwrap_or_default(),
+        )
+        .env("VETCHRICORE_TIMESTAMP_MS", message.timestamp_ms.to_string())
+        .stdin(Stdio::piped())
+        .stdout(Stdio::null())
+        .stderr(Stdio::piped())
+        .kill_on_drop(true);
+    let mut child = command.spawn()?;
+    if let Some(mut stdin) = child.stdin.take() {
+        // A hook that ignores stdin may exit before reading it; that is not a failure.
+        if let Err(error) = stdin.write_all(message.body.as_bytes()).await {
+            debug!(hook = %hook.name, %error, "hook did not read the message body");
+        }
+    }
+    let output = child.wait_with_output().await?;
+    if !output.status.success() {
+        let stderr = String::from_utf8_lossy(&output.stderr);
+        bail!("exited with {}: {}", output.status, stderr.trim());
+    }
+    Ok(())
+}
+
+#[cfg(unix)]
+fn shell_command(command: &str) -> Command {
+    let mut shell = Command::new("sh");
+    shell.arg("-c").arg(command);
+    shell
+}
+
+#[cfg(windows)]
+fn shell_command(command: &str) -> Command {
+    let mut shell = Command::new("cmd");
+    shell.arg("/C").arg(command);
+    shell
+}
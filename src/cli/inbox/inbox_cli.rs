@@ -0,0 +1,54 @@
+use crate::cli::InvokeContext;
+use crate::cli::ToArgs;
+use crate::cli::inbox::policy::InboxPolicyArgs;
+use crate::cli::inbox::quarantine::InboxQuarantineArgs;
+use crate::cli::response::CliResponse;
+use arbitrary::Arbitrary;
+use eyre::Result;
+use facet::Facet;
+use figue as args;
+use std::ffi::OsString;
+
+#[derive(Facet, Arbitrary, Debug, PartialEq)]
+pub struct InboxArgs {
+    #[facet(args::subcommand)]
+    pub command: InboxCommand,
+}
+
+#[derive(Facet, Arbitrary, Debug, PartialEq)]
+#[repr(u8)]
+pub enum InboxCommand {
+    /// Show or set how messages from unknown senders are treated.
+    Policy(InboxPolicyArgs),
+    /// Review messages held from unknown senders.
+    Quarantine(InboxQuarantineArgs),
+}
+
+impl InboxArgs {
+    /// # Errors
+    ///
+    /// Returns an error if the selected inbox subcommand fails.
+    pub async fn invoke(self, context: &InvokeContext) -> Result<CliResponse> {
+        match self.command {
+            InboxCommand::Policy(args) => args.invoke(context).await,
+            InboxCommand::Quarantine(args) => args.invoke(context).await,
+        }
+    }
+}
+
+impl ToArgs for InboxArgs {
+    fn to_args(&self) -> Vec<OsString> {
+        let mut args = Vec::new();
+        match &self.command {
+            InboxCommand::Policy(policy_args) => {
+                args.push("policy".into());
+                args.extend(policy_args.to_args());
+            }
+            InboxCommand::Quarantine(quarantine_args) => {
+                args.push("quarantine".into());
+                args.extend(quarantine_args.to_args());
+            }
+        }
+        args
+    }
+}
@@ -0,0 +1,5 @@
+mod inbox_cli;
+pub(crate) mod policy;
+pub(crate) mod quarantine;
+
+pub use inbox_cli::*;
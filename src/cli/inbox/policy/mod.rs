@@ -0,0 +1,5 @@
+mod policy_cli;
+pub(crate) mod set;
+pub(crate) mod show;
+
+pub use policy_cli::*;
@@ -0,0 +1,52 @@
+use crate::cli::InvokeContext;
+use crate::cli::ToArgs;
+use crate::cli::inbox::policy::set::InboxPolicySetArgs;
+use crate::cli::inbox::policy::show::InboxPolicyShowArgs;
+use crate::cli::response::CliResponse;
+use arbitrary::Arbitrary;
+use eyre::Result;
+use facet::Facet;
+use figue as args;
+use std::ffi::OsString;
+
+#[derive(Facet, Arbitrary, Debug, PartialEq)]
+pub struct InboxPolicyArgs {
+    #[facet(args::subcommand)]
+    pub command: InboxPolicyCommand,
+}
+
+#[derive(Facet, Arbitrary, Debug, PartialEq)]
+#[repr(u8)]
+pub enum InboxPolicyCommand {
+    Show(InboxPolicyShowArgs),
+    Set(InboxPolicySetArgs),
+}
+
+impl InboxPolicyArgs {
+    /// # Errors
+    ///
+    /// Returns an error if the selected policy subcommand fails.
+    pub async fn invoke(self, context: &InvokeContext) -> Result<CliResponse> {
+        Ok(match self.command {
+            InboxPolicyCommand::Show(args) => args.invoke(context).await?.into(),
+            InboxPolicyCommand::Set(args) => args.invoke(context).await?.into(),
+        })
+    }
+}
+
+impl ToArgs for InboxPolicyArgs {
+    fn to_args(&self) -> Vec<OsString> {
+        let mut args = Vec::new();
+        match &self.command {
+            InboxPolicyCommand::Show(show_args) => {
+                args.push("show".into());
+                args.extend(show_args.to_args());
+            }
+            InboxPolicyCommand::Set(set_args) => {
+                args.push("set".into());
+                args.extend(set_args.to_args());
+            }
+        }
+        args
+    }
+}
@@ -0,0 +1,47 @@
+use crate::cli::InvokeContext;
+use crate::cli::ToArgs;
+use crate::cli::app_state;
+use crate::cli::app_state::SenderPolicy;
+use arbitrary::Arbitrary;
+use eyre::Result;
+use facet::Facet;
+use figue as args;
+use std::fmt;
+
+#[derive(Facet, Arbitrary, Debug, PartialEq)]
+pub struct InboxPolicySetArgs {
+    /// One of allow-all, known-only, or quarantine.
+    #[facet(args::positional)]
+    pub policy: String,
+}
+
+#[derive(Clone, Debug, PartialEq, Eq, Facet)]
+pub struct InboxPolicySetResponse {
+    policy: String,
+}
+
+impl fmt::Display for InboxPolicySetResponse {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "Unknown-sender policy set to {}.", self.policy)
+    }
+}
+
+impl InboxPolicySetArgs {
+    #[expect(
+        clippy::unused_async,
+        reason = "command handlers use async invoke signature consistently"
+    )]
+    pub async fn invoke(self, context: &InvokeContext) -> Result<InboxPolicySetResponse> {
+        let policy = self.policy.parse::<SenderPolicy>()?;
+        app_state::set_sender_policy(context.profile_home(), policy)?;
+        Ok(InboxPolicySetResponse {
+            policy: policy.as_str().to_owned(),
+        })
+    }
+}
+
+impl ToArgs for InboxPolicySetArgs {
+    fn to_args(&self) -> Vec<std::ffi::OsString> {
+        vec![self.policy.clone().into()]
+    }
+}
@@ -0,0 +1,39 @@
+use crate::cli::InvokeContext;
+use crate::cli::ToArgs;
+use crate::cli::app_state;
+use arbitrary::Arbitrary;
+use eyre::Result;
+use facet::Facet;
+use std::fmt;
+
+#[derive(Facet, Arbitrary, Debug, PartialEq, Default)]
+pub struct InboxPolicyShowArgs;
+
+#[derive(Clone, Debug, PartialEq, Eq, Facet)]
+pub struct InboxPolicyShowResponse {
+    policy: String,
+    blocked_senders: usize,
+}
+
+impl fmt::Display for InboxPolicyShowResponse {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        writeln!(f, "Unknown senders: {}", self.policy)?;
+        write!(f, "Blocked senders: {}", self.blocked_senders)
+    }
+}
+
+impl InboxPolicyShowArgs {
+    #[expect(
+        clippy::unused_async,
+        reason = "command handlers use async invoke signature consistently"
+    )]
+    pub async fn invoke(self, context: &InvokeContext) -> Result<InboxPolicyShowResponse> {
+        let profile_home = context.profile_home();
+        Ok(InboxPolicyShowResponse {
+            policy: app_state::sender_policy(profile_home)?.as_str().to_owned(),
+            blocked_senders: app_state::list_blocked_senders(profile_home)?.len(),
+        })
+    }
+}
+
+impl ToArgs for InboxPolicyShowArgs {}
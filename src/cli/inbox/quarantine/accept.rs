@@ -0,0 +1,101 @@
+use crate::cli::InvokeContext;
+use crate::cli::ToArgs;
+use crate::cli::app_state;
+use crate::cli::app_state::ChatHistoryEntry;
+use crate::cli::app_state::MessageDirection;
+use arbitrary::Arbitrary;
+use eyre::Result;
+use eyre::bail;
+use facet::Facet;
+use figue as args;
+use std::fmt;
+
+#[derive(Facet, Arbitrary, Debug, PartialEq)]
+pub struct InboxQuarantineAcceptArgs {
+    #[facet(args::positional)]
+    pub id: u64,
+
+    /// Add the sender as a known user with this name and file the message in their history.
+    #[facet(args::named)]
+    pub known_user: Option<String>,
+}
+
+#[derive(Clone, Debug, PartialEq, Eq, Facet)]
+pub struct InboxQuarantineAcceptResponse {
+    id: u64,
+    sender: String,
+    known_user: Option<String>,
+    body: String,
+}
+
+impl fmt::Display for InboxQuarantineAcceptResponse {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match &self.known_user {
+            Some(known_user) => write!(
+                f,
+                "Accepted message {}; {} is now a known user.\n{known_user}> {}",
+                self.id, self.sender, self.body
+            ),
+            None => write!(
+                f,
+                "Accepted message {}.\n{}> {}",
+                self.id, self.sender, self.body
+            ),
+        }
+    }
+}
+
+impl InboxQuarantineAcceptArgs {
+    /// Release a quarantined message, optionally trusting its sender from now on.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the message does not exist, the known-user name is taken, or
+    /// profile data cannot be persisted.
+    #[expect(
+        clippy::unused_async,
+        reason = "command handlers use async invoke signature consistently"
+    )]
+    pub async fn invoke(self, context: &InvokeContext) -> Result<InboxQuarantineAcceptResponse> {
+        let profile_home = context.profile_home();
+        if let Some(known_user) = &self.known_user {
+            let Some(entry) = app_state::list_quarantine(profile_home)?
+                .into_iter()
+                .find(|entry| entry.id == self.id)
+            else {
+                bail!("Quarantined message {} does not exist.", self.id);
+            };
+            app_state::add_known_user(profile_home, known_user, entry.sender)?;
+        }
+        let entry = app_state::take_quarantined_message(profile_home, self.id)?;
+        if let Some(known_user) = &self.known_user {
+            app_state::append_chat_history(
+                profile_home,
+                known_user,
+                &ChatHistoryEntry {
+                    timestamp_ms: entry.received_at_ms,
+                    direction: MessageDirection::Inbound,
+                    message_id: entry.message_id,
+                    body: entry.body.clone(),
+                },
+            )?;
+        }
+        Ok(InboxQuarantineAcceptResponse {
+            id: entry.id,
+            sender: entry.sender.to_string(),
+            known_user: self.known_user,
+            body: entry.body,
+        })
+    }
+}
+
+impl ToArgs for InboxQuarantineAcceptArgs {
+    fn to_args(&self) -> Vec<std::ffi::OsString> {
+        let mut args = vec![self.id.to_string().into()];
+        if let Some(known_user) = &self.known_user {
+            args.push("--known-user".into());
+            args.push(known_user.clone().into());
+        }
+        args
+    }
+}
@@ -0,0 +1,66 @@
+use crate::cli::InvokeContext;
+use crate::cli::ToArgs;
+use crate::cli::app_state;
+use crate::cli::chat::history::format_timestamp_ms;
+use arbitrary::Arbitrary;
+use eyre::Result;
+use facet::Facet;
+use std::fmt;
+
+#[derive(Facet, Arbitrary, Debug, PartialEq, Default)]
+pub struct InboxQuarantineListArgs;
+
+#[derive(Clone, Debug, PartialEq, Eq, Facet)]
+pub struct InboxQuarantineListItem {
+    id: u64,
+    received_at: String,
+    sender: String,
+    body: String,
+}
+
+#[derive(Clone, Debug, PartialEq, Eq, Facet)]
+pub struct InboxQuarantineListResponse {
+    messages: Vec<InboxQuarantineListItem>,
+}
+
+impl fmt::Display for InboxQuarantineListResponse {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        if self.messages.is_empty() {
+            return f.write_str("Nothing is quarantined.");
+        }
+
+        for (index, message) in self.messages.iter().enumerate() {
+            if index > 0 {
+                writeln!(f)?;
+            }
+            write!(
+                f,
+                "{} [{}] from {}: {}",
+                message.id, message.received_at, message.sender, message.body
+            )?;
+        }
+        Ok(())
+    }
+}
+
+impl InboxQuarantineListArgs {
+    #[expect(
+        clippy::unused_async,
+        reason = "command handlers use async invoke signature consistently"
+    )]
+    pub async fn invoke(self, context: &InvokeContext) -> Result<InboxQuarantineListResponse> {
+        Ok(InboxQuarantineListResponse {
+            messages: app_state::list_quarantine(context.profile_home())?
+                .into_iter()
+                .map(|entry| InboxQuarantineListItem {
+                    id: entry.id,
+                    received_at: format_timestamp_ms(entry.received_at_ms),
+                    sender: entry.sender.to_string(),
+                    body: entry.body,
+                })
+                .collect(),
+        })
+    }
+}
+
+impl ToArgs for InboxQuarantineListArgs {}
@@ -0,0 +1,6 @@
+pub(crate) mod accept;
+pub(crate) mod list;
+mod quarantine_cli;
+pub(crate) mod reject;
+
+pub use quarantine_cli::*;
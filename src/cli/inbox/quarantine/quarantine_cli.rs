@@ -0,0 +1,59 @@
+use crate::cli::InvokeContext;
+use crate::cli::ToArgs;
+use crate::cli::inbox::quarantine::accept::InboxQuarantineAcceptArgs;
+use crate::cli::inbox::quarantine::list::InboxQuarantineListArgs;
+use crate::cli::inbox::quarantine::reject::InboxQuarantineRejectArgs;
+use crate::cli::response::CliResponse;
+use arbitrary::Arbitrary;
+use eyre::Result;
+use facet::Facet;
+use figue as args;
+use std::ffi::OsString;
+
+#[derive(Facet, Arbitrary, Debug, PartialEq)]
+pub struct InboxQuarantineArgs {
+    #[facet(args::subcommand)]
+    pub command: InboxQuarantineCommand,
+}
+
+#[derive(Facet, Arbitrary, Debug, PartialEq)]
+#[repr(u8)]
+pub enum InboxQuarantineCommand {
+    List(InboxQuarantineListArgs),
+    Accept(InboxQuarantineAcceptArgs),
+    Reject(InboxQuarantineRejectArgs),
+}
+
+impl InboxQuarantineArgs {
+    /// # Errors
+    ///
+    /// Returns an error if the selected quarantine subcommand fails.
+    pub async fn invoke(self, context: &InvokeContext) -> Result<CliResponse> {
+        Ok(match self.command {
+            InboxQuarantineCommand::List(args) => args.invoke(context).await?.into(),
+            InboxQuarantineCommand::Accept(args) => args.invoke(context).await?.into(),
+            InboxQuarantineCommand::Reject(args) => args.invoke(context).await?.into(),
+        })
+    }
+}
+
+impl ToArgs for InboxQuarantineArgs {
+    fn to_args(&self) -> Vec<OsString> {
+        let mut args = Vec::new();
+        match &self.command {
+            InboxQuarantineCommand::List(list_args) => {
+                args.push("list".into());
+                args.extend(list_args.to_args());
+            }
+            InboxQuarantineCommand::Accept(accept_args) => {
+                args.push("accept".into());
+                args.extend(accept_args.to_args());
+            }
+            InboxQuarantineCommand::Reject(reject_args) => {
+                args.push("reject".into());
+                args.extend(reject_args.to_args());
+            }
+        }
+        args
+    }
+}
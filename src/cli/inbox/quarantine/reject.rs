@@ -0,0 +1,64 @@
+use crate::cli::InvokeContext;
+use crate::cli::ToArgs;
+use crate::cli::app_state;
+use arbitrary::Arbitrary;
+use eyre::Result;
+use facet::Facet;
+use figue as args;
+use std::fmt;
+
+#[derive(Facet, Arbitrary, Debug, PartialEq)]
+pub struct InboxQuarantineRejectArgs {
+    #[facet(args::positional)]
+    pub id: u64,
+
+    /// Also block the sender so later messages are dropped silently.
+    #[facet(args::named, default)]
+    pub block: bool,
+}
+
+#[derive(Clone, Debug, PartialEq, Eq, Facet)]
+pub struct InboxQuarantineRejectResponse {
+    id: u64,
+    sender: String,
+    blocked: bool,
+}
+
+impl fmt::Display for InboxQuarantineRejectResponse {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "Rejected message {}.", self.id)?;
+        if self.blocked {
+            write!(f, " {} is now blocked.", self.sender)?;
+        }
+        Ok(())
+    }
+}
+
+impl InboxQuarantineRejectArgs {
+    #[expect(
+        clippy::unused_async,
+        reason = "command handlers use async invoke signature consistently"
+    )]
+    pub async fn invoke(self, context: &InvokeContext) -> Result<InboxQuarantineRejectResponse> {
+        let profile_home = context.profile_home();
+        let entry = app_state::take_quarantined_message(profile_home, self.id)?;
+        if self.block && !app_state::list_blocked_senders(profile_home)?.contains(&entry.sender) {
+            app_state::block_sender(profile_home, &entry.sender)?;
+        }
+        Ok(InboxQuarantineRejectResponse {
+            id: entry.id,
+            sender: entry.sender.to_string(),
+            blocked: self.block,
+        })
+    }
+}
+
+impl ToArgs for InboxQuarantineRejectArgs {
+    fn to_args(&self) -> Vec<std::ffi::OsString> {
+        let mut args = vec![self.id.to_string().into()];
+        if self.block {
+            args.push("--block".into());
+        }
+        args
+    }
+}
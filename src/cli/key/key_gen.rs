@@ -2,6 +2,7 @@ use crate::cli::InvokeContext;
 use crate::cli::ToArgs;
 use crate::cli::app_state;
 use crate::cli::veilid_runtime::printing_update_callback;
+use crate::cli::veilid_runtime::shutdown_api;
 use crate::cli::veilid_runtime::start_api_for_profile;
 use arbitrary::Arbitrary;
 use eyre::Result;
@@ -44,7 +45,7 @@ impl KeyGenArgs {
             .get_async(CRYPTO_KIND_VLD0)
             .ok_or_else(|| eyre::eyre!("VLD0 cryptosystem unavailable"))?;
         let keypair = vcrypto.generate_keypair().await;
-        api.shutdown().await;
+        shutdown_api(api).await;
 
         app_state::store_keypair(profile_home, &keypair)?;
         Ok(KeyGenResponse {
@@ -0,0 +1,81 @@
+use crate::cli::InvokeContext;
+use crate::cli::ToArgs;
+use crate::cli::app_state;
+use crate::cli::app_state::ProfileHome;
+use arbitrary::Arbitrary;
+use eyre::Result;
+use eyre::bail;
+use facet::Facet;
+use figue as args;
+use std::fmt;
+use veilid_core::PublicKey;
+
+#[derive(Facet, Arbitrary, Debug, PartialEq)]
+pub struct KnownUserBlockArgs {
+    /// Known-user name or public key to block.
+    #[facet(args::positional)]
+    pub target: String,
+}
+
+#[derive(Clone, Debug, PartialEq, Eq, Facet)]
+pub struct KnownUserBlockResponse {
+    target: String,
+    public_key: String,
+}
+
+impl fmt::Display for KnownUserBlockResponse {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        if self.target == self.public_key {
+            write!(f, "Messages from {} will be dropped.", self.public_key)
+        } else {
+            write!(
+                f,
+                "Messages from {} ({}) will be dropped.",
+                self.target, self.public_key
+            )
+        }
+    }
+}
+
+impl KnownUserBlockArgs {
+    /// Add the sender to the profile blocklist so listeners drop its messages silently.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the target is neither a known user nor a public key, is
+    /// already blocked, or the blocklist cannot be persisted.
+    #[expect(
+        clippy::unused_async,
+        reason = "command handlers use async invoke signature consistently"
+    )]
+    pub async fn invoke(self, context: &InvokeContext) -> Result<KnownUserBlockResponse> {
+        let profile_home = context.profile_home();
+        let pubkey = resolve_sender(profile_home, &self.target)?;
+        app_state::block_sender(profile_home, &pubkey)?;
+        Ok(KnownUserBlockResponse {
+            target: self.target,
+            public_key: pubkey.to_string(),
+        })
+    }
+}
+
+/// Resolve a known-user name, or failing that a raw public key.
+///
+/// # Errors
+///
+/// Returns an error if the target is neither, or known users cannot be loaded.
+pub(crate) fn resolve_sender(profile_home: &ProfileHome, target: &str) -> Result<PublicKey> {
+    if let Some(pubkey) = app_state::known_user_public_key(profile_home, target)? {
+        return Ok(pubkey);
+    }
+    let Ok(pubkey) = target.parse::<PublicKey>() else {
+        bail!("'{}' is neither a known user nor a public key.", target);
+    };
+    Ok(pubkey)
+}
+
+impl ToArgs for KnownUserBlockArgs {
+    fn to_args(&self) -> Vec<std::ffi::OsString> {
+        vec![self.target.clone().into()]
+    }
+}
@@ -1,10 +1,14 @@
 use crate::cli::InvokeContext;
 use crate::cli::ToArgs;
 use crate::cli::known_user::add::KnownUserAddArgs;
+use crate::cli::known_user::block::KnownUserBlockArgs;
 use crate::cli::known_user::list::KnownUserListArgs;
 use crate::cli::known_user::remove::KnownUserRemoveArgs;
 use crate::cli::known_user::rename::KnownUserRenameArgs;
 use crate::cli::known_user::route::KnownUserRouteArgs;
+use crate::cli::known_user::status::KnownUserStatusArgs;
+use crate::cli::known_user::unblock::KnownUserUnblockArgs;
+use crate::cli::known_user::watch::KnownUserWatchArgs;
 use crate::cli::response::CliResponse;
 use arbitrary::Arbitrary;
 use eyre::Result;
@@ -28,6 +32,13 @@ pub enum KnownUserCommand {
     Rename(KnownUserRenameArgs),
     Remove(KnownUserRemoveArgs),
     Route(KnownUserRouteArgs),
+    /// Check whether known users are publishing a route, without sending a message.
+    Status(KnownUserStatusArgs),
+    /// Print when known users come online or go offline, until Ctrl+C.
+    Watch(KnownUserWatchArgs),
+    /// Drop messages from a known user or public key without notice.
+    Block(KnownUserBlockArgs),
+    Unblock(KnownUserUnblockArgs),
 }
 
 impl KnownUserArgs {
@@ -43,6 +54,13 @@ impl KnownUserArgs {
             KnownUserCommand::Rename(args) => args.invoke(context).await?.into(),
             KnownUserCommand::Remove(args) => args.invoke(context).await?.into(),
             KnownUserCommand::Route(args) => args.invoke(context).await?,
+            KnownUserCommand::Status(args) => args.invoke(context).await?.into(),
+            KnownUserCommand::Watch(args) => {
+                args.invoke(context).await?;
+                CliResponse::empty()
+            }
+            KnownUserCommand::Block(args) => args.invoke(context).await?.into(),
+            KnownUserCommand::Unblock(args) => args.invoke(context).await?.into(),
         })
     }
 }
@@ -79,6 +97,22 @@ impl ToArgs for KnownUserArgs {
                 args.push("route".into());
                 args.extend(route_args.to_args());
             }
+            KnownUserCommand::Status(status_args) => {
+                args.push("status".into());
+                args.extend(status_args.to_args());
+            }
+            KnownUserCommand::Watch(watch_args) => {
+                args.push("watch".into());
+                args.extend(watch_args.to_args());
+            }
+            KnownUserCommand::Block(block_args) => {
+                args.push("block".into());
+                args.extend(block_args.to_args());
+            }
+            KnownUserCommand::Unblock(unblock_args) => {
+                args.push("unblock".into());
+                args.extend(unblock_args.to_args());
+            }
         }
         args
     }
@@ -1,8 +1,12 @@
 pub(crate) mod add;
+pub(crate) mod block;
 mod known_user_cli;
 pub(crate) mod list;
 pub(crate) mod remove;
 pub(crate) mod rename;
 pub(crate) mod route;
+pub(crate) mod status;
+pub(crate) mod unblock;
+pub(crate) mod watch;
 
 pub use known_user_cli::*;
@@ -0,0 +1,239 @@
+use crate::cli::InvokeContext;
+use crate::cli::ToArgs;
+use crate::cli::app_state;
+use crate::cli::chat::history::format_timestamp_ms;
+use crate::cli::events::EventOutput;
+use crate::cli::route::listen::wait_for_public_internet_ready;
+use crate::cli::veilid_runtime::shutdown_api;
+use crate::cli::veilid_runtime::start_api_for_profile;
+use arbitrary::Arbitrary;
+use eyre::Result;
+use eyre::bail;
+use facet::Facet;
+use figue as args;
+use std::fmt;
+use std::sync::Arc;
+use std::sync::atomic::AtomicBool;
+use std::sync::atomic::Ordering;
+use veilid_core::RecordKey;
+use veilid_core::RoutingContext;
+use veilid_core::VeilidUpdate;
+
+#[derive(Facet, Arbitrary, Debug, PartialEq)]
+pub struct KnownUserStatusArgs {
+    /// Known user to check; every known user is checked when omitted.
+    #[facet(args::positional, default)]
+    pub name: Option<String>,
+}
+
+#[derive(Clone, Debug, PartialEq, Eq, Facet)]
+pub struct KnownUserRouteStatus {
+    record_key: String,
+    online: bool,
+    seq: Option<u32>,
+    last_seen: Option<String>,
+    error: Option<String>,
+}
+
+#[derive(Clone, Debug, PartialEq, Eq, Facet)]
+pub struct KnownUserStatusItem {
+    name: String,
+    online: bool,
+    routes: Vec<KnownUserRouteStatus>,
+}
+
+#[derive(Clone, Debug, PartialEq, Eq, Facet)]
+pub struct KnownUserStatusResponse {
+    known_users: Vec<KnownUserStatusItem>,
+}
+
+impl fmt::Display for KnownUserStatusResponse {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        if self.known_users.is_empty() {
+            return f.write_str("You have no known users. A new dawn awaits.");
+        }
+
+        for (index, known_user) in self.known_users.iter().enumerate() {
+            if index > 0 {
+                writeln!(f)?;
+            }
+            let state = if known_user.online {
+                "online"
+            } else {
+                "offline"
+            };
+            write!(f, "{}: {state}", known_user.name)?;
+            if known_user.routes.is_empty() {
+                write!(f, " (no route record keys)")?;
+            }
+            for route in &known_user.routes {
+                write!(f, "\n  {} ", route.record_key)?;
+                match &route.error {
+                    Some(error) => write!(f, "unreachable: {error}")?,
+                    None if route.online => write!(f, "online")?,
+                    None => write!(f, "offline")?,
+                }
+                if let Some(seq) = route.seq {
+                    write!(f, ", seq {seq}")?;
+                }
+                match &route.last_seen {
+                    Some(last_seen) => write!(f, ", last seen {last_seen}")?,
+                    None => write!(f, ", never seen online")?,
+                }
+            }
+        }
+        Ok(())
+    }
+}
+
+/// What a route record currently publishes.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub(crate) struct RoutePresence {
+    /// The record holds a non-empty route blob.
+    pub online: bool,
+    /// Sequence number of the route subkey, if it has ever been written.
+    pub seq: Option<u32>,
+}
+
+/// Read subkey 0 of a route record without importing the route.
+///
+/// # Errors
+///
+/// Returns an error if the record cannot be opened or read.
+pub(crate) async fn read_route_presence(
+    router: &RoutingContext,
+    key: &RecordKey,
+) -> Result<RoutePresence> {
+    let _ = router.open_dht_record(key.clone(), None).await?;
+    let value = router.get_dht_value(key.clone(), 0, true).await;
+    let _ = router.close_dht_record(key.clone()).await;
+
+    Ok(match value? {
+        Some(value) => RoutePresence {
+            online: !value.data().is_empty(),
+            seq: Some(value.seq()),
+        },
+        None => RoutePresence {
+            online: false,
+            seq: None,
+        },
+    })
+}
+
+impl KnownUserStatusArgs {
+    /// Report whether known users are publishing a route, without sending anything.
+    ///
+    /// A route record counts as online when its route subkey holds a non-empty blob.
+    /// Each time a record is seen online the time is remembered, so later checks can
+    /// report when an offline known user was last around. Notes go to stderr under a
+    /// JSON output format so stdout holds only the response.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the named known user does not exist, profile data cannot be
+    /// loaded, or Veilid cannot be started.
+    pub async fn invoke(self, context: &InvokeContext) -> Result<KnownUserStatusResponse> {
+        let profile_home = context.profile_home();
+        let known_users = app_state::list_known_users(profile_home)?;
+        let names = match &self.name {
+            Some(name) => {
+                if !known_users.iter().any(|entry| &entry.name == name) {
+                    bail!("Known user '{}' does not exist.", name);
+                }
+                vec![name.clone()]
+            }
+            None => known_users.into_iter().map(|entry| entry.name).collect(),
+        };
+        let mut targets = Vec::with_capacity(names.len());
+        for name in names {
+            let keys = app_state::route_keys_for_known_user(profile_home, &name)?;
+            targets.push((name, keys));
+        }
+        if targets.iter().all(|(_, keys)| keys.is_empty()) {
+            return Ok(KnownUserStatusResponse {
+                known_users: targets
+                    .into_iter()
+                    .map(|(name, _)| KnownUserStatusItem {
+                        name,
+                        online: false,
+                        routes: Vec::new(),
+                    })
+                    .collect(),
+            });
+        }
+
+        let public_internet_ready = Arc::new(AtomicBool::new(false));
+        let callback = {
+            let public_internet_ready = Arc::clone(&public_internet_ready);
+            Arc::new(move |update: VeilidUpdate| {
+                if let VeilidUpdate::Attachment(attachment) = update {
+                    public_internet_ready
+                        .store(attachment.public_internet_ready, Ordering::Release);
+                }
+            }) as crate::cli::veilid_runtime::UpdateCallback
+        };
+
+        let output = EventOutput::for_context(context);
+        let mut last_seen = app_state::route_last_seen(profile_home)?;
+        let api = start_api_for_profile(profile_home, true, callback).await?;
+        let router = match wait_for_public_internet_ready(&api, &public_internet_ready, output)
+            .await
+            .and_then(|()| Ok(api.routing_context()?.with_default_safety()?))
+        {
+            Ok(router) => router,
+            Err(error) => {
+                shutdown_api(api).await;
+                return Err(error);
+            }
+        };
+
+        let now_ms = u64::try_from(chrono::Utc::now().timestamp_millis()).unwrap_or_default();
+        let mut seen = Vec::new();
+        let mut known_users = Vec::with_capacity(targets.len());
+        for (name, keys) in targets {
+            let mut routes = Vec::with_capacity(keys.len());
+            for key in keys {
+                let presence = read_route_presence(&router, &key).await;
+                if let Ok(RoutePresence { online: true, .. }) = presence {
+                    let _ = last_seen.insert(key.to_string(), now_ms);
+                    seen.push(key.clone());
+                }
+                let last_seen = last_seen
+                    .get(&key.to_string())
+                    .copied()
+                    .map(format_timestamp_ms);
+                routes.push(match presence {
+                    Ok(presence) => KnownUserRouteStatus {
+                        record_key: key.to_string(),
+                        online: presence.online,
+                        seq: presence.seq,
+                        last_seen,
+                        error: None,
+                    },
+                    Err(error) => KnownUserRouteStatus {
+                        record_key: key.to_string(),
+                        online: false,
+                        seq: None,
+                        last_seen,
+                        error: Some(error.to_string()),
+                    },
+                });
+            }
+            known_users.push(KnownUserStatusItem {
+                name,
+                online: routes.iter().any(|route| route.online),
+                routes,
+            });
+        }
+
+        shutdown_api(api).await;
+        app_state::record_routes_seen(profile_home, &seen, now_ms)?;
+        Ok(KnownUserStatusResponse { known_users })
+    }
+}
+
+impl ToArgs for KnownUserStatusArgs {
+    fn to_args(&self) -> Vec<std::ffi::OsString> {
+        self.name.iter().map(Into::into).collect()
+    }
+}
@@ -0,0 +1,52 @@
+use crate::cli::InvokeContext;
+use crate::cli::ToArgs;
+use crate::cli::app_state;
+use crate::cli::known_user::block::resolve_sender;
+use arbitrary::Arbitrary;
+use eyre::Result;
+use facet::Facet;
+use figue as args;
+use std::fmt;
+
+#[derive(Facet, Arbitrary, Debug, PartialEq)]
+pub struct KnownUserUnblockArgs {
+    /// Known-user name or public key to unblock.
+    #[facet(args::positional)]
+    pub target: String,
+}
+
+#[derive(Clone, Debug, PartialEq, Eq, Facet)]
+pub struct KnownUserUnblockResponse {
+    public_key: String,
+}
+
+impl fmt::Display for KnownUserUnblockResponse {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "Messages from {} are no longer dropped.",
+            self.public_key
+        )
+    }
+}
+
+impl KnownUserUnblockArgs {
+    #[expect(
+        clippy::unused_async,
+        reason = "command handlers use async invoke signature consistently"
+    )]
+    pub async fn invoke(self, context: &InvokeContext) -> Result<KnownUserUnblockResponse> {
+        let profile_home = context.profile_home();
+        let pubkey = resolve_sender(profile_home, &self.target)?;
+        app_state::unblock_sender(profile_home, &pubkey)?;
+        Ok(KnownUserUnblockResponse {
+            public_key: pubkey.to_string(),
+        })
+    }
+}
+
+impl ToArgs for KnownUserUnblockArgs {
+    fn to_args(&self) -> Vec<std::ffi::OsString> {
+        vec![self.target.clone().into()]
+    }
+}
@@ -0,0 +1,235 @@
+use crate::cli::InvokeContext;
+use crate::cli::ToArgs;
+use crate::cli::app_state;
+use crate::cli::app_state::ProfileHome;
+use crate::cli::events::EventOutput;
+use crate::cli::route::listen::wait_for_public_internet_ready;
+use crate::cli::veilid_runtime::shutdown_api;
+use crate::cli::veilid_runtime::start_api_for_profile;
+use arbitrary::Arbitrary;
+use eyre::Result;
+use eyre::bail;
+use facet::Facet;
+use std::collections::BTreeSet;
+use std::collections::HashMap;
+use std::sync::Arc;
+use std::sync::atomic::AtomicBool;
+use std::sync::atomic::Ordering;
+use std::time::Duration;
+use tokio::sync::mpsc;
+use tracing::warn;
+use veilid_core::RecordKey;
+use veilid_core::RoutingContext;
+use veilid_core::ValueSubkeyRangeSet;
+use veilid_core::VeilidUpdate;
+
+/// How often every watched record is re-read and its watch renewed, in case a change
+/// notification was missed or a watch lapsed.
+const REFRESH_INTERVAL: Duration = Duration::from_secs(60);
+
+#[derive(Facet, Arbitrary, Debug, PartialEq, Default)]
+pub struct KnownUserWatchArgs;
+
+/// A change notification for subkey 0 of a watched route record.
+#[derive(Debug)]
+struct RouteChange {
+    key: RecordKey,
+    /// Whether the new value is a route blob, or `None` when the value was not
+    /// included and must be read again.
+    online: Option<bool>,
+}
+
+/// Online state of every watched route record, grouped by known user.
+struct Presence {
+    owners: HashMap<String, String>,
+    online: HashMap<String, bool>,
+}
+
+impl Presence {
+    fn is_user_online(&self, known_user: &str) -> bool {
+        self.owners
+            .iter()
+            .filter(|(_, owner)| *owner == known_user)
+            .any(|(key, _)| self.online.get(key).copied().unwrap_or(false))
+    }
+
+    /// Record a route's state and print a line if its owner came online or went offline.
+    fn update(&mut self, profile_home: &ProfileHome, key: &RecordKey, online: bool) {
+        let key_text = key.to_string();
+        let Some(known_user) = self.owners.get(&key_text).cloned() else {
+            return;
+        };
+        let was_online = self.is_user_online(&known_user);
+        let _ = self.online.insert(key_text, online);
+        if online {
+            record_seen(profile_home, key);
+        }
+        match (was_online, self.is_user_online(&known_user)) {
+            (false, true) => println!("{known_user} is now online."),
+            (true, false) => println!("{known_user} went offline."),
+            _ => {}
+        }
+    }
+}
+
+impl KnownUserWatchArgs {
+    /// Watch every known user's route records and print when they come online or go
+    /// offline, until Ctrl+C.
+    ///
+    /// Veilid notifies us when subkey 0 of a watched record changes; the records are
+    /// also re-read periodically so a missed notification only delays an event.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if no known user has route record keys, profile data cannot be
+    /// loaded, or Veilid cannot be started.
+    pub async fn invoke(self, context: &InvokeContext) -> Result<()> {
+        let profile_home = context.profile_home();
+        let mut owners = HashMap::new();
+        let mut keys = Vec::new();
+        for known_user in app_state::list_known_users(profile_home)? {
+            for key in app_state::route_keys_for_known_user(profile_home, &known_user.name)? {
+                let _ = owners.insert(key.to_string(), known_user.name.clone());
+                keys.push(key);
+            }
+        }
+        if keys.is_empty() {
+            bail!("None of your known users have route record keys to watch.");
+        }
+
+        let public_internet_ready = Arc::new(AtomicBool::new(false));
+        let (change_tx, mut change_rx) = mpsc::unbounded_channel::<RouteChange>();
+        let callback = {
+            let public_internet_ready = Arc::clone(&public_internet_ready);
+            Arc::new(move |update: VeilidUpdate| match update {
+                VeilidUpdate::Attachment(attachment) => {
+                    public_internet_ready
+                        .store(attachment.public_internet_ready, Ordering::Release);
+                }
+                VeilidUpdate::ValueChange(change) => {
+                    if change.subkeys.contains(0) {
+                        let _ = change_tx.send(RouteChange {
+                            key: change.key.clone(),
+                            online: change.value.as_ref().map(|value| !value.data().is_empty()),
+                        });
+                    }
+                }
+                _ => {}
+            }) as crate::cli::veilid_runtime::UpdateCallback
+        };
+
+        let api = start_api_for_profile(profile_home, true, callback).await?;
+        if let Err(error) =
+            wait_for_public_internet_ready(&api, &public_internet_ready, EventOutput::Text).await
+        {
+            shutdown_api(api).await;
+            return Err(error);
+        }
+        let router = api.routing_context()?.with_default_safety()?;
+
+        let mut presence = Presence {
+            owners,
+            online: HashMap::new(),
+        };
+        for key in &keys {
+            if let Err(error) = router.open_dht_record(key.clone(), None).await {
+                warn!(%error, %key, "failed to open route record for watching");
+                continue;
+            }
+            let online = read_online(&router, key).await.unwrap_or(false);
+            let _ = presence.online.insert(key.to_string(), online);
+            if online {
+                record_seen(profile_home, key);
+            }
+            watch_route(&router, key).await;
+        }
+        let known_users = presence.owners.values().cloned().collect::<BTreeSet<_>>();
+        for known_user in &known_users {
+            let state = if presence.is_user_online(known_user) {
+                "online"
+            } else {
+                "offline"
+            };
+            println!("{known_user} is {state}.");
+        }
+        println!(
+            "Watching {} route record keys. Press Ctrl+C to stop.",
+            keys.len()
+        );
+
+        let mut refresh = tokio::time::interval(REFRESH_INTERVAL);
+        refresh.reset();
+        loop {
+            tokio::select! {
+                _ = tokio::signal::ctrl_c() => {
+                    println!("Ctrl+C detected.");
+                    break;
+                }
+                Some(change) = change_rx.recv() => {
+                    let online = match change.online {
+                        Some(online) => online,
+                        None => {
+                            watch_route(&router, &change.key).await;
+                            read_online(&router, &change.key).await.unwrap_or(false)
+                        }
+                    };
+                    presence.update(profile_home, &change.key, online);
+                }
+                _ = refresh.tick() => {
+                    for key in &keys {
+                        if let Ok(online) = read_online(&router, key).await {
+                            presence.update(profile_home, key, online);
+                        }
+                        watch_route(&router, key).await;
+                    }
+                }
+            }
+        }
+
+        for key in &keys {
+            let _ = router.cancel_dht_watch(key.clone(), None).await;
+            let _ = router.close_dht_record(key.clone()).await;
+        }
+        shutdown_api(api).await;
+        Ok(())
+    }
+}
+
+/// Remember when a route was last seen online, for `known-user status`.
+fn record_seen(profile_home: &ProfileHome, key: &RecordKey) {
+    let now_ms = u64::try_from(chrono::Utc::now().timestamp_millis()).unwrap_or_default();
+    if let Err(error) = app_state::record_route_seen(profile_home, key, now_ms) {
+        warn!(%error, "failed to record route presence");
+    }
+}
+
+/// Whether an open route record currently holds a route blob.
+///
+/// Reads from the network rather than the local copy so a stale value is not reported.
+async fn read_online(router: &RoutingContext, key: &RecordKey) -> Result<bool> {
+    Ok(router
+        .get_dht_value(key.clone(), 0, true)
+        .await?
+        .is_some_and(|value| !value.data().is_empty()))
+}
+
+/// Ask Veilid to report changes to the route subkey of an open route record.
+///
+/// Watching again renews a watch that has lapsed.
+async fn watch_route(router: &RoutingContext, key: &RecordKey) {
+    match router
+        .watch_dht_values(
+            key.clone(),
+            Some(ValueSubkeyRangeSet::single(0)),
+            None,
+            None,
+        )
+        .await
+    {
+        Ok(true) => {}
+        Ok(false) => warn!(%key, "route record watch was not accepted"),
+        Err(error) => warn!(%error, %key, "failed to watch route record"),
+    }
+}
+
+impl ToArgs for KnownUserWatchArgs {}
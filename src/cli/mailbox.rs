@@ -0,0 +1,261 @@
+//! Store-and-forward mailbox kept in a route's own DHT record.
+//!
+//! Routes created with a mailbox use an SMPL schema instead of `DHTSchema::dflt(1)`:
+//!
+//! ```text
+//! subkey 0                 route blob (owner)
+//! subkey 1                 mailbox writer keypair (owner)
+//! subkeys 2..2+SLOTS       mailbox slots (mailbox writer member)
+//! ```
+//!
+//! Anyone holding the route record key can read the writer keypair and drop a sealed
+//! envelope into an empty slot. The owner drains the slots when it starts listening and
+//! clears them with the same writer keypair. Two senders picking the same empty slot at
+//! the same moment can overwrite each other; the slot search starts at a random offset
+//! to make that unlikely.
+//!
+//! The writer keypair is published in cleartext, so the mailbox is not private storage:
+//! anyone who learns the route record key can also overwrite or wipe every slot, and
+//! can fill the mailbox so nobody else can deposit. Envelopes stay sealed to the
+//! recipient, so they cannot be read or forged, only lost.
+
+use crate::cli::app_state::LocalRouteIdentity;
+use eyre::Result;
+use eyre::bail;
+use tracing::warn;
+use veilid_core::DHTSchema;
+use veilid_core::DHTSchemaSMPLMember;
+use veilid_core::KeyPair;
+use veilid_core::RecordKey;
+use veilid_core::RoutingContext;
+use veilid_core::SetDHTValueOptions;
+use veilid_core::VeilidAPI;
+
+/// Subkey holding the mailbox writer keypair.
+const MAILBOX_WRITER_SUBKEY: u32 = 1;
+/// Subkeys written by the owner before the mailbox slots begin.
+const MAILBOX_OWNER_SUBKEYS: u16 = 2;
+/// Number of messages a mailbox can hold before it is drained.
+pub const MAILBOX_SLOTS: u16 = 32;
+
+/// The DHT schema for a route record, with mailbox slots when `mailbox_writer` is set.
+///
+/// # Errors
+///
+/// Returns an error if Veilid rejects the schema parameters.
+pub fn route_record_schema(api: &VeilidAPI, mailbox_writer: Option<&KeyPair>) -> Result<DHTSchema> {
+    match mailbox_writer {
+        Some(writer) => mailbox_schema(api, writer),
+        None => Ok(DHTSchema::dflt(1)?),
+    }
+}
+
+/// The SMPL schema for a route record whose mailbox slots are writable by `writer`.
+///
+/// # Errors
+///
+/// Returns an error if Veilid rejects the schema parameters.
+pub fn mailbox_schema(api: &VeilidAPI, writer: &KeyPair) -> Result<DHTSchema> {
+    let member = DHTSchemaSMPLMember {
+        m_key: api.generate_member_id(&writer.key())?,
+        m_cnt: MAILBOX_SLOTS,
+    };
+    Ok(DHTSchema::smpl(MAILBOX_OWNER_SUBKEYS, vec![member])?)
+}
+
+/// Publish the mailbox writer keypair so senders can find it.
+///
+/// The route record must already be open with the owner keypair. Does nothing for
+/// routes without a mailbox.
+///
+/// # Errors
+///
+/// Returns an error if the DHT write fails.
+pub async fn publish_mailbox_writer(
+    router: &RoutingContext,
+    identity: &LocalRouteIdentity,
+) -> Result<()> {
+    if let Some(writer) = &identity.mailbox_writer {
+        router
+            .set_dht_value(
+                identity.record_key.clone(),
+                MAILBOX_WRITER_SUBKEY,
+                encode_writer(writer),
+                None,
+            )
+            .await?;
+    }
+    Ok(())
+}
+
+/// Read every non-empty mailbox slot, then clear it with the mailbox writer keypair.
+///
+/// The route record must already be open with the owner keypair. Returns the stored
+/// payloads in slot order; routes without a mailbox yield nothing. Slots that cannot be
+/// read are skipped and slots that cannot be cleared are still returned, both with a
+/// warning, so one bad slot never stops a listener; an uncleared message is drained
+/// again next time and dropped as a duplicate.
+pub async fn drain_mailbox(router: &RoutingContext, identity: &LocalRouteIdentity) -> Vec<Vec<u8>> {
+    let Some(writer) = &identity.mailbox_writer else {
+        return Vec::new();
+    };
+    let mut payloads = Vec::new();
+    for subkey in mailbox_slot_subkeys() {
+        let value = match router
+            .get_dht_value(identity.record_key.clone(), subkey, true)
+            .await
+        {
+            Ok(value) => value,
+            Err(error) => {
+                warn!(route = %identity.name, subkey, %error, "could not read mailbox slot");
+                continue;
+            }
+        };
+        let Some(payload) = value.as_ref().and_then(|value| slot_payload(value.data())) else {
+            continue;
+        };
+        payloads.push(payload.to_vec());
+        if let Err(error) = router
+            .set_dht_value(
+                identity.record_key.clone(),
+                subkey,
+                Vec::new(),
+                Some(SetDHTValueOptions {
+                    writer: Some(writer.clone()),
+                    ..Default::default()
+                }),
+            )
+            .await
+        {
+            warn!(route = %identity.name, subkey, %error, "could not clear mailbox slot");
+        }
+    }
+    payloads
+}
+
+/// Leave a sealed envelope in the mailbox of the route published under `key`.
+///
+/// Returns `false` when the route has no mailbox. `start` picks the slot the search
+/// for an empty one begins at.
+///
+/// # Errors
+///
+/// Returns an error if the record cannot be read or written, the published writer
+/// keypair is malformed, or every slot is occupied.
+pub async fn deposit(
+    router: &RoutingContext,
+    key: &RecordKey,
+    payload: Vec<u8>,
+    start: u16,
+) -> Result<bool> {
+    let _ = router.open_dht_record(key.clone(), None).await?;
+    let writer = router
+        .get_dht_value(key.clone(), MAILBOX_WRITER_SUBKEY, true)
+        .await;
+    let _ = router.close_dht_record(key.clone()).await;
+    let Some(writer) = writer.ok().flatten() else {
+        return Ok(false);
+    };
+    let Some(writer) = decode_writer(writer.data())? else {
+        return Ok(false);
+    };
+
+    let _ = router.open_dht_record(key.clone(), Some(writer)).await?;
+    let result = write_first_empty_slot(router, key, payload, start).await;
+    let _ = router.close_dht_record(key.clone()).await;
+    result.map(|()| true)
+}
+
+async fn write_first_empty_slot(
+    router: &RoutingContext,
+    key: &RecordKey,
+    payload: Vec<u8>,
+    start: u16,
+) -> Result<()> {
+    for subkey in slot_search_order(start) {
+        let occupied = router
+            .get_dht_value(key.clone(), subkey, true)
+            .await?
+            .is_some_and(|value| slot_payload(value.data()).is_some());
+        if occupied {
+            continue;
+        }
+        router
+            .set_dht_value(key.clone(), subkey, payload, None)
+            .await?;
+        return Ok(());
+    }
+    bail!("mailbox is full")
+}
+
+fn mailbox_slot_subkeys() -> impl Iterator<Item = u32> {
+    let first = u32::from(MAILBOX_OWNER_SUBKEYS);
+    first..first + u32::from(MAILBOX_SLOTS)
+}
+
+/// Every slot subkey once, beginning at slot `start` and wrapping around.
+fn slot_search_order(start: u16) -> Vec<u32> {
+    let subkeys = mailbox_slot_subkeys().collect::<Vec<_>>();
+    let start = usize::from(start % MAILBOX_SLOTS);
+    subkeys[start..]
+        .iter()
+        .chain(&subkeys[..start])
+        .copied()
+        .collect()
+}
+
+/// The message held in a slot, or `None` when the slot is empty or was cleared.
+fn slot_payload(data: &[u8]) -> Option<&[u8]> {
+    (!data.is_empty()).then_some(data)
+}
+
+fn encode_writer(writer: &KeyPair) -> Vec<u8> {
+    writer.to_string().into_bytes()
+}
+
+/// Parse the published writer keypair; an empty subkey means the route has no mailbox.
+fn decode_writer(data: &[u8]) -> Result<Option<KeyPair>> {
+    if data.is_empty() {
+        return Ok(None);
+    }
+    Ok(Some(std::str::from_utf8(data)?.parse::<KeyPair>()?))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn slot_search_visits_every_slot_once_from_start() {
+        let order = slot_search_order(30);
+        assert_eq!(order.len(), usize::from(MAILBOX_SLOTS));
+        assert_eq!(order[0], u32::from(MAILBOX_OWNER_SUBKEYS) + 30);
+        assert_eq!(order[2], u32::from(MAILBOX_OWNER_SUBKEYS));
+        let mut sorted = order.clone();
+        sorted.sort_unstable();
+        assert_eq!(sorted, mailbox_slot_subkeys().collect::<Vec<_>>());
+    }
+
+    #[test]
+    fn slot_search_start_wraps_past_slot_count() {
+        assert_eq!(slot_search_order(MAILBOX_SLOTS + 1), slot_search_order(1));
+    }
+
+    #[test]
+    fn slots_never_overlap_owner_subkeys() {
+        assert!(mailbox_slot_subkeys().all(|subkey| subkey > MAILBOX_WRITER_SUBKEY));
+    }
+
+    #[test]
+    fn cleared_slot_is_empty() {
+        assert_eq!(slot_payload(&[]), None);
+        assert_eq!(slot_payload(b"sealed"), Some(&b"sealed"[..]));
+    }
+
+    #[test]
+    fn writer_decoding_treats_empty_as_no_mailbox() -> Result<()> {
+        assert_eq!(decode_writer(&[])?, None);
+        decode_writer(b"not a keypair").expect_err("garbage writer should be rejected");
+        Ok(())
+    }
+}
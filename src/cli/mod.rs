@@ -1,23 +1,46 @@
 pub mod app_state;
+pub mod chat;
+pub mod chat_crypto;
+pub mod daemon;
+pub mod envelope;
+pub mod events;
+pub mod file_transfer;
 pub mod global_args;
+pub mod hook;
+pub mod hooks;
+pub mod inbox;
 pub mod key;
 pub mod known_user;
+pub mod mailbox;
 pub mod media;
+pub mod outbox;
 pub mod output_format;
 pub mod profile;
+pub mod rate_limit;
 pub mod response;
+pub mod room;
 pub mod route;
+pub mod safety;
 pub mod send;
+pub mod sequencing;
 pub mod test;
 pub mod veilid_runtime;
 
+use crate::cli::chat::ChatArgs;
+use crate::cli::daemon::DaemonArgs;
+use crate::cli::daemon::client::DaemonConnection;
+use crate::cli::daemon::client::runs_on_daemon;
 use crate::cli::global_args::GlobalArgs;
+use crate::cli::hook::HookArgs;
+use crate::cli::inbox::InboxArgs;
 use crate::cli::key::KeyArgs;
 use crate::cli::known_user::KnownUserArgs;
 use crate::cli::media::MediaArgs;
+use crate::cli::outbox::OutboxArgs;
 use crate::cli::output_format::OutputFormatArg;
 use crate::cli::profile::ProfileArgs;
 use crate::cli::response::CliResponse;
+use crate::cli::room::RoomArgs;
 use crate::cli::route::RouteArgs;
 use crate::cli::send::SendArgs;
 use crate::cli::test::TestArgs;
@@ -92,6 +115,14 @@ impl InvokeContext {
     pub fn output_format(&self) -> Option<OutputFormatArg> {
         self.output_format
     }
+
+    /// The same context with another `--output-format`, for commands run on behalf of
+    /// a daemon client.
+    #[must_use]
+    pub fn with_output_format(mut self, output_format: Option<OutputFormatArg>) -> Self {
+        self.output_format = output_format;
+        self
+    }
 }
 
 // Blanket implementation for references
@@ -152,8 +183,17 @@ impl Cli {
             .enable_all()
             .build()
             .wrap_err("Failed to build tokio runtime")?;
-        let response = runtime
-            .block_on(async move { self.command.invoke(&context).instrument(span).await })?;
+        let response = runtime.block_on(async move {
+            if runs_on_daemon(&self.command, &context)
+                && let Some(daemon) = DaemonConnection::connect(context.profile_home()).await?
+            {
+                return daemon
+                    .forward(self.command, &context, output_format)
+                    .instrument(span)
+                    .await;
+            }
+            self.command.invoke(&context).instrument(span).await
+        })?;
         response.write(output_format)?;
         Ok(())
     }
@@ -194,6 +234,18 @@ pub enum Command {
     Media(MediaArgs),
     /// Sending commands.
     Send(SendArgs),
+    /// Chat history and interactive chat commands.
+    Chat(ChatArgs),
+    /// Outbox commands for messages queued while a known user was offline.
+    Outbox(OutboxArgs),
+    /// Chat room commands.
+    Room(RoomArgs),
+    /// Inbox policy and quarantine commands.
+    Inbox(InboxArgs),
+    /// Shell hooks run for incoming chat messages.
+    Hook(HookArgs),
+    /// Background daemon that keeps the profile's Veilid node attached.
+    Daemon(DaemonArgs),
     /// Test utility commands.
     Test(TestArgs),
 }
@@ -210,6 +262,12 @@ impl Command {
             Command::Route(args) => args.invoke(context).await,
             Command::Media(args) => args.invoke(context).await,
             Command::Send(args) => args.invoke(context).await,
+            Command::Chat(args) => args.invoke(context).await,
+            Command::Outbox(args) => args.invoke(context).await,
+            Command::Room(args) => args.invoke(context).await,
+            Command::Inbox(args) => args.invoke(context).await,
+            Command::Hook(args) => args.invoke(context).await,
+            Command::Daemon(args) => args.invoke(context).await,
             Command::Test(args) => args.invoke(context).await,
         }
     }
@@ -243,6 +301,30 @@ impl ToArgs for Command {
                 args.push("send".into());
                 args.extend(send_args.to_args());
             }
+            Command::Chat(chat_args) => {
+                args.push("chat".into());
+                args.extend(chat_args.to_args());
+            }
+            Command::Outbox(outbox_args) => {
+                args.push("outbox".into());
+                args.extend(outbox_args.to_args());
+            }
+            Command::Room(room_args) => {
+                args.push("room".into());
+                args.extend(room_args.to_args());
+            }
+            Command::Inbox(inbox_args) => {
+                args.push("inbox".into());
+                args.extend(inbox_args.to_args());
+            }
+            Command::Hook(hook_args) => {
+                args.push("hook".into());
+                args.extend(hook_args.to_args());
+            }
+            Command::Daemon(daemon_args) => {
+                args.push("daemon".into());
+                args.extend(daemon_args.to_args());
+            }
             Command::Test(test_args) => {
                 args.push("test".into());
                 args.extend(test_args.to_args());
@@ -0,0 +1,42 @@
+use crate::cli::InvokeContext;
+use crate::cli::ToArgs;
+use crate::cli::app_state;
+use arbitrary::Arbitrary;
+use eyre::Result;
+use facet::Facet;
+use figue as args;
+use std::fmt;
+
+#[derive(Facet, Arbitrary, Debug, PartialEq)]
+pub struct OutboxCancelArgs {
+    #[facet(args::positional)]
+    pub id: u64,
+}
+
+#[derive(Clone, Debug, PartialEq, Eq, Facet)]
+pub struct OutboxCancelResponse {
+    id: u64,
+}
+
+impl fmt::Display for OutboxCancelResponse {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "Cancelled queued message {}.", self.id)
+    }
+}
+
+impl OutboxCancelArgs {
+    #[expect(
+        clippy::unused_async,
+        reason = "command handlers use async invoke signature consistently"
+    )]
+    pub async fn invoke(self, context: &InvokeContext) -> Result<OutboxCancelResponse> {
+        app_state::remove_outbox_entry(context.profile_home(), self.id)?;
+        Ok(OutboxCancelResponse { id: self.id })
+    }
+}
+
+impl ToArgs for OutboxCancelArgs {
+    fn to_args(&self) -> Vec<std::ffi::OsString> {
+        vec![self.id.to_string().into()]
+    }
+}
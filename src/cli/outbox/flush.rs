@@ -0,0 +1,120 @@
+use crate::cli::app_state;
+use crate::cli::app_state::OutboxEntry;
+use crate::cli::app_state::ProfileHome;
+use crate::cli::envelope::Extensions;
+use crate::cli::events::EventOutput;
+use crate::cli::send::chat::ChatSession;
+use crate::cli::send::chat::read_route_blob;
+use eyre::Result;
+use tracing::warn;
+use veilid_core::KeyPair;
+use veilid_core::RecordKey;
+use veilid_core::RoutingContext;
+use veilid_core::VeilidAPI;
+
+/// What a flush pass managed to deliver.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub struct OutboxFlushSummary {
+    pub delivered: usize,
+    pub remaining: usize,
+}
+
+/// Try to deliver queued messages, oldest first, to every known user that is online.
+///
+/// A known user counts as online when any of their route records holds route data.
+/// Delivery to a known user stops at the first failure so messages keep their order;
+/// the failed entry's attempt count is bumped and it stays queued.
+///
+/// # Errors
+///
+/// Returns an error if outbox or known-user data cannot be loaded or persisted.
+pub(crate) async fn flush_outbox(
+    api: &VeilidAPI,
+    router: &RoutingContext,
+    profile_home: &ProfileHome,
+    keypair: &KeyPair,
+    known_user_filter: Option<&str>,
+    output: EventOutput,
+) -> Result<OutboxFlushSummary> {
+    let entries = app_state::list_outbox(profile_home)?
+        .into_iter()
+        .filter(|entry| known_user_filter.is_none_or(|filter| entry.known_user == filter))
+        .collect::<Vec<_>>();
+
+    let mut known_users = Vec::<&str>::new();
+    for entry in &entries {
+        if !known_users.contains(&entry.known_user.as_str()) {
+            known_users.push(&entry.known_user);
+        }
+    }
+
+    let mut summary = OutboxFlushSummary::default();
+    for known_user in known_users {
+        let queued = entries
+            .iter()
+            .filter(|entry| entry.known_user == known_user)
+            .collect::<Vec<_>>();
+        let Some(recipient) = app_state::known_user_public_key(profile_home, known_user)? else {
+            summary.remaining += queued.len();
+            continue;
+        };
+        let keys = app_state::route_keys_for_known_user(profile_home, known_user)?;
+        if !is_online(router, &keys).await {
+            summary.remaining += queued.len();
+            continue;
+        }
+
+        let mut session = ChatSession {
+            profile_home,
+            known_user: known_user.to_owned(),
+            api,
+            router,
+            keypair,
+            recipient,
+            keys,
+            retry_attempts: 1,
+            acknowledge: false,
+            extensions: Extensions::default(),
+            cached_route_id: None,
+            output,
+        };
+        let delivered = deliver_in_order(&mut session, &queued).await?;
+        session.release_route();
+
+        summary.delivered += delivered;
+        summary.remaining += queued.len() - delivered;
+    }
+
+    Ok(summary)
+}
+
+async fn deliver_in_order(session: &mut ChatSession<'_>, queued: &[&OutboxEntry]) -> Result<usize> {
+    let mut delivered = 0;
+    for entry in queued {
+        match session.send_text(&entry.body).await {
+            Ok(_) => {
+                app_state::remove_outbox_entry(session.profile_home, entry.id)?;
+                session.output.note(format_args!(
+                    "Delivered queued message {} to {}.",
+                    entry.id, entry.known_user
+                ));
+                delivered += 1;
+            }
+            Err(error) => {
+                warn!(%error, id = entry.id, "failed to deliver queued message");
+                app_state::record_outbox_attempt(session.profile_home, entry.id)?;
+                break;
+            }
+        }
+    }
+    Ok(delivered)
+}
+
+async fn is_online(router: &RoutingContext, keys: &[RecordKey]) -> bool {
+    for key in keys {
+        if matches!(read_route_blob(router, key).await, Ok(Some(_))) {
+            return true;
+        }
+    }
+    false
+}
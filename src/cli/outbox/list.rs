@@ -0,0 +1,88 @@
+use crate::cli::InvokeContext;
+use crate::cli::ToArgs;
+use crate::cli::app_state;
+use crate::cli::chat::history::format_timestamp_ms;
+use arbitrary::Arbitrary;
+use eyre::Result;
+use facet::Facet;
+use figue as args;
+use std::fmt;
+
+#[derive(Facet, Arbitrary, Debug, PartialEq)]
+pub struct OutboxListArgs {
+    /// Only show messages queued for this known user.
+    #[facet(args::named)]
+    pub known_user: Option<String>,
+}
+
+#[derive(Clone, Debug, PartialEq, Eq, Facet)]
+pub struct OutboxListItem {
+    id: u64,
+    known_user: String,
+    queued_at: String,
+    attempts: u32,
+    body: String,
+}
+
+#[derive(Clone, Debug, PartialEq, Eq, Facet)]
+pub struct OutboxListResponse {
+    messages: Vec<OutboxListItem>,
+}
+
+impl fmt::Display for OutboxListResponse {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        if self.messages.is_empty() {
+            return f.write_str("Your outbox is empty.");
+        }
+
+        for (index, message) in self.messages.iter().enumerate() {
+            if index > 0 {
+                writeln!(f)?;
+            }
+            write!(
+                f,
+                "{} [{}] to {} ({} attempts): {}",
+                message.id, message.queued_at, message.known_user, message.attempts, message.body
+            )?;
+        }
+        Ok(())
+    }
+}
+
+impl OutboxListArgs {
+    #[expect(
+        clippy::unused_async,
+        reason = "command handlers use async invoke signature consistently"
+    )]
+    pub async fn invoke(self, context: &InvokeContext) -> Result<OutboxListResponse> {
+        let entries = app_state::list_outbox(context.profile_home())?;
+        Ok(OutboxListResponse {
+            messages: entries
+                .into_iter()
+                .filter(|entry| {
+                    self.known_user
+                        .as_ref()
+                        .is_none_or(|known_user| &entry.known_user == known_user)
+                })
+                .map(|entry| OutboxListItem {
+                    id: entry.id,
+                    known_user: entry.known_user,
+                    queued_at: format_timestamp_ms(entry.queued_at_ms),
+                    attempts: entry.attempts,
+                    body: entry.body,
+                })
+                .collect(),
+        })
+    }
+}
+
+impl ToArgs for OutboxListArgs {
+    fn to_args(&self) -> Vec<std::ffi::OsString> {
+        let mut args = vec![];
+        if let Some(known_user) = &self.known_user {
+            args.push("--known-user".into());
+            args.push(known_user.clone().into());
+        }
+        args
+    }
+}
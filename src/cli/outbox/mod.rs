@@ -0,0 +1,7 @@
+pub(crate) mod cancel;
+pub(crate) mod flush;
+pub(crate) mod list;
+mod outbox_cli;
+pub(crate) mod retry;
+
+pub use outbox_cli::*;
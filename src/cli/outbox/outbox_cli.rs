@@ -0,0 +1,59 @@
+use crate::cli::InvokeContext;
+use crate::cli::ToArgs;
+use crate::cli::outbox::cancel::OutboxCancelArgs;
+use crate::cli::outbox::list::OutboxListArgs;
+use crate::cli::outbox::retry::OutboxRetryArgs;
+use crate::cli::response::CliResponse;
+use arbitrary::Arbitrary;
+use eyre::Result;
+use facet::Facet;
+use figue as args;
+use std::ffi::OsString;
+
+#[derive(Facet, Arbitrary, Debug, PartialEq)]
+pub struct OutboxArgs {
+    #[facet(args::subcommand)]
+    pub command: OutboxCommand,
+}
+
+#[derive(Facet, Arbitrary, Debug, PartialEq)]
+#[repr(u8)]
+pub enum OutboxCommand {
+    List(OutboxListArgs),
+    Cancel(OutboxCancelArgs),
+    Retry(OutboxRetryArgs),
+}
+
+impl OutboxArgs {
+    /// # Errors
+    ///
+    /// Returns an error if the selected outbox subcommand fails.
+    pub async fn invoke(self, context: &InvokeContext) -> Result<CliResponse> {
+        Ok(match self.command {
+            OutboxCommand::List(args) => args.invoke(context).await?.into(),
+            OutboxCommand::Cancel(args) => args.invoke(context).await?.into(),
+            OutboxCommand::Retry(args) => args.invoke(context).await?.into(),
+        })
+    }
+}
+
+impl ToArgs for OutboxArgs {
+    fn to_args(&self) -> Vec<OsString> {
+        let mut args = Vec::new();
+        match &self.command {
+            OutboxCommand::List(list_args) => {
+                args.push("list".into());
+                args.extend(list_args.to_args());
+            }
+            OutboxCommand::Cancel(cancel_args) => {
+                args.push("cancel".into());
+                args.extend(cancel_args.to_args());
+            }
+            OutboxCommand::Retry(retry_args) => {
+                args.push("retry".into());
+                args.extend(retry_args.to_args());
+            }
+        }
+        args
+    }
+}
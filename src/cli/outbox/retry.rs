@@ -0,0 +1,121 @@
+use crate::cli::Cli;
+use crate::cli::InvokeContext;
+use crate::cli::ToArgs;
+use crate::cli::app_state;
+use crate::cli::events::EventOutput;
+use crate::cli::key::KeyArgs;
+use crate::cli::key::KeyCommand;
+use crate::cli::key::key_gen::KeyGenArgs;
+use crate::cli::outbox::flush::flush_outbox;
+use crate::cli::route::listen::wait_for_public_internet_ready;
+use crate::cli::veilid_runtime::shutdown_api;
+use crate::cli::veilid_runtime::start_api_for_profile;
+use arbitrary::Arbitrary;
+use eyre::Result;
+use facet::Facet;
+use figue as args;
+use std::fmt;
+use std::sync::Arc;
+use std::sync::atomic::AtomicBool;
+use std::sync::atomic::Ordering;
+use veilid_core::VeilidUpdate;
+
+#[derive(Facet, Arbitrary, Debug, PartialEq)]
+pub struct OutboxRetryArgs {
+    /// Only retry messages queued for this known user.
+    #[facet(args::named)]
+    pub known_user: Option<String>,
+}
+
+#[derive(Clone, Debug, PartialEq, Eq, Facet)]
+pub struct OutboxRetryResponse {
+    delivered: usize,
+    remaining: usize,
+}
+
+impl fmt::Display for OutboxRetryResponse {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "Delivered {} queued messages; {} remain in the outbox.",
+            self.delivered, self.remaining
+        )
+    }
+}
+
+impl OutboxRetryArgs {
+    /// # Errors
+    ///
+    /// Returns an error if Veilid cannot start or outbox data cannot be persisted.
+    pub async fn invoke(self, context: &InvokeContext) -> Result<OutboxRetryResponse> {
+        let profile_home = context.profile_home();
+        let known_user = self.known_user.as_deref();
+        let pending = app_state::list_outbox(profile_home)?
+            .into_iter()
+            .filter(|entry| known_user.is_none_or(|filter| entry.known_user == filter))
+            .count();
+        if pending == 0 {
+            return Ok(OutboxRetryResponse {
+                delivered: 0,
+                remaining: 0,
+            });
+        }
+
+        let my_keypair = app_state::load_keypair(profile_home)?.ok_or_else(|| {
+            eyre::eyre!(
+                "You have no key. Run '{}' first.",
+                Cli::display_invocation(&crate::cli::Command::Key(KeyArgs {
+                    command: KeyCommand::Gen(KeyGenArgs),
+                }))
+            )
+        })?;
+
+        let public_internet_ready = Arc::new(AtomicBool::new(false));
+        let callback = {
+            let public_internet_ready = Arc::clone(&public_internet_ready);
+            Arc::new(move |update: VeilidUpdate| {
+                if let VeilidUpdate::Attachment(attachment) = update {
+                    public_internet_ready
+                        .store(attachment.public_internet_ready, Ordering::Release);
+                }
+            }) as crate::cli::veilid_runtime::UpdateCallback
+        };
+
+        let api = start_api_for_profile(profile_home, true, callback).await?;
+        if let Err(error) =
+            wait_for_public_internet_ready(&api, &public_internet_ready, EventOutput::Text).await
+        {
+            shutdown_api(api).await;
+            return Err(error);
+        }
+
+        let router = api.routing_context()?.with_default_safety()?;
+        let summary = flush_outbox(
+            &api,
+            &router,
+            profile_home,
+            &my_keypair,
+            known_user,
+            EventOutput::Text,
+        )
+        .await;
+        shutdown_api(api).await;
+        let summary = summary?;
+
+        Ok(OutboxRetryResponse {
+            delivered: summary.delivered,
+            remaining: summary.remaining,
+        })
+    }
+}
+
+impl ToArgs for OutboxRetryArgs {
+    fn to_args(&self) -> Vec<std::ffi::OsString> {
+        let mut args = vec![];
+        if let Some(known_user) = &self.known_user {
+            args.push("--known-user".into());
+            args.push(known_user.clone().into());
+        }
+        args
+    }
+}
@@ -1,6 +1,7 @@
 pub(crate) mod add;
 mod details;
 pub(crate) mod list;
+pub(crate) mod network;
 mod profile_cli;
 pub(crate) mod remove;
 pub(crate) mod show;
@@ -0,0 +1,182 @@
+use crate::cli::InvokeContext;
+use crate::cli::ToArgs;
+use crate::cli::app_state;
+use crate::cli::app_state::ProfileVeilidConfig;
+use arbitrary::Arbitrary;
+use eyre::Result;
+use facet::Facet;
+use figue as args;
+use std::fmt;
+
+#[derive(Facet, Arbitrary, Debug, PartialEq, Default)]
+pub struct ProfileNetworkArgs {
+    /// Comma-separated bootstrap hosts or URLs; an empty value restores the public ones.
+    #[facet(args::named)]
+    pub bootstrap: Option<String>,
+
+    /// Address to listen on for UDP, TCP, and WS, like 127.0.0.1:5150; empty clears it.
+    #[facet(args::named)]
+    pub listen_address: Option<String>,
+
+    /// Password shared by every node of a private network; empty clears it.
+    #[facet(args::named)]
+    pub network_key: Option<String>,
+
+    /// Comma-separated capability codes to disable, like TUNL,RLAY; empty clears them.
+    #[facet(args::named)]
+    pub disable_capabilities: Option<String>,
+
+    /// Remove every override before applying the other flags.
+    #[facet(args::named, default)]
+    pub reset: bool,
+}
+
+#[derive(Clone, Debug, PartialEq, Eq, Facet)]
+pub struct ProfileNetworkResponse {
+    profile: String,
+    path: String,
+    bootstrap: Vec<String>,
+    listen_address: Option<String>,
+    network_key_set: bool,
+    disable_capabilities: Vec<String>,
+    updated: bool,
+}
+
+impl fmt::Display for ProfileNetworkResponse {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        if self.updated {
+            writeln!(f, "Updated Veilid network settings for {}.", self.profile)?;
+        } else {
+            writeln!(f, "Veilid network settings for {}:", self.profile)?;
+        }
+        writeln!(f, "Config file: {}", self.path)?;
+        if self.bootstrap.is_empty() {
+            writeln!(f, "Bootstrap: public (default)")?;
+        } else {
+            writeln!(f, "Bootstrap: {}", self.bootstrap.join(", "))?;
+        }
+        writeln!(
+            f,
+            "Listen address: {}",
+            self.listen_address.as_deref().unwrap_or("any (default)")
+        )?;
+        writeln!(
+            f,
+            "Network key: {}",
+            if self.network_key_set {
+                "set"
+            } else {
+                "none (public network)"
+            }
+        )?;
+        if self.disable_capabilities.is_empty() {
+            write!(f, "Disabled capabilities: none")
+        } else {
+            write!(
+                f,
+                "Disabled capabilities: {}",
+                self.disable_capabilities.join(", ")
+            )
+        }
+    }
+}
+
+/// Split a comma-separated flag into trimmed, non-empty items.
+fn split_list(value: &str) -> Vec<String> {
+    value
+        .split(',')
+        .map(str::trim)
+        .filter(|item| !item.is_empty())
+        .map(str::to_owned)
+        .collect()
+}
+
+/// Treat an empty flag value as clearing the setting.
+fn non_empty(value: &str) -> Option<String> {
+    let value = value.trim();
+    (!value.is_empty()).then(|| value.to_owned())
+}
+
+impl ProfileNetworkArgs {
+    /// Show or change the Veilid network settings nodes for this profile start with.
+    ///
+    /// Settings that are not given keep their current value.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the profile does not exist, its configuration file is
+    /// invalid, or the new settings cannot be persisted.
+    #[expect(
+        clippy::unused_async,
+        reason = "command handlers use async invoke signature consistently"
+    )]
+    pub async fn invoke(self, context: &InvokeContext) -> Result<ProfileNetworkResponse> {
+        let profile_home = context.profile_home();
+        let mut config = if self.reset {
+            ProfileVeilidConfig::default()
+        } else {
+            app_state::veilid_config(profile_home)?
+        };
+        if let Some(bootstrap) = &self.bootstrap {
+            config.bootstrap = split_list(bootstrap);
+        }
+        if let Some(listen_address) = &self.listen_address {
+            config.listen_address = non_empty(listen_address);
+        }
+        if let Some(network_key) = &self.network_key {
+            config.network_key = non_empty(network_key);
+        }
+        if let Some(disable_capabilities) = &self.disable_capabilities {
+            config.disable_capabilities = split_list(disable_capabilities)
+                .into_iter()
+                .map(|code| code.to_ascii_uppercase())
+                .collect();
+        }
+        let updated = self.reset
+            || self.bootstrap.is_some()
+            || self.listen_address.is_some()
+            || self.network_key.is_some()
+            || self.disable_capabilities.is_some();
+        if updated {
+            app_state::set_veilid_config(profile_home, &config)?;
+        }
+
+        Ok(ProfileNetworkResponse {
+            profile: profile_home.profile().to_owned(),
+            path: app_state::veilid_config_file(profile_home)
+                .display()
+                .to_string(),
+            bootstrap: config.bootstrap,
+            listen_address: config.listen_address,
+            network_key_set: config.network_key.is_some(),
+            disable_capabilities: config.disable_capabilities,
+            updated,
+        })
+    }
+}
+
+impl ToArgs for ProfileNetworkArgs {
+    fn to_args(&self) -> Vec<std::ffi::OsString> {
+        let mut args = Vec::new();
+        if let Some(bootstrap) = &self.bootstrap {
+            args.push("--bootstrap".into());
+            args.push(bootstrap.clone().into());
+        }
+        if let Some(listen_address) = &self.listen_address {
+            args.push("--listen-address".into());
+            args.push(listen_address.clone().into());
+        }
+        if let Some(network_key) = &self.network_key {
+            args.push("--network-key".into());
+            args.push(network_key.clone().into());
+        }
+        if let Some(disable_capabilities) = &self.disable_capabilities {
+            args.push("--disable-capabilities".into());
+            args.push(disable_capabilities.clone().into());
+        }
+        if self.reset {
+            args.push("--reset".into());
+        }
+        args
+    }
+}
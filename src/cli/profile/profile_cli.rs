@@ -2,6 +2,7 @@ use crate::cli::InvokeContext;
 use crate::cli::ToArgs;
 use crate::cli::profile::add::ProfileAddArgs;
 use crate::cli::profile::list::ProfileListArgs;
+use crate::cli::profile::network::ProfileNetworkArgs;
 use crate::cli::profile::remove::ProfileRemoveArgs;
 use crate::cli::profile::show::ProfileShowArgs;
 use crate::cli::profile::use_profile::ProfileUseArgs;
@@ -28,6 +29,7 @@ pub enum ProfileCommand {
     Use(ProfileUseArgs),
     Remove(ProfileRemoveArgs),
     Show(ProfileShowArgs),
+    Network(ProfileNetworkArgs),
 }
 
 impl ProfileArgs {
@@ -43,6 +45,7 @@ impl ProfileArgs {
             ProfileCommand::Use(args) => args.invoke(context).await?.into(),
             ProfileCommand::Remove(args) => args.invoke(context).await?.into(),
             ProfileCommand::Show(args) => args.invoke(context).await?.into(),
+            ProfileCommand::Network(args) => args.invoke(context).await?.into(),
         })
     }
 }
@@ -79,6 +82,10 @@ impl ToArgs for ProfileArgs {
                 args.push("show".into());
                 args.extend(show_args.to_args());
             }
+            ProfileCommand::Network(network_args) => {
+                args.push("network".into());
+                args.extend(network_args.to_args());
+            }
         }
         args
     }
@@ -0,0 +1,296 @@
+//! Token-bucket limits for inbound messages on a listening route.
+//!
+//! A global bucket is checked before an envelope is opened, so a flood costs little
+//! more than decoding; a bucket per verified sender is checked after. Messages over
+//! either limit are dropped and counted for the summary printed when listening stops.
+
+use crate::cli::app_state::RouteRateLimits;
+use std::collections::BTreeMap;
+use std::collections::HashMap;
+use std::fmt::Write;
+use std::time::Instant;
+
+/// Sender buckets kept before idle ones are forgotten.
+const MAX_TRACKED_SENDERS: usize = 4096;
+
+#[derive(Clone, Debug)]
+struct TokenBucket {
+    capacity: f64,
+    tokens: f64,
+    refill_per_second: f64,
+    refilled_at: Instant,
+}
+
+impl TokenBucket {
+    fn new(per_minute: u32, burst: u32, now: Instant) -> Self {
+        let capacity = f64::from(burst.max(1));
+        Self {
+            capacity,
+            tokens: capacity,
+            refill_per_second: f64::from(per_minute) / 60.0,
+            refilled_at: now,
+        }
+    }
+
+    fn refill(&mut self, now: Instant) {
+        let elapsed = now
+            .saturating_duration_since(self.refilled_at)
+            .as_secs_f64();
+        self.tokens = elapsed
+            .mul_add(self.refill_per_second, self.tokens)
+            .min(self.capacity);
+        self.refilled_at = now;
+    }
+
+    fn try_take(&mut self, now: Instant) -> bool {
+        self.refill(now);
+        if self.tokens >= 1.0 {
+            self.tokens -= 1.0;
+            true
+        } else {
+            false
+        }
+    }
+
+    fn is_full(&mut self, now: Instant) -> bool {
+        self.refill(now);
+        self.tokens >= self.capacity
+    }
+}
+
+/// The tightest of several routes' limits, field by field, for traffic that cannot be
+/// tied to one route. A limit disabled on some routes but set on others is set.
+#[must_use]
+pub fn strictest(limits: impl IntoIterator<Item = RouteRateLimits>) -> RouteRateLimits {
+    let mut strictest = RouteRateLimits {
+        per_sender_per_minute: 0,
+        per_sender_burst: 0,
+        global_per_minute: 0,
+        global_burst: 0,
+    };
+    for limits in limits {
+        (strictest.per_sender_per_minute, strictest.per_sender_burst) = tighter(
+            (strictest.per_sender_per_minute, strictest.per_sender_burst),
+            (limits.per_sender_per_minute, limits.per_sender_burst),
+        );
+        (strictest.global_per_minute, strictest.global_burst) = tighter(
+            (strictest.global_per_minute, strictest.global_burst),
+            (limits.global_per_minute, limits.global_burst),
+        );
+    }
+    strictest
+}
+
+/// The lower rate and burst of two `(per_minute, burst)` limits, ignoring a disabled one.
+fn tighter(current: (u32, u32), other: (u32, u32)) -> (u32, u32) {
+    match (current.0, other.0) {
+        (_, 0) => current,
+        (0, _) => other,
+        _ => (current.0.min(other.0), current.1.min(other.1)),
+    }
+}
+
+/// Inbound limits for one listener, with counts of what was dropped.
+#[derive(Clone, Debug)]
+pub struct InboundLimiter {
+    limits: RouteRateLimits,
+    global: Option<TokenBucket>,
+    senders: HashMap<String, TokenBucket>,
+    dropped_global: u64,
+    dropped_by_sender: BTreeMap<String, u64>,
+}
+
+impl InboundLimiter {
+    #[must_use]
+    pub fn new(limits: RouteRateLimits) -> Self {
+        let global = (limits.global_per_minute > 0).then(|| {
+            TokenBucket::new(
+                limits.global_per_minute,
+                limits.global_burst,
+                Instant::now(),
+            )
+        });
+        Self {
+            limits,
+            global,
+            senders: HashMap::new(),
+            dropped_global: 0,
+            dropped_by_sender: BTreeMap::new(),
+        }
+    }
+
+    /// The limits this limiter applies.
+    #[must_use]
+    pub fn limits(&self) -> RouteRateLimits {
+        self.limits
+    }
+
+    /// Take a token from the global bucket for a message that has not been opened yet.
+    pub fn admit_any(&mut self) -> bool {
+        let Some(global) = &mut self.global else {
+            return true;
+        };
+        if global.try_take(Instant::now()) {
+            return true;
+        }
+        self.dropped_global += 1;
+        false
+    }
+
+    /// Take a token from `sender`'s bucket; `label` names the sender in the summary.
+    pub fn admit_sender(&mut self, sender: &str, label: &str) -> bool {
+        if self.limits.per_sender_per_minute == 0 {
+            return true;
+        }
+        let now = Instant::now();
+        if self.senders.len() >= MAX_TRACKED_SENDERS && !self.senders.contains_key(sender) {
+            self.senders.retain(|_, bucket| !bucket.is_full(now));
+        }
+        let bucket = self.senders.entry(sender.to_owned()).or_insert_with(|| {
+            TokenBucket::new(
+                self.limits.per_sender_per_minute,
+                self.limits.per_sender_burst,
+                now,
+            )
+        });
+        if bucket.try_take(now) {
+            return true;
+        }
+        *self.dropped_by_sender.entry(label.to_owned()).or_default() += 1;
+        false
+    }
+
+    /// A description of dropped traffic, or `None` when nothing was dropped.
+    #[must_use]
+    pub fn summary(&self) -> Option<String> {
+        let dropped_senders = self.dropped_by_sender.values().sum::<u64>();
+        if self.dropped_global == 0 && dropped_senders == 0 {
+            return None;
+        }
+
+        let mut summary = format!(
+            "Dropped {} inbound messages over the rate limits.",
+            self.dropped_global + dropped_senders
+        );
+        if self.dropped_global > 0 {
+            let _ = write!(
+                summary,
+                "\n  global cap ({}/min): {}",
+                self.limits.global_per_minute, self.dropped_global
+            );
+        }
+        for (label, dropped) in &self.dropped_by_sender {
+            let _ = write!(
+                summary,
+                "\n  {label} ({}/min): {dropped}",
+                self.limits.per_sender_per_minute
+            );
+        }
+        Some(summary)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::time::Duration;
+
+    #[test]
+    fn bucket_allows_a_burst_then_refuses() {
+        let now = Instant::now();
+        let mut bucket = TokenBucket::new(60, 3, now);
+        for _ in 0..3 {
+            assert!(bucket.try_take(now));
+        }
+        assert!(!bucket.try_take(now));
+    }
+
+    #[test]
+    fn bucket_refills_at_the_per_minute_rate() {
+        let start = Instant::now();
+        let mut bucket = TokenBucket::new(60, 1, start);
+        assert!(bucket.try_take(start));
+        assert!(!bucket.try_take(start + Duration::from_millis(500)));
+        assert!(bucket.try_take(start + Duration::from_secs(1)));
+    }
+
+    #[test]
+    fn bucket_refill_is_capped_at_the_burst() {
+        let start = Instant::now();
+        let mut bucket = TokenBucket::new(60, 2, start);
+        assert!(bucket.try_take(start));
+        assert!(bucket.try_take(start));
+        let later = start + Duration::from_hours(1);
+        assert!(bucket.is_full(later));
+        assert!(bucket.try_take(later));
+        assert!(bucket.try_take(later));
+        assert!(!bucket.try_take(later));
+    }
+
+    #[test]
+    fn zero_rates_disable_the_limits() {
+        let mut limiter = InboundLimiter::new(RouteRateLimits {
+            per_sender_per_minute: 0,
+            per_sender_burst: 1,
+            global_per_minute: 0,
+            global_burst: 1,
+        });
+        for _ in 0..100 {
+            assert!(limiter.admit_any());
+            assert!(limiter.admit_sender("sender", "sender"));
+        }
+        assert_eq!(limiter.summary(), None);
+    }
+
+    #[test]
+    fn strictest_takes_the_tightest_enabled_limits() {
+        let open = RouteRateLimits::default();
+        let loose = RouteRateLimits {
+            per_sender_per_minute: 60,
+            per_sender_burst: 5,
+            global_per_minute: 0,
+            global_burst: 50,
+        };
+        let tight = RouteRateLimits {
+            per_sender_per_minute: 10,
+            per_sender_burst: 20,
+            global_per_minute: 100,
+            global_burst: 30,
+        };
+        assert_eq!(
+            strictest([open, loose, tight]),
+            RouteRateLimits {
+                per_sender_per_minute: 10,
+                per_sender_burst: 5,
+                global_per_minute: 100,
+                global_burst: 30,
+            }
+        );
+        let none = strictest([open, open]);
+        assert_eq!(none.per_sender_per_minute, 0);
+        assert_eq!(none.global_per_minute, 0);
+    }
+
+    #[test]
+    fn default_limits_are_disabled() {
+        let limits = RouteRateLimits::default();
+        assert_eq!(limits.per_sender_per_minute, 0);
+        assert_eq!(limits.global_per_minute, 0);
+    }
+
+    #[test]
+    fn senders_have_separate_buckets_and_drops_are_summarised() {
+        let mut limiter = InboundLimiter::new(RouteRateLimits {
+            per_sender_per_minute: 1,
+            per_sender_burst: 1,
+            global_per_minute: 0,
+            global_burst: 1,
+        });
+        assert!(limiter.admit_sender("a", "alice"));
+        assert!(!limiter.admit_sender("a", "alice"));
+        assert!(limiter.admit_sender("b", "bob"));
+        let summary = limiter.summary().expect("a message was dropped");
+        assert!(summary.contains("alice (1/min): 1"));
+        assert!(!summary.contains("bob"));
+    }
+}
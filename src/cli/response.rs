@@ -38,18 +38,47 @@ where
     }
 }
 
+/// Output already rendered elsewhere, such as by a daemon, in the format asked for.
+struct RenderedDeferredRender {
+    body: String,
+}
+
+impl DeferredRender for RenderedDeferredRender {
+    fn render(&self, _output_format: OutputFormat) -> Result<String> {
+        Ok(self.body.clone())
+    }
+}
+
 impl CliResponse {
     #[must_use]
     pub fn empty() -> Self {
         Self::default()
     }
 
+    /// A response whose output was already rendered.
+    #[must_use]
+    pub fn rendered(body: String) -> Self {
+        Self {
+            renderer: Some(Box::new(RenderedDeferredRender { body })),
+        }
+    }
+
+    /// Render the response body, or `None` for an empty response.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if response serialization to the selected output format fails.
+    pub fn render(self, output_format: OutputFormat) -> Result<Option<String>> {
+        self.renderer
+            .map(|renderer| renderer.render(output_format))
+            .transpose()
+    }
+
     /// # Errors
     ///
     /// Returns an error if response serialization to the selected output format fails.
     pub fn write(self, output_format: OutputFormat) -> Result<()> {
-        if let Some(renderer) = self.renderer {
-            let body = renderer.render(output_format)?;
+        if let Some(body) = self.render(output_format)? {
             println!("{body}");
         }
         Ok(())
@@ -0,0 +1,49 @@
+use crate::cli::InvokeContext;
+use crate::cli::ToArgs;
+use crate::cli::app_state;
+use arbitrary::Arbitrary;
+use eyre::Result;
+use facet::Facet;
+use figue as args;
+use std::fmt;
+
+#[derive(Facet, Arbitrary, Debug, PartialEq)]
+pub struct RoomAddMemberArgs {
+    #[facet(args::positional)]
+    pub room: String,
+
+    #[facet(args::positional)]
+    pub known_user: String,
+}
+
+#[derive(Clone, Debug, PartialEq, Eq, Facet)]
+pub struct RoomAddMemberResponse {
+    room: String,
+    known_user: String,
+}
+
+impl fmt::Display for RoomAddMemberResponse {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "Added {} to room '{}'.", self.known_user, self.room)
+    }
+}
+
+impl RoomAddMemberArgs {
+    #[expect(
+        clippy::unused_async,
+        reason = "command handlers use async invoke signature consistently"
+    )]
+    pub async fn invoke(self, context: &InvokeContext) -> Result<RoomAddMemberResponse> {
+        app_state::add_room_member(context.profile_home(), &self.room, &self.known_user)?;
+        Ok(RoomAddMemberResponse {
+            room: self.room,
+            known_user: self.known_user,
+        })
+    }
+}
+
+impl ToArgs for RoomAddMemberArgs {
+    fn to_args(&self) -> Vec<std::ffi::OsString> {
+        vec![self.room.clone().into(), self.known_user.clone().into()]
+    }
+}
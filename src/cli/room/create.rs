@@ -0,0 +1,60 @@
+use crate::cli::InvokeContext;
+use crate::cli::ToArgs;
+use crate::cli::app_state;
+use crate::cli::envelope::RoomId;
+use arbitrary::Arbitrary;
+use eyre::Result;
+use facet::Facet;
+use figue as args;
+use std::fmt;
+
+#[derive(Facet, Arbitrary, Debug, PartialEq)]
+pub struct RoomCreateArgs {
+    #[facet(args::positional)]
+    pub name: String,
+
+    /// Join an existing room by the id another member shared, instead of starting a new one.
+    #[facet(args::named)]
+    pub id: Option<String>,
+}
+
+#[derive(Clone, Debug, PartialEq, Eq, Facet)]
+pub struct RoomCreateResponse {
+    name: String,
+    room_id: String,
+}
+
+impl fmt::Display for RoomCreateResponse {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "Created room '{}' with id {}.", self.name, self.room_id)
+    }
+}
+
+impl RoomCreateArgs {
+    #[expect(
+        clippy::unused_async,
+        reason = "command handlers use async invoke signature consistently"
+    )]
+    pub async fn invoke(self, context: &InvokeContext) -> Result<RoomCreateResponse> {
+        let room_id = match &self.id {
+            Some(id) => id.parse::<RoomId>()?,
+            None => RoomId::generate(),
+        };
+        let room = app_state::create_room(context.profile_home(), &self.name, room_id)?;
+        Ok(RoomCreateResponse {
+            name: room.name,
+            room_id: room.room_id.to_string(),
+        })
+    }
+}
+
+impl ToArgs for RoomCreateArgs {
+    fn to_args(&self) -> Vec<std::ffi::OsString> {
+        let mut args = vec![self.name.clone().into()];
+        if let Some(id) = &self.id {
+            args.push("--id".into());
+            args.push(id.clone().into());
+        }
+        args
+    }
+}
@@ -0,0 +1,65 @@
+use crate::cli::InvokeContext;
+use crate::cli::ToArgs;
+use crate::cli::app_state;
+use arbitrary::Arbitrary;
+use eyre::Result;
+use facet::Facet;
+use std::fmt;
+
+#[derive(Facet, Arbitrary, Debug, PartialEq, Default)]
+pub struct RoomListArgs;
+
+#[derive(Clone, Debug, PartialEq, Eq, Facet)]
+pub struct RoomListItem {
+    name: String,
+    room_id: String,
+    members: Vec<String>,
+}
+
+#[derive(Clone, Debug, PartialEq, Eq, Facet)]
+pub struct RoomListResponse {
+    rooms: Vec<RoomListItem>,
+}
+
+impl fmt::Display for RoomListResponse {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        if self.rooms.is_empty() {
+            return f.write_str("You have no rooms.");
+        }
+
+        for (index, room) in self.rooms.iter().enumerate() {
+            if index > 0 {
+                writeln!(f)?;
+            }
+            let members = if room.members.is_empty() {
+                "no members".to_owned()
+            } else {
+                room.members.join(", ")
+            };
+            write!(f, "{} ({}): {}", room.name, room.room_id, members)?;
+        }
+        Ok(())
+    }
+}
+
+impl RoomListArgs {
+    #[expect(
+        clippy::unused_async,
+        reason = "command handlers use async invoke signature consistently"
+    )]
+    pub async fn invoke(self, context: &InvokeContext) -> Result<RoomListResponse> {
+        let rooms = app_state::list_rooms(context.profile_home())?;
+        Ok(RoomListResponse {
+            rooms: rooms
+                .into_iter()
+                .map(|room| RoomListItem {
+                    name: room.name,
+                    room_id: room.room_id.to_string(),
+                    members: room.members,
+                })
+                .collect(),
+        })
+    }
+}
+
+impl ToArgs for RoomListArgs {}
@@ -0,0 +1,8 @@
+pub(crate) mod add_member;
+pub(crate) mod create;
+pub(crate) mod list;
+pub(crate) mod remove_member;
+mod room_cli;
+pub(crate) mod send;
+
+pub use room_cli::*;
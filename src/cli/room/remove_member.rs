@@ -0,0 +1,49 @@
+use crate::cli::InvokeContext;
+use crate::cli::ToArgs;
+use crate::cli::app_state;
+use arbitrary::Arbitrary;
+use eyre::Result;
+use facet::Facet;
+use figue as args;
+use std::fmt;
+
+#[derive(Facet, Arbitrary, Debug, PartialEq)]
+pub struct RoomRemoveMemberArgs {
+    #[facet(args::positional)]
+    pub room: String,
+
+    #[facet(args::positional)]
+    pub known_user: String,
+}
+
+#[derive(Clone, Debug, PartialEq, Eq, Facet)]
+pub struct RoomRemoveMemberResponse {
+    room: String,
+    known_user: String,
+}
+
+impl fmt::Display for RoomRemoveMemberResponse {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "Removed {} from room '{}'.", self.known_user, self.room)
+    }
+}
+
+impl RoomRemoveMemberArgs {
+    #[expect(
+        clippy::unused_async,
+        reason = "command handlers use async invoke signature consistently"
+    )]
+    pub async fn invoke(self, context: &InvokeContext) -> Result<RoomRemoveMemberResponse> {
+        app_state::remove_room_member(context.profile_home(), &self.room, &self.known_user)?;
+        Ok(RoomRemoveMemberResponse {
+            room: self.room,
+            known_user: self.known_user,
+        })
+    }
+}
+
+impl ToArgs for RoomRemoveMemberArgs {
+    fn to_args(&self) -> Vec<std::ffi::OsString> {
+        vec![self.room.clone().into(), self.known_user.clone().into()]
+    }
+}
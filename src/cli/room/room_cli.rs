@@ -0,0 +1,73 @@
+use crate::cli::InvokeContext;
+use crate::cli::ToArgs;
+use crate::cli::response::CliResponse;
+use crate::cli::room::add_member::RoomAddMemberArgs;
+use crate::cli::room::create::RoomCreateArgs;
+use crate::cli::room::list::RoomListArgs;
+use crate::cli::room::remove_member::RoomRemoveMemberArgs;
+use crate::cli::room::send::RoomSendArgs;
+use arbitrary::Arbitrary;
+use eyre::Result;
+use facet::Facet;
+use figue as args;
+use std::ffi::OsString;
+
+#[derive(Facet, Arbitrary, Debug, PartialEq)]
+pub struct RoomArgs {
+    #[facet(args::subcommand)]
+    pub command: RoomCommand,
+}
+
+#[derive(Facet, Arbitrary, Debug, PartialEq)]
+#[repr(u8)]
+pub enum RoomCommand {
+    Create(RoomCreateArgs),
+    AddMember(RoomAddMemberArgs),
+    RemoveMember(RoomRemoveMemberArgs),
+    List(RoomListArgs),
+    Send(RoomSendArgs),
+}
+
+impl RoomArgs {
+    /// # Errors
+    ///
+    /// Returns an error if the selected room subcommand fails.
+    pub async fn invoke(self, context: &InvokeContext) -> Result<CliResponse> {
+        Ok(match self.command {
+            RoomCommand::Create(args) => args.invoke(context).await?.into(),
+            RoomCommand::AddMember(args) => args.invoke(context).await?.into(),
+            RoomCommand::RemoveMember(args) => args.invoke(context).await?.into(),
+            RoomCommand::List(args) => args.invoke(context).await?.into(),
+            RoomCommand::Send(args) => args.invoke(context).await?.into(),
+        })
+    }
+}
+
+impl ToArgs for RoomArgs {
+    fn to_args(&self) -> Vec<OsString> {
+        let mut args = Vec::new();
+        match &self.command {
+            RoomCommand::Create(create_args) => {
+                args.push("create".into());
+                args.extend(create_args.to_args());
+            }
+            RoomCommand::AddMember(add_member_args) => {
+                args.push("add-member".into());
+                args.extend(add_member_args.to_args());
+            }
+            RoomCommand::RemoveMember(remove_member_args) => {
+                args.push("remove-member".into());
+                args.extend(remove_member_args.to_args());
+            }
+            RoomCommand::List(list_args) => {
+                args.push("list".into());
+                args.extend(list_args.to_args());
+            }
+            RoomCommand::Send(send_args) => {
+                args.push("send".into());
+                args.extend(send_args.to_args());
+            }
+        }
+        args
+    }
+}
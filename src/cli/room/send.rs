@@ -0,0 +1,189 @@
+use crate::cli::Cli;
+use crate::cli::InvokeContext;
+use crate::cli::ToArgs;
+use crate::cli::app_state;
+use crate::cli::envelope::Extensions;
+use crate::cli::events::EventOutput;
+use crate::cli::key::KeyArgs;
+use crate::cli::key::KeyCommand;
+use crate::cli::key::key_gen::KeyGenArgs;
+use crate::cli::route::listen::wait_for_public_internet_ready;
+use crate::cli::send::chat::ChatSession;
+use crate::cli::veilid_runtime::shutdown_api;
+use crate::cli::veilid_runtime::start_api_for_profile;
+use arbitrary::Arbitrary;
+use eyre::Result;
+use eyre::bail;
+use facet::Facet;
+use figue as args;
+use std::fmt;
+use std::sync::Arc;
+use std::sync::atomic::AtomicBool;
+use std::sync::atomic::Ordering;
+use veilid_core::VeilidUpdate;
+
+#[derive(Facet, Arbitrary, Debug, PartialEq)]
+pub struct RoomSendArgs {
+    #[facet(args::positional)]
+    pub room: String,
+
+    #[facet(args::named)]
+    pub message: String,
+
+    #[facet(args::named)]
+    pub retry: Option<usize>,
+
+    /// Use `app_call` and wait for a delivery receipt from each member.
+    #[facet(args::named, default)]
+    pub ack: bool,
+}
+
+#[derive(Clone, Debug, PartialEq, Eq, Facet)]
+pub struct RoomDeliveryItem {
+    known_user: String,
+    outcome: String,
+}
+
+#[derive(Clone, Debug, PartialEq, Eq, Facet)]
+pub struct RoomSendResponse {
+    room: String,
+    deliveries: Vec<RoomDeliveryItem>,
+}
+
+impl fmt::Display for RoomSendResponse {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "Room '{}':", self.room)?;
+        for delivery in &self.deliveries {
+            write!(f, "\n  {}: {}", delivery.known_user, delivery.outcome)?;
+        }
+        Ok(())
+    }
+}
+
+impl RoomSendArgs {
+    /// Fan the message out to every member's routes, tagged with the room id.
+    ///
+    /// A member that cannot be reached or looked up is reported in the response rather
+    /// than failing the whole send. Progress notes go to stderr under a JSON output
+    /// format so stdout holds only the response.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the room does not exist or has no members, the profile has
+    /// no key, or Veilid cannot be started.
+    pub async fn invoke(self, context: &InvokeContext) -> Result<RoomSendResponse> {
+        let retry_attempts = self.retry.unwrap_or(1);
+        if retry_attempts == 0 {
+            bail!("--retry must be greater than 0.");
+        }
+
+        let profile_home = context.profile_home();
+        let Some(room) = app_state::room(profile_home, &self.room)? else {
+            bail!("Room '{}' does not exist.", self.room);
+        };
+        if room.members.is_empty() {
+            bail!("Room '{}' has no members.", room.name);
+        }
+        let my_keypair = app_state::load_keypair(profile_home)?.ok_or_else(|| {
+            eyre::eyre!(
+                "You have no key. Run '{}' first.",
+                Cli::display_invocation(&crate::cli::Command::Key(KeyArgs {
+                    command: KeyCommand::Gen(KeyGenArgs),
+                }))
+            )
+        })?;
+
+        let public_internet_ready = Arc::new(AtomicBool::new(false));
+        let callback = {
+            let public_internet_ready = Arc::clone(&public_internet_ready);
+            Arc::new(move |update: VeilidUpdate| {
+                if let VeilidUpdate::Attachment(attachment) = update {
+                    public_internet_ready
+                        .store(attachment.public_internet_ready, Ordering::Release);
+                }
+            }) as crate::cli::veilid_runtime::UpdateCallback
+        };
+
+        let output = EventOutput::for_context(context);
+        let api = start_api_for_profile(profile_home, true, callback).await?;
+        let router = match wait_for_public_internet_ready(&api, &public_internet_ready, output)
+            .await
+            .and_then(|()| Ok(api.routing_context()?.with_default_safety()?))
+        {
+            Ok(router) => router,
+            Err(error) => {
+                shutdown_api(api).await;
+                return Err(error);
+            }
+        };
+
+        let mut deliveries = Vec::with_capacity(room.members.len());
+        for member in &room.members {
+            let member_routes =
+                app_state::known_user_public_key(profile_home, member).and_then(|recipient| {
+                    Ok((
+                        recipient,
+                        app_state::route_keys_for_known_user(profile_home, member)?,
+                    ))
+                });
+            let outcome = match member_routes {
+                Err(error) => format!("failed: {error}"),
+                Ok((None, _)) => "not a known user".to_owned(),
+                Ok((Some(_), keys)) if keys.is_empty() => "no route record keys".to_owned(),
+                Ok((Some(recipient), keys)) => {
+                    let mut session = ChatSession {
+                        profile_home,
+                        known_user: member.clone(),
+                        api: &api,
+                        router: &router,
+                        keypair: &my_keypair,
+                        recipient,
+                        keys,
+                        retry_attempts,
+                        acknowledge: self.ack,
+                        extensions: Extensions {
+                            room_id: Some(room.room_id),
+                            ..Extensions::default()
+                        },
+                        cached_route_id: None,
+                        output,
+                    };
+                    let outcome = match session.send_text(&self.message).await {
+                        Ok(delivery) => delivery.to_string(),
+                        Err(error) => format!("failed: {error}"),
+                    };
+                    session.release_route();
+                    outcome
+                }
+            };
+            deliveries.push(RoomDeliveryItem {
+                known_user: member.clone(),
+                outcome,
+            });
+        }
+
+        shutdown_api(api).await;
+        Ok(RoomSendResponse {
+            room: room.name,
+            deliveries,
+        })
+    }
+}
+
+impl ToArgs for RoomSendArgs {
+    fn to_args(&self) -> Vec<std::ffi::OsString> {
+        let mut args = vec![
+            self.room.clone().into(),
+            "--message".into(),
+            self.message.clone().into(),
+        ];
+        if let Some(retry) = self.retry {
+            args.push("--retry".into());
+            args.push(retry.to_string().into());
+        }
+        if self.ack {
+            args.push("--ack".into());
+        }
+        args
+    }
+}
@@ -3,12 +3,15 @@ use crate::cli::InvokeContext;
 use crate::cli::ToArgs;
 use crate::cli::app_state;
 use crate::cli::app_state::LocalRouteIdentity;
+use crate::cli::app_state::RouteSafety;
+use crate::cli::events::EventOutput;
+use crate::cli::mailbox;
 use crate::cli::route::RouteArgs;
 use crate::cli::route::RouteCommand;
 use crate::cli::route::listen::RouteListenArgs;
-use crate::cli::route::listen::listen_on_named_route;
+use crate::cli::route::listen::listen_on_routes;
 use crate::cli::route::listen::wait_for_public_internet_ready;
-use crate::cli::veilid_runtime::printing_update_callback;
+use crate::cli::veilid_runtime::shutdown_api;
 use crate::cli::veilid_runtime::start_api_for_profile;
 use arbitrary::Arbitrary;
 use eyre::Result;
@@ -20,7 +23,6 @@ use std::sync::Arc;
 use std::sync::atomic::AtomicBool;
 use std::sync::atomic::Ordering;
 use veilid_core::CRYPTO_KIND_VLD0;
-use veilid_core::DHTSchema;
 use veilid_core::VeilidUpdate;
 
 #[derive(Facet, Arbitrary, Debug, PartialEq, Default)]
@@ -30,6 +32,10 @@ pub struct RouteAddArgs {
 
     #[facet(args::named, default)]
     pub listen: bool,
+
+    /// Add mailbox subkeys so known users can leave messages while you are offline.
+    #[facet(args::named, default)]
+    pub mailbox: bool,
 }
 
 #[derive(Clone, Debug, PartialEq, Eq, Facet)]
@@ -38,6 +44,7 @@ pub struct RouteAddResponse {
     record_key: String,
     profile: String,
     initialized_offline: bool,
+    mailbox_slots: Option<u16>,
 }
 
 impl fmt::Display for RouteAddResponse {
@@ -51,10 +58,15 @@ impl fmt::Display for RouteAddResponse {
             write!(
                 f,
                 "Initialized route record in offline state (empty route data)."
-            )
-        } else {
-            Ok(())
+            )?;
         }
+        if let Some(mailbox_slots) = self.mailbox_slots {
+            if self.initialized_offline {
+                writeln!(f)?;
+            }
+            write!(f, "Mailbox enabled with {mailbox_slots} slots.")?;
+        }
+        Ok(())
     }
 }
 
@@ -67,87 +79,94 @@ impl RouteAddArgs {
                 self.name,
                 Cli::display_invocation(&RouteArgs {
                     command: RouteCommand::Listen(RouteListenArgs {
-                        name: self.name.clone(),
+                        name: Some(self.name.clone()),
+                        all: false,
                         count: None,
+                        save_reply_routes: false,
                     }),
                 })
             );
         }
 
-        let api =
-            start_api_for_profile(profile_home, false, printing_update_callback(false)).await?;
+        let public_internet_ready = Arc::new(AtomicBool::new(false));
+        let callback = {
+            let public_internet_ready = Arc::clone(&public_internet_ready);
+            Arc::new(move |update: VeilidUpdate| {
+                if let VeilidUpdate::Attachment(attachment) = update {
+                    public_internet_ready
+                        .store(attachment.public_internet_ready, Ordering::Release);
+                }
+            }) as crate::cli::veilid_runtime::UpdateCallback
+        };
+
+        // One attached instance both generates the route keys and initializes the record.
+        let api = start_api_for_profile(profile_home, true, callback).await?;
         let crypto = api.crypto()?;
         let vcrypto = crypto
             .get_async(CRYPTO_KIND_VLD0)
             .ok_or_else(|| eyre::eyre!("VLD0 cryptosystem unavailable"))?;
         let route_keypair = vcrypto.generate_keypair().await;
+        let mailbox_writer = if self.mailbox {
+            Some(vcrypto.generate_keypair().await)
+        } else {
+            None
+        };
         let record_encryption_key = vcrypto.random_shared_secret().await;
+        let schema = mailbox::route_record_schema(&api, mailbox_writer.as_ref())?;
         let record_key = api.get_dht_record_key(
-            DHTSchema::dflt(1)?,
+            schema.clone(),
             route_keypair.key().clone(),
             Some(record_encryption_key),
         )?;
 
-        api.shutdown().await;
-
         let identity = LocalRouteIdentity {
             name: self.name.clone(),
             keypair: route_keypair,
             record_key,
+            mailbox_writer,
+            safety: RouteSafety::default(),
         };
-        app_state::add_local_route_identity(
-            profile_home,
-            &identity.name,
-            &identity.keypair,
-            &identity.record_key,
-        )?;
+        app_state::add_local_route_identity(profile_home, &identity)?;
 
         let record_key_text = identity.record_key.to_string();
-
-        let public_internet_ready = Arc::new(AtomicBool::new(false));
-        let callback = {
-            let public_internet_ready = Arc::clone(&public_internet_ready);
-            Arc::new(move |update: VeilidUpdate| {
-                if let VeilidUpdate::Attachment(attachment) = update {
-                    public_internet_ready
-                        .store(attachment.public_internet_ready, Ordering::Release);
-                }
-            }) as crate::cli::veilid_runtime::UpdateCallback
-        };
-
-        let api = start_api_for_profile(profile_home, true, callback).await?;
-        wait_for_public_internet_ready(&api, &public_internet_ready).await?;
-
-        let router = api.routing_context()?.with_default_safety()?;
-        if router
-            .open_dht_record(identity.record_key.clone(), Some(identity.keypair.clone()))
-            .await
-            .is_err()
-        {
-            let _ = router
-                .create_dht_record(
-                    CRYPTO_KIND_VLD0,
-                    DHTSchema::dflt(1)?,
-                    Some(identity.keypair.clone()),
-                )
-                .await?;
-        }
-
-        router
-            .set_dht_value(identity.record_key.clone(), 0, Vec::new(), None)
-            .await?;
-        let _ = router.close_dht_record(identity.record_key.clone()).await;
-        api.shutdown().await;
-
         if self.listen {
-            listen_on_named_route(context, &self.name, None).await?;
+            // Listening creates the record and publishes the route itself.
+            shutdown_api(api).await;
+            listen_on_routes(context, std::slice::from_ref(&self.name), None, false).await?;
+        } else {
+            if let Err(error) =
+                wait_for_public_internet_ready(&api, &public_internet_ready, EventOutput::Text)
+                    .await
+            {
+                shutdown_api(api).await;
+                return Err(error);
+            }
+
+            let router = api.routing_context()?.with_default_safety()?;
+            if router
+                .open_dht_record(identity.record_key.clone(), Some(identity.keypair.clone()))
+                .await
+                .is_err()
+            {
+                let _ = router
+                    .create_dht_record(CRYPTO_KIND_VLD0, schema, Some(identity.keypair.clone()))
+                    .await?;
+            }
+
+            router
+                .set_dht_value(identity.record_key.clone(), 0, Vec::new(), None)
+                .await?;
+            mailbox::publish_mailbox_writer(&router, &identity).await?;
+            let _ = router.close_dht_record(identity.record_key.clone()).await;
+            shutdown_api(api).await;
         }
 
         Ok(RouteAddResponse {
             name: self.name,
             record_key: record_key_text,
             profile: profile_home.profile().to_owned(),
-            initialized_offline: true,
+            initialized_offline: !self.listen,
+            mailbox_slots: self.mailbox.then_some(mailbox::MAILBOX_SLOTS),
         })
     }
 }
@@ -158,6 +177,9 @@ impl ToArgs for RouteAddArgs {
         if self.listen {
             args.push("--listen".into());
         }
+        if self.mailbox {
+            args.push("--mailbox".into());
+        }
         args
     }
 }
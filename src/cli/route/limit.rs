@@ -0,0 +1,132 @@
+use crate::cli::InvokeContext;
+use crate::cli::ToArgs;
+use crate::cli::app_state;
+use crate::cli::app_state::RouteRateLimits;
+use arbitrary::Arbitrary;
+use eyre::Result;
+use eyre::bail;
+use facet::Facet;
+use figue as args;
+use std::fmt;
+
+#[derive(Facet, Arbitrary, Debug, PartialEq)]
+pub struct RouteLimitArgs {
+    #[facet(args::positional)]
+    pub name: String,
+
+    /// Messages per minute accepted from one sender; 0 disables the limit.
+    #[facet(args::named)]
+    pub per_sender: Option<u32>,
+
+    /// Messages one sender may send back to back before the rate applies.
+    #[facet(args::named)]
+    pub per_sender_burst: Option<u32>,
+
+    /// Messages per minute accepted from all senders together; 0 disables the limit.
+    #[facet(args::named)]
+    pub global: Option<u32>,
+
+    /// Messages all senders together may send back to back before the rate applies.
+    #[facet(args::named)]
+    pub global_burst: Option<u32>,
+}
+
+#[derive(Clone, Debug, PartialEq, Eq, Facet)]
+pub struct RouteLimitResponse {
+    name: String,
+    per_sender_per_minute: u32,
+    per_sender_burst: u32,
+    global_per_minute: u32,
+    global_burst: u32,
+    updated: bool,
+}
+
+fn format_limit(per_minute: u32, burst: u32) -> String {
+    if per_minute == 0 {
+        "unlimited".to_owned()
+    } else {
+        format!("{per_minute}/min, burst {burst}")
+    }
+}
+
+impl fmt::Display for RouteLimitResponse {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        if self.updated {
+            writeln!(f, "Updated rate limits for route {}.", self.name)?;
+        } else {
+            writeln!(f, "Rate limits for route {}:", self.name)?;
+        }
+        writeln!(
+            f,
+            "Per sender: {}",
+            format_limit(self.per_sender_per_minute, self.per_sender_burst)
+        )?;
+        write!(
+            f,
+            "Global: {}",
+            format_limit(self.global_per_minute, self.global_burst)
+        )
+    }
+}
+
+impl RouteLimitArgs {
+    /// Show or change the inbound rate limits for a route.
+    ///
+    /// Limits that are not given keep their current value.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the route does not exist or the limits cannot be persisted.
+    #[expect(
+        clippy::unused_async,
+        reason = "command handlers use async invoke signature consistently"
+    )]
+    pub async fn invoke(self, context: &InvokeContext) -> Result<RouteLimitResponse> {
+        let profile_home = context.profile_home();
+        if app_state::local_route_identity(profile_home, &self.name)?.is_none() {
+            bail!("Route '{}' does not exist.", self.name);
+        }
+
+        let current = app_state::route_rate_limits(profile_home, &self.name)?;
+        let limits = RouteRateLimits {
+            per_sender_per_minute: self.per_sender.unwrap_or(current.per_sender_per_minute),
+            per_sender_burst: self.per_sender_burst.unwrap_or(current.per_sender_burst),
+            global_per_minute: self.global.unwrap_or(current.global_per_minute),
+            global_burst: self.global_burst.unwrap_or(current.global_burst),
+        };
+        let updated = self.per_sender.is_some()
+            || self.per_sender_burst.is_some()
+            || self.global.is_some()
+            || self.global_burst.is_some();
+        if updated {
+            app_state::set_route_rate_limits(profile_home, &self.name, limits)?;
+        }
+
+        Ok(RouteLimitResponse {
+            name: self.name,
+            per_sender_per_minute: limits.per_sender_per_minute,
+            per_sender_burst: limits.per_sender_burst,
+            global_per_minute: limits.global_per_minute,
+            global_burst: limits.global_burst,
+            updated,
+        })
+    }
+}
+
+impl ToArgs for RouteLimitArgs {
+    fn to_args(&self) -> Vec<std::ffi::OsString> {
+        let mut args = vec![self.name.clone().into()];
+        for (flag, value) in [
+            ("--per-sender", self.per_sender),
+            ("--per-sender-burst", self.per_sender_burst),
+            ("--global", self.global),
+            ("--global-burst", self.global_burst),
+        ] {
+            if let Some(value) = value {
+                args.push(flag.into());
+                args.push(value.to_string().into());
+            }
+        }
+        args
+    }
+}
@@ -2,141 +2,264 @@ use crate::cli::Cli;
 use crate::cli::InvokeContext;
 use crate::cli::ToArgs;
 use crate::cli::app_state;
+use crate::cli::app_state::ChatHistoryEntry;
+use crate::cli::app_state::LocalRouteIdentity;
+use crate::cli::app_state::MessageDirection;
+use crate::cli::app_state::ProfileHome;
+use crate::cli::app_state::RouteRateLimits;
+use crate::cli::app_state::RouteRotationPolicy;
+use crate::cli::app_state::RouteSafety;
+use crate::cli::app_state::SenderPolicy;
+use crate::cli::chat_crypto;
+use crate::cli::envelope::Envelope;
+use crate::cli::envelope::MessageKind;
+use crate::cli::envelope::RoomId;
+use crate::cli::events::EventOutput;
+use crate::cli::events::MessageEvent;
+use crate::cli::events::ReadyEvent;
+use crate::cli::events::RouteRepublishedEvent;
+use crate::cli::events::StoppedEvent;
+use crate::cli::file_transfer;
+use crate::cli::file_transfer::ChunkOutcome;
+use crate::cli::hooks::HookMessage;
+use crate::cli::hooks::HookRunner;
+use crate::cli::inbox::InboxArgs;
+use crate::cli::inbox::InboxCommand;
+use crate::cli::inbox::quarantine::InboxQuarantineArgs;
+use crate::cli::inbox::quarantine::InboxQuarantineCommand;
+use crate::cli::inbox::quarantine::list::InboxQuarantineListArgs;
+use crate::cli::key::KeyArgs;
+use crate::cli::key::KeyCommand;
+use crate::cli::key::key_gen::KeyGenArgs;
+use crate::cli::known_user::KnownUserArgs;
+use crate::cli::known_user::KnownUserCommand;
+use crate::cli::known_user::route::KnownUserRouteArgs;
+use crate::cli::known_user::route::KnownUserRouteCommand;
+use crate::cli::known_user::route::add::KnownUserRouteAddArgs;
+use crate::cli::mailbox;
+use crate::cli::outbox::flush::flush_outbox;
+use crate::cli::rate_limit;
+use crate::cli::rate_limit::InboundLimiter;
 use crate::cli::route::RouteArgs;
 use crate::cli::route::RouteCommand;
 use crate::cli::route::add::RouteAddArgs;
+use crate::cli::safety;
+use crate::cli::sequencing::Arrival;
+use crate::cli::sequencing::InboundSequencer;
+use crate::cli::veilid_runtime;
+use crate::cli::veilid_runtime::shutdown_api;
 use crate::cli::veilid_runtime::start_api_for_profile;
 use arbitrary::Arbitrary;
 use eyre::Result;
 use eyre::bail;
 use facet::Facet;
 use figue as args;
+use std::collections::BTreeMap;
+use std::collections::HashMap;
 use std::collections::HashSet;
 use std::sync::Arc;
 use std::sync::Mutex;
 use std::sync::atomic::AtomicBool;
-use std::sync::atomic::AtomicUsize;
 use std::sync::atomic::Ordering;
 use std::time::Duration;
 use std::time::Instant;
+use tokio::sync::mpsc;
+use tracing::debug;
+use tracing::warn;
 use veilid_core::CRYPTO_KIND_VLD0;
-use veilid_core::DHTSchema;
+use veilid_core::KeyPair;
+use veilid_core::OperationId;
+use veilid_core::RecordKey;
 use veilid_core::RouteBlob;
 use veilid_core::RouteId;
+use veilid_core::RoutingContext;
 use veilid_core::VeilidUpdate;
 
 #[derive(Facet, Arbitrary, Debug, PartialEq)]
 pub struct RouteListenArgs {
+    /// Route to listen on, or several separated by commas.
     #[facet(args::positional)]
-    pub name: String,
+    pub name: Option<String>,
+
+    /// Listen on every local route.
+    #[facet(args::named, default)]
+    pub all: bool,
 
     #[facet(args::named)]
     pub count: Option<usize>,
+
+    /// Save reply routes attached by known users so you can answer them right away.
+    #[facet(args::named, default)]
+    pub save_reply_routes: bool,
 }
 
 const ROUTE_ALLOCATE_MAX_ATTEMPTS: usize = 20;
 const ROUTE_ALLOCATE_RETRY_DELAY: Duration = Duration::from_secs(1);
+/// How often a listener tries to deliver messages waiting in the outbox.
+const OUTBOX_FLUSH_INTERVAL: Duration = Duration::from_secs(30);
+/// Senders with new sequence numbers held before they are written to the profile.
+const SEQUENCE_SAVE_BATCH: usize = 32;
+/// Longest a new sequence number waits to be written while messages keep arriving.
+const SEQUENCE_SAVE_INTERVAL: Duration = Duration::from_secs(30);
 
 impl RouteListenArgs {
     /// # Errors
     ///
-    /// Returns an error if the route is not found or listening fails.
+    /// Returns an error if no route is selected, a route is not found, or listening fails.
     pub async fn invoke(self, context: &InvokeContext) -> Result<()> {
         if matches!(self.count, Some(0)) {
             bail!("--count must be greater than 0.");
         }
-        listen_on_named_route(context, &self.name, self.count).await
+        let route_names = self.route_names(context.profile_home())?;
+        listen_on_routes(context, &route_names, self.count, self.save_reply_routes).await
+    }
+
+    /// The routes selected by `--all` or the name argument.
+    ///
+    /// A name that matches a route exactly is that one route; otherwise it is split on
+    /// commas.
+    fn route_names(&self, profile_home: &ProfileHome) -> Result<Vec<String>> {
+        let routes = app_state::list_local_route_identities(profile_home)?
+            .into_iter()
+            .map(|identity| identity.name)
+            .collect::<Vec<_>>();
+        let selected = match (&self.name, self.all) {
+            (Some(_), true) => bail!("Name routes or pass --all, not both."),
+            (None, false) => bail!("Name a route to listen on, or pass --all."),
+            (None, true) => routes,
+            (Some(name), false) if routes.contains(name) => vec![name.clone()],
+            (Some(names), false) => {
+                let mut selected = Vec::<String>::new();
+                for name in names.split(',').map(str::trim) {
+                    if !name.is_empty() && !selected.iter().any(|chosen| chosen == name) {
+                        selected.push(name.to_owned());
+                    }
+                }
+                selected
+            }
+        };
+        if selected.is_empty() {
+            bail!(
+                "You have no routes. Create one with '{}'.",
+                Cli::display_invocation(&RouteArgs {
+                    command: RouteCommand::Add(RouteAddArgs {
+                        name: "<name>".to_owned(),
+                        listen: false,
+                        mailbox: false,
+                    }),
+                })
+            );
+        }
+        Ok(selected)
     }
 }
 
-/// Listen for incoming messages using an existing named route identity.
+/// Listen for incoming messages on one or more existing named route identities.
+///
+/// Every route is published from the same Veilid node and republished on its own when
+/// Veilid reports it dead. With several routes, each message is tagged with the route
+/// it arrived on.
 ///
 /// # Errors
 ///
-/// Returns an error if the profile/route identity cannot be loaded,
+/// Returns an error if the profile/route identities cannot be loaded,
 /// attachment readiness is not reached, or Veilid operations fail.
-#[expect(
-    clippy::too_many_lines,
-    reason = "listen flow includes setup, callback wiring, and runtime loop"
-)]
-pub async fn listen_on_named_route(
+pub async fn listen_on_routes(
     context: &InvokeContext,
-    route_name: &str,
+    route_names: &[String],
     message_count_limit: Option<usize>,
+    save_reply_routes: bool,
 ) -> Result<()> {
     let profile_home = context.profile_home();
-    let identity = app_state::local_route_identity(profile_home, route_name)?.ok_or_else(|| {
+    let identities = route_names
+        .iter()
+        .map(|route_name| load_route_identity(profile_home, route_name))
+        .collect::<Result<Vec<_>>>()?;
+    let my_keypair = app_state::load_keypair(profile_home)?.ok_or_else(|| {
         eyre::eyre!(
-            "Route '{}' does not exist. Create it with '{}'.",
-            route_name,
-            Cli::display_invocation(&RouteArgs {
-                command: RouteCommand::Add(RouteAddArgs {
-                    name: route_name.to_owned(),
-                    listen: true,
-                }),
-            })
+            "You have no key. Run '{}' first.",
+            Cli::display_invocation(&crate::cli::Command::Key(KeyArgs {
+                command: KeyCommand::Gen(KeyGenArgs),
+            }))
         )
     })?;
 
     let public_internet_ready = Arc::new(AtomicBool::new(false));
-    let known_user_map = Arc::new(Mutex::new(
-        app_state::list_known_users(profile_home)?
-            .into_iter()
-            .map(|entry| (entry.pubkey.to_string(), entry.name))
-            .collect::<std::collections::HashMap<_, _>>(),
-    ));
     let dead_routes = Arc::new(Mutex::new(HashSet::<RouteId>::new()));
-    let printed_messages = Arc::new(AtomicUsize::new(0));
+    let (inbound_tx, mut inbound_rx) = mpsc::unbounded_channel::<InboundMessage>();
+    let mut printed_messages = 0usize;
+    let mut printed_by_route = vec![0usize; identities.len()];
     let callback = route_update_callback(
         Arc::clone(&public_internet_ready),
-        Arc::clone(&known_user_map),
+        inbound_tx,
         Arc::clone(&dead_routes),
-        Arc::clone(&printed_messages),
     );
 
+    let output = EventOutput::for_context(context);
     let api = start_api_for_profile(profile_home, true, callback).await?;
-    wait_for_public_internet_ready(&api, &public_internet_ready).await?;
+    wait_for_public_internet_ready(&api, &public_internet_ready, output).await?;
 
+    let mut inbound_handler = InboundHandler::load(&api, profile_home, &my_keypair)?;
+    inbound_handler.save_reply_routes = save_reply_routes;
+    inbound_handler.output = output;
+    inbound_handler.tag_routes = route_names.len() > 1;
+    for route_name in route_names {
+        inbound_handler.limit_route(
+            route_name,
+            app_state::route_rate_limits(profile_home, route_name)?,
+        );
+    }
     let router = api.routing_context()?.with_default_safety()?;
-    if router
-        .open_dht_record(identity.record_key.clone(), Some(identity.keypair.clone()))
-        .await
-        .is_err()
-    {
-        let _ = router
-            .create_dht_record(
-                CRYPTO_KIND_VLD0,
-                DHTSchema::dflt(1)?,
-                Some(identity.keypair.clone()),
+    let mut routes = Vec::with_capacity(identities.len());
+    for identity in identities {
+        let rotation = app_state::route_rotation_policy(profile_home, &identity.name)?;
+        let mut published = PublishedRoute::publish(&api, identity, output).await?;
+        published.rotation = rotation;
+        let text = if route_names.len() > 1 {
+            format!(
+                "Published route '{}' under record key {} for {}",
+                published.identity.name,
+                published.identity.record_key,
+                profile_home.profile()
             )
-            .await?;
+        } else {
+            format!(
+                "Created route information and stored it under record key {} for {}\nListening for messages.",
+                published.identity.record_key,
+                profile_home.profile()
+            )
+        };
+        output.event(
+            &ReadyEvent {
+                event: "ready".to_owned(),
+                route: published.identity.name.clone(),
+                record_key: published.identity.record_key.to_string(),
+                profile: profile_home.profile().to_owned(),
+            },
+            text,
+        );
+        routes.push(published);
+    }
+    if routes.len() > 1 {
+        output.note(format_args!(
+            "Listening for messages on {} routes.",
+            routes.len()
+        ));
     }
 
-    let mut route_blob = allocate_private_route_with_retry(
-        &api,
-        ROUTE_ALLOCATE_MAX_ATTEMPTS,
-        ROUTE_ALLOCATE_RETRY_DELAY,
-    )
-    .await?;
-    router
-        .set_dht_value(
-            identity.record_key.clone(),
-            0,
-            route_blob.blob.clone(),
-            None,
-        )
-        .await?;
-
-    println!(
-        "Created route information and stored it under record key {} for {}",
-        identity.record_key,
-        profile_home.profile()
-    );
-    println!("Listening for messages.");
+    for (index, published) in routes.iter().enumerate() {
+        for inbound in published.drain_mailbox().await {
+            if inbound_handler.handle(inbound).await {
+                printed_messages += 1;
+                printed_by_route[index] += 1;
+            }
+        }
+    }
 
+    let mut last_outbox_flush: Option<Instant> = None;
     loop {
         if let Some(limit) = message_count_limit
-            && printed_messages.load(Ordering::Acquire) >= limit
+            && printed_messages >= limit
         {
             break;
         }
@@ -145,72 +268,891 @@ pub async fn listen_on_named_route(
             _ = tokio::signal::ctrl_c() => {
                 break;
             }
+            Some(mut inbound) = inbound_rx.recv() => {
+                let index = tag_route(&routes, &mut inbound);
+                if inbound_handler.handle(inbound).await {
+                    printed_messages += 1;
+                    if let Some(index) = index {
+                        printed_by_route[index] += 1;
+                        routes[index].record_message();
+                    }
+                }
+            }
             () = tokio::time::sleep(Duration::from_millis(250)) => {
-                let should_rotate = {
-                    let mut guard = dead_routes
-                        .lock()
-                        .map_err(|_poison| eyre::eyre!("dead route state lock poisoned"))?;
-                    guard.remove(&route_blob.route_id)
-                };
+                for published in &mut routes {
+                    if let Err(error) = published.rotate_if_due(&api, &dead_routes).await {
+                        inbound_handler.save_sequences();
+                        return Err(error);
+                    }
+                }
+
+                let flush_due = last_outbox_flush
+                    .is_none_or(|flushed| flushed.elapsed() >= OUTBOX_FLUSH_INTERVAL);
+                if flush_due {
+                    last_outbox_flush = Some(Instant::now());
+                    if let Err(error) =
+                        flush_outbox(&api, &router, profile_home, &my_keypair, None, output).await
+                    {
+                        warn!(%error, "failed to flush outbox");
+                    }
+                }
+            }
+        }
+    }
+
+    inbound_handler.save_sequences();
+    if let Some(summary) = inbound_handler.dropped_summary() {
+        output.note(summary);
+    }
+    for (published, messages) in routes.into_iter().zip(printed_by_route) {
+        let route = published.identity.name.clone();
+        published.unpublish(&api).await;
+        if output.is_json() {
+            output.event(
+                &StoppedEvent {
+                    event: "stopped".to_owned(),
+                    route,
+                    messages,
+                },
+                "",
+            );
+        }
+    }
+    shutdown_api(api).await;
+    Ok(())
+}
+
+/// Tag a message with the published route it arrived on, returning that route's index.
+///
+/// With a single route every message belongs to it. Otherwise messages over a private
+/// route that has since been released, or sent to the node directly, are left untagged.
+fn tag_route(routes: &[PublishedRoute], inbound: &mut InboundMessage) -> Option<usize> {
+    let index = match routes {
+        [_] => 0,
+        _ => {
+            let route_id = inbound.route_id.as_ref()?;
+            routes
+                .iter()
+                .position(|published| published.owns_route(route_id))?
+        }
+    };
+    inbound.route = Some(routes[index].identity.name.clone());
+    Some(index)
+}
+
+/// Load a named local route identity, pointing at `route add` when it is missing.
+///
+/// # Errors
+///
+/// Returns an error if the route does not exist or route data cannot be loaded.
+pub(crate) fn load_route_identity(
+    profile_home: &ProfileHome,
+    route_name: &str,
+) -> Result<LocalRouteIdentity> {
+    app_state::local_route_identity(profile_home, route_name)?.ok_or_else(|| {
+        eyre::eyre!(
+            "Route '{}' does not exist. Create it with '{}'.",
+            route_name,
+            Cli::display_invocation(&RouteArgs {
+                command: RouteCommand::Add(RouteAddArgs {
+                    name: route_name.to_owned(),
+                    listen: true,
+                    mailbox: false,
+                }),
+            })
+        )
+    })
+}
+
+/// A local route identity whose private route is currently published in its DHT record.
+pub(crate) struct PublishedRoute {
+    pub identity: LocalRouteIdentity,
+    /// Routing context using the identity's safety settings, for the route's DHT record.
+    router: RoutingContext,
+    route_blob: RouteBlob,
+    output: EventOutput,
+    /// When to replace the private route before Veilid reports it dead.
+    pub rotation: RouteRotationPolicy,
+    published_at: Instant,
+    messages_since_rotation: u32,
+    /// Replaced private routes kept allocated until their grace period ends.
+    retiring: Vec<(RouteId, Instant)>,
+}
+
+impl PublishedRoute {
+    /// Open (or create) the identity's route record and publish a freshly allocated
+    /// private route, along with the mailbox writer when the route has a mailbox.
+    ///
+    /// The record is accessed and the private route allocated with the identity's
+    /// [`LocalRouteIdentity::safety`] settings.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the safety settings are rejected, the record cannot be opened
+    /// or created, no private route can be allocated, or the DHT writes fail.
+    pub async fn publish(
+        api: &veilid_core::VeilidAPI,
+        identity: LocalRouteIdentity,
+        output: EventOutput,
+    ) -> Result<Self> {
+        let router = safety::routing_context(api, identity.safety)?;
+        if router
+            .open_dht_record(identity.record_key.clone(), Some(identity.keypair.clone()))
+            .await
+            .is_err()
+        {
+            let _ = router
+                .create_dht_record(
+                    CRYPTO_KIND_VLD0,
+                    mailbox::route_record_schema(api, identity.mailbox_writer.as_ref())?,
+                    Some(identity.keypair.clone()),
+                )
+                .await?;
+        }
+
+        let route_blob = allocate_private_route_with_retry(
+            api,
+            identity.safety,
+            ROUTE_ALLOCATE_MAX_ATTEMPTS,
+            ROUTE_ALLOCATE_RETRY_DELAY,
+            output,
+        )
+        .await?;
+        router
+            .set_dht_value(
+                identity.record_key.clone(),
+                0,
+                route_blob.blob.clone(),
+                None,
+            )
+            .await?;
+        mailbox::publish_mailbox_writer(&router, &identity).await?;
+
+        Ok(Self {
+            identity,
+            router,
+            route_blob,
+            output,
+            rotation: RouteRotationPolicy::default(),
+            published_at: Instant::now(),
+            messages_since_rotation: 0,
+            retiring: Vec::new(),
+        })
+    }
+
+    /// Whether `route_id` is this route's private route or one still in its grace period.
+    pub fn owns_route(&self, route_id: &RouteId) -> bool {
+        &self.route_blob.route_id == route_id || self.retiring.iter().any(|(id, _)| id == route_id)
+    }
+
+    /// Count an accepted message towards [`RouteRotationPolicy::after_messages`].
+    pub fn record_message(&mut self) {
+        self.messages_since_rotation = self.messages_since_rotation.saturating_add(1);
+    }
+
+    /// Why the private route should be replaced now, if it should.
+    fn rotation_due(&self, dead: bool) -> Option<&'static str> {
+        if dead {
+            return Some("dead");
+        }
+        if self.rotation.interval_secs > 0
+            && self.published_at.elapsed() >= Duration::from_secs(self.rotation.interval_secs)
+        {
+            return Some("interval");
+        }
+        if self.rotation.after_messages > 0
+            && self.messages_since_rotation >= self.rotation.after_messages
+        {
+            return Some("messages");
+        }
+        None
+    }
+
+    /// Take every message waiting in the route's mailbox.
+    ///
+    /// Slots that cannot be read or cleared are logged and skipped by
+    /// [`mailbox::drain_mailbox`] rather than stopping the listener.
+    pub async fn drain_mailbox(&self) -> Vec<InboundMessage> {
+        let drained = mailbox::drain_mailbox(&self.router, &self.identity).await;
+        if !drained.is_empty() {
+            self.output.note(format_args!(
+                "Draining {} messages from the mailbox.",
+                drained.len()
+            ));
+        }
+        drained
+            .into_iter()
+            .map(|payload| InboundMessage {
+                payload,
+                call_id: None,
+                route_id: None,
+                route: Some(self.identity.name.clone()),
+            })
+            .collect()
+    }
+
+    /// Replace the private route when Veilid reported it dead or the rotation policy
+    /// says it is due, and release replaced routes whose grace period has ended.
+    ///
+    /// Rotation is make-before-break: the new route is published to the record before
+    /// the old one is retired, and a live old route stays allocated for
+    /// [`RouteRotationPolicy::grace_secs`] so senders holding it can finish. Dead routes
+    /// are released at once.
+    ///
+    /// Returns whether the route was rotated.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the dead-route state is poisoned, no replacement route can be
+    /// allocated, or the DHT write fails.
+    pub async fn rotate_if_due(
+        &mut self,
+        api: &veilid_core::VeilidAPI,
+        dead_routes: &Mutex<HashSet<RouteId>>,
+    ) -> Result<bool> {
+        let dead = {
+            let mut guard = dead_routes
+                .lock()
+                .map_err(|_poison| eyre::eyre!("dead route state lock poisoned"))?;
+            let now = Instant::now();
+            self.retiring.retain(|(route_id, release_at)| {
+                let keep = !guard.remove(route_id) && *release_at > now;
+                if !keep {
+                    let _ = api.release_private_route(route_id.clone());
+                }
+                keep
+            });
+            guard.remove(&self.route_blob.route_id)
+        };
+        let Some(reason) = self.rotation_due(dead) else {
+            return Ok(false);
+        };
+
+        let route_blob = allocate_private_route_with_retry(
+            api,
+            self.identity.safety,
+            ROUTE_ALLOCATE_MAX_ATTEMPTS,
+            ROUTE_ALLOCATE_RETRY_DELAY,
+            self.output,
+        )
+        .await?;
+        self.router
+            .set_dht_value(
+                self.identity.record_key.clone(),
+                0,
+                route_blob.blob.clone(),
+                None,
+            )
+            .await?;
+        let old = std::mem::replace(&mut self.route_blob, route_blob);
+        if dead {
+            let _ = api.release_private_route(old.route_id);
+        } else {
+            self.retiring.push((
+                old.route_id,
+                Instant::now() + Duration::from_secs(self.rotation.grace_secs),
+            ));
+        }
+        self.published_at = Instant::now();
+        self.messages_since_rotation = 0;
+
+        let text = if dead {
+            "Route changed; republished route information.".to_owned()
+        } else {
+            format!(
+                "Rotated route '{}' ({reason} policy); the old route is kept for {}.",
+                self.identity.name,
+                humantime::format_duration(Duration::from_secs(self.rotation.grace_secs))
+            )
+        };
+        self.output.event(
+            &RouteRepublishedEvent {
+                event: "route_republished".to_owned(),
+                route: self.identity.name.clone(),
+                record_key: self.identity.record_key.to_string(),
+                reason: reason.to_owned(),
+            },
+            text,
+        );
+        Ok(true)
+    }
+
+    /// Mark the route record offline, then release the private route and close the record.
+    pub async fn unpublish(self, api: &veilid_core::VeilidAPI) {
+        let _ = self
+            .router
+            .set_dht_value(self.identity.record_key.clone(), 0, Vec::new(), None)
+            .await;
+        self.output.note(format_args!(
+            "Stopped listening on '{}'; route record marked offline (empty route data).",
+            self.identity.name
+        ));
 
-                if should_rotate {
-                    route_blob = allocate_private_route_with_retry(
-                        &api,
-                        ROUTE_ALLOCATE_MAX_ATTEMPTS,
-                        ROUTE_ALLOCATE_RETRY_DELAY,
+        let _ = api.release_private_route(self.route_blob.route_id);
+        for (route_id, _) in self.retiring {
+            let _ = api.release_private_route(route_id);
+        }
+        let _ = self
+            .router
+            .close_dht_record(self.identity.record_key.clone())
+            .await;
+    }
+}
+
+/// A payload delivered to this node, with the call id to answer when it arrived via `app_call`.
+#[derive(Debug)]
+pub(crate) struct InboundMessage {
+    payload: Vec<u8>,
+    call_id: Option<OperationId>,
+    /// The private route the payload arrived over, if any.
+    route_id: Option<RouteId>,
+    /// Name of the local route the payload was addressed to, once known.
+    route: Option<String>,
+}
+
+/// Opens inbound envelopes and prints them under the sender's known-user name.
+pub(crate) struct InboundHandler<'a> {
+    api: &'a veilid_core::VeilidAPI,
+    profile_home: &'a ProfileHome,
+    keypair: &'a KeyPair,
+    /// Known-user names by public key text.
+    known_users: HashMap<String, String>,
+    /// Local room names by room id.
+    rooms: HashMap<RoomId, String>,
+    /// Store reply routes attached by known users instead of only suggesting the command.
+    pub save_reply_routes: bool,
+    /// Reply routes already suggested this session, so each is mentioned once.
+    offered_reply_routes: HashSet<String>,
+    /// How messages from unknown senders are treated.
+    policy: SenderPolicy,
+    /// Public key text of senders whose messages are dropped silently.
+    blocked: HashSet<String>,
+    /// Messages from unknown senders dropped because the quarantine was full.
+    quarantine_dropped: u64,
+    /// Inbound rate limits by route name.
+    limiters: BTreeMap<String, InboundLimiter>,
+    /// The strictest of the routes' limits, for messages whose route is not known.
+    untagged_limiter: InboundLimiter,
+    /// Recent message ids and per-sender sequence numbers.
+    sequencer: InboundSequencer,
+    /// When changed sequence numbers were last written to the profile.
+    sequences_saved_at: Instant,
+    /// Where messages and notices are printed.
+    pub output: EventOutput,
+    /// Route name for messages that are not tagged with the route they arrived on.
+    pub route: Option<String>,
+    /// Prefix printed messages with their route, for listeners on several routes.
+    pub tag_routes: bool,
+    /// Shell hooks run for each accepted chat message.
+    hooks: HookRunner,
+}
+
+impl<'a> InboundHandler<'a> {
+    /// Snapshot the known users, rooms, sender policy, blocklist, received sequence
+    /// numbers, and hooks used to screen, label, and act on inbound messages.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if profile data cannot be loaded.
+    pub fn load(
+        api: &'a veilid_core::VeilidAPI,
+        profile_home: &'a ProfileHome,
+        keypair: &'a KeyPair,
+    ) -> Result<Self> {
+        let known_users = app_state::list_known_users(profile_home)?
+            .into_iter()
+            .map(|entry| (entry.pubkey.to_string(), entry.name))
+            .collect();
+        let rooms = app_state::list_rooms(profile_home)?
+            .into_iter()
+            .map(|room| (room.room_id, room.name))
+            .collect();
+        let blocked = app_state::list_blocked_senders(profile_home)?
+            .iter()
+            .map(ToString::to_string)
+            .collect();
+        Ok(Self {
+            api,
+            profile_home,
+            keypair,
+            known_users,
+            rooms,
+            save_reply_routes: false,
+            offered_reply_routes: HashSet::new(),
+            policy: app_state::sender_policy(profile_home)?,
+            blocked,
+            quarantine_dropped: 0,
+            limiters: BTreeMap::new(),
+            untagged_limiter: InboundLimiter::new(rate_limit::strictest([])),
+            sequencer: InboundSequencer::new(app_state::received_sequences(profile_home)?),
+            sequences_saved_at: Instant::now(),
+            output: EventOutput::Text,
+            route: None,
+            tag_routes: false,
+            hooks: HookRunner::load(profile_home)?,
+        })
+    }
+
+    /// Verify, decrypt, and print one inbound envelope.
+    ///
+    /// Envelopes that fail to decode, verify, or decrypt are dropped with a notice
+    /// instead of being printed raw. Blocked senders are dropped silently, and
+    /// unknown senders are handled according to the profile's [`SenderPolicy`].
+    /// Messages over their route's [`Self::limit_route`] limits, or the strictest
+    /// limits when their route is not known, are dropped and counted. Repeats of a
+    /// recently seen message id are acknowledged again but not shown, and skipped
+    /// sequence numbers are reported. Calls are answered with a signed receipt for the
+    /// message id, or an empty reply when the envelope could not be opened or was
+    /// dropped.
+    ///
+    /// Returns whether a new chat message was accepted, so `--count` is not used up
+    /// by dropped, quarantined, or repeated traffic, receipts, or file chunks.
+    pub async fn handle(&mut self, inbound: InboundMessage) -> bool {
+        let route = inbound.route.or_else(|| self.route.clone());
+        if !self.limiter(route.as_deref()).admit_any() {
+            debug!("dropped inbound message over the global rate limit");
+            if let Some(call_id) = inbound.call_id {
+                let _ = self.api.app_call_reply(call_id, Vec::new()).await;
+            }
+            return false;
+        }
+
+        let opened = chat_crypto::open_message(self.api, self.keypair, &inbound.payload).await;
+        let (accepted, counted) = match &opened {
+            Ok(envelope) => match self.screen(envelope) {
+                Screening::Drop => (false, false),
+                _ if !self.admit_sender(envelope, route.as_deref()) => (false, false),
+                _ if self.is_duplicate(envelope) => (true, false),
+                Screening::Deliver => {
+                    let accepted = self.deliver(envelope, route.as_deref()).await;
+                    if !accepted {
+                        // Let the sender's retry through instead of acknowledging it as a repeat.
+                        self.sequencer.forget(envelope.message_id);
+                    }
+                    (accepted, accepted && envelope.kind == MessageKind::Chat)
+                }
+                Screening::Quarantine => (self.quarantine(envelope), false),
+            },
+            Err(error) => {
+                self.output
+                    .note(format_args!("Dropped inbound message: {error}"));
+                (false, false)
+            }
+        };
+
+        if let Some(call_id) = inbound.call_id {
+            let reply = match &opened {
+                Ok(envelope) if accepted => chat_crypto::seal_receipt(
+                    self.api,
+                    self.keypair,
+                    &envelope.sender,
+                    envelope.message_id,
+                )
+                .await
+                .map(|sealed| sealed.payload)
+                .unwrap_or_default(),
+                _ => Vec::new(),
+            };
+            let _ = self.api.app_call_reply(call_id, reply).await;
+        }
+
+        counted
+    }
+
+    /// Check an envelope against recently seen message ids, reporting any sequence
+    /// numbers skipped before it or a late arrival.
+    ///
+    /// Only known users' sequence numbers are tracked, so unknown and quarantined
+    /// senders cannot grow the persisted state; their messages are still deduplicated
+    /// by id.
+    fn is_duplicate(&mut self, envelope: &Envelope) -> bool {
+        let sender = envelope.sender.to_string();
+        let sequence = envelope
+            .extensions
+            .sequence
+            .filter(|_| self.known_users.contains_key(&sender));
+        let arrival = self
+            .sequencer
+            .observe(&sender, envelope.message_id, sequence);
+        let label = self.known_users.get(&sender).unwrap_or(&sender);
+        match arrival {
+            Arrival::Duplicate => {
+                debug!(%sender, message_id = %envelope.message_id, "dropped duplicate message");
+                return true;
+            }
+            Arrival::InOrder => {}
+            Arrival::Late => {
+                if let Some(sequence) = sequence {
+                    self.output.note(format_args!(
+                        "Message {sequence} from {label} arrived out of order."
+                    ));
+                }
+                return false;
+            }
+            Arrival::Gap { first, last } if first == last => {
+                self.output
+                    .note(format_args!("Missed message {first} from {label}."));
+            }
+            Arrival::Gap { first, last } => {
+                self.output.note(format_args!(
+                    "Missed {} messages from {label} ({first} to {last}).",
+                    last - first + 1
+                ));
+            }
+        }
+        if self.sequencer.unsaved_len() >= SEQUENCE_SAVE_BATCH
+            || self.sequences_saved_at.elapsed() >= SEQUENCE_SAVE_INTERVAL
+        {
+            self.save_sequences();
+        }
+        false
+    }
+
+    /// Write sequence numbers received since the last save to the profile.
+    ///
+    /// Called in batches while handling messages; call it again when the listener
+    /// stops so the last batch is kept.
+    pub fn save_sequences(&mut self) {
+        self.sequences_saved_at = Instant::now();
+        let received = self.sequencer.take_unsaved();
+        if let Err(error) = app_state::set_received_sequences(self.profile_home, &received) {
+            warn!(%error, "failed to record received sequence numbers");
+        }
+    }
+
+    /// Apply inbound rate limits to messages arriving on `route`.
+    ///
+    /// Messages that cannot be tied to a route get the strictest limits of all the
+    /// routes, so untagged traffic cannot bypass them.
+    pub fn limit_route(&mut self, route: &str, limits: RouteRateLimits) {
+        let _ = self
+            .limiters
+            .insert(route.to_owned(), InboundLimiter::new(limits));
+        self.untagged_limiter = InboundLimiter::new(rate_limit::strictest(
+            self.limiters.values().map(InboundLimiter::limits),
+        ));
+    }
+
+    /// The limiter for messages on `route`, or the listener-wide one when the route is
+    /// not known.
+    fn limiter(&mut self, route: Option<&str>) -> &mut InboundLimiter {
+        match route.and_then(|route| self.limiters.get_mut(route)) {
+            Some(limiter) => limiter,
+            None => &mut self.untagged_limiter,
+        }
+    }
+
+    /// A summary of traffic dropped by the rate limits, if any was.
+    ///
+    /// With limits on several routes, each route's summary is named.
+    #[must_use]
+    pub fn dropped_summary(&self) -> Option<String> {
+        let mut summaries = self
+            .limiters
+            .iter()
+            .filter_map(|(route, limiter)| {
+                let summary = limiter.summary()?;
+                Some(if self.limiters.len() > 1 {
+                    format!("On route '{route}': {summary}")
+                } else {
+                    summary
+                })
+            })
+            .collect::<Vec<_>>();
+        if let Some(summary) = self.untagged_limiter.summary() {
+            summaries.push(format!("On messages without a known route: {summary}"));
+        }
+        if self.quarantine_dropped > 0 {
+            summaries.push(format!(
+                "Dropped {} messages from unknown senders over the quarantine limits ({} per sender, {} in total).",
+                self.quarantine_dropped,
+                app_state::MAX_QUARANTINE_PER_SENDER,
+                app_state::MAX_QUARANTINE_TOTAL
+            ));
+        }
+        (!summaries.is_empty()).then(|| summaries.join("\n"))
+    }
+
+    /// Take a token from the sender's rate limit bucket for `route`.
+    ///
+    /// File chunks, which only known users get through screening, are exempt: a
+    /// transfer sends them back to back and a dropped chunk stalls it, while the global
+    /// bucket still caps them with everything else.
+    fn admit_sender(&mut self, envelope: &Envelope, route: Option<&str>) -> bool {
+        if envelope.kind == MessageKind::FileChunk {
+            return true;
+        }
+        let sender = envelope.sender.to_string();
+        let label = self.known_users.get(&sender).unwrap_or(&sender).clone();
+        if self.limiter(route).admit_sender(&sender, &label) {
+            return true;
+        }
+        debug!(%sender, "dropped inbound message over the sender rate limit");
+        false
+    }
+
+    /// Decide what to do with an opened envelope based on the blocklist and sender policy.
+    fn screen(&self, envelope: &Envelope) -> Screening {
+        let sender = envelope.sender.to_string();
+        if self.blocked.contains(&sender) {
+            debug!(%sender, "dropped message from blocked sender");
+            return Screening::Drop;
+        }
+        if self.known_users.contains_key(&sender) {
+            return Screening::Deliver;
+        }
+        if envelope.kind == MessageKind::FileChunk {
+            // Files are only written to disk for known users, whatever the policy.
+            debug!(%sender, "dropped file chunk from unknown sender");
+            return Screening::Drop;
+        }
+        match self.policy {
+            SenderPolicy::AllowAll => Screening::Deliver,
+            SenderPolicy::KnownOnly => {
+                debug!(%sender, "dropped message from unknown sender");
+                Screening::Drop
+            }
+            SenderPolicy::Quarantine if envelope.kind == MessageKind::Chat => Screening::Quarantine,
+            SenderPolicy::Quarantine => Screening::Drop,
+        }
+    }
+
+    /// Print or store an accepted envelope, returning whether it should be acknowledged.
+    ///
+    /// Chat lines sent to a room are prefixed with the room's local name, or its id
+    /// when the room is not known here, and with the route they arrived on when
+    /// [`Self::tag_routes`] is set. A reply route attached by a known user is
+    /// saved or suggested, see [`Self::save_reply_routes`]. File chunks are written to
+    /// the profile's downloads folder and go unacknowledged if they cannot be stored,
+    /// so the sender retries them.
+    async fn deliver(&mut self, envelope: &Envelope, route: Option<&str>) -> bool {
+        let sender = envelope.sender.to_string();
+        let known_user = self.known_users.get(&sender).cloned();
+        let label = known_user.as_deref().unwrap_or(&sender);
+        match envelope.kind {
+            MessageKind::Chat => {
+                let body = String::from_utf8_lossy(&envelope.body);
+                let room = envelope.extensions.room_id.map(|room_id| {
+                    self.rooms
+                        .get(&room_id)
+                        .cloned()
+                        .unwrap_or_else(|| format!("room {room_id}"))
+                });
+                let mut line = match &room {
+                    Some(room) => format!("[{room}] {label}> {body}"),
+                    None => format!("{label}> {body}"),
+                };
+                if self.tag_routes
+                    && let Some(route) = route
+                {
+                    line.insert_str(0, &format!("@{route} "));
+                }
+                self.output.event(
+                    &MessageEvent {
+                        event: "message".to_owned(),
+                        message_id: envelope.message_id.to_string(),
+                        sender: sender.clone(),
+                        known_user: known_user.clone(),
+                        route: route.map(str::to_owned),
+                        room: room.clone(),
+                        sequence: envelope.extensions.sequence,
+                        timestamp_ms: envelope.timestamp_ms,
+                        body: body.to_string(),
+                    },
+                    line,
+                );
+                self.hooks.run(&HookMessage {
+                    message_id: envelope.message_id.to_string(),
+                    sender_pubkey: sender.clone(),
+                    known_user: known_user.clone(),
+                    route: route.map(str::to_owned),
+                    room,
+                    timestamp_ms: envelope.timestamp_ms,
+                    body: body.to_string(),
+                });
+                if let Some(known_user) = &known_user
+                    && let Err(error) = app_state::append_chat_history(
+                        self.profile_home,
+                        known_user,
+                        &ChatHistoryEntry {
+                            timestamp_ms: envelope.timestamp_ms,
+                            direction: MessageDirection::Inbound,
+                            message_id: envelope.message_id.to_string(),
+                            body: body.into_owned(),
+                        },
                     )
-                    .await?;
-                    router
-                        .set_dht_value(
-                            identity.record_key.clone(),
-                            0,
-                            route_blob.blob.clone(),
-                            None,
-                        )
-                        .await?;
-                    println!("Route changed; republished route information.");
+                {
+                    warn!(%error, "failed to record chat history");
+                }
+                if let (Some(known_user), Some(reply_route)) =
+                    (&known_user, &envelope.extensions.reply_route)
+                {
+                    self.offer_reply_route(known_user, reply_route);
+                }
+                true
+            }
+            MessageKind::FileChunk => self.receive_file_chunk(envelope, label).await,
+            MessageKind::Receipt => {
+                self.output
+                    .note(format_args!("Ignored unexpected receipt from {label}."));
+                true
+            }
+            MessageKind::Unknown(_) => {
+                self.output.note(format_args!(
+                    "Ignored {} message from {label}; upgrade to read it.",
+                    envelope.kind
+                ));
+                true
+            }
+        }
+    }
+
+    async fn receive_file_chunk(&self, envelope: &Envelope, label: &str) -> bool {
+        let Some(header) = &envelope.extensions.file_chunk else {
+            self.output.note(format_args!(
+                "Dropped file chunk from {label}: missing file chunk header."
+            ));
+            return false;
+        };
+        match file_transfer::receive_chunk(
+            self.api,
+            self.profile_home,
+            &envelope.sender,
+            header,
+            &envelope.body,
+        )
+        .await
+        {
+            Ok(ChunkOutcome::Stored { received, count }) => {
+                // Report roughly every tenth of the file rather than every chunk.
+                if received == 1 || received * 10 / count != (received - 1) * 10 / count {
+                    self.output.note(format_args!(
+                        "Receiving {} from {label}: {received} of {count} chunks.",
+                        header.file_name
+                    ));
                 }
+                true
+            }
+            Ok(ChunkOutcome::Duplicate) => true,
+            Ok(ChunkOutcome::Completed { path }) => {
+                self.output.note(format_args!(
+                    "Received {} from {label}; saved to {}.",
+                    header.file_name,
+                    path.display()
+                ));
+                true
+            }
+            Err(error) => {
+                self.output
+                    .note(format_args!("Dropped file chunk from {label}: {error}"));
+                false
             }
         }
     }
 
-    let _ = router
-        .set_dht_value(identity.record_key.clone(), 0, Vec::new(), None)
-        .await;
-    println!("Stopped listening; route record marked offline (empty route data).");
+    /// Hold a chat message from an unknown sender for review, stamped with the time it
+    /// arrived here, returning whether it was stored.
+    fn quarantine(&mut self, envelope: &Envelope) -> bool {
+        let body = String::from_utf8_lossy(&envelope.body);
+        let received_at_ms =
+            u64::try_from(chrono::Utc::now().timestamp_millis()).unwrap_or_default();
+        match app_state::quarantine_message(
+            self.profile_home,
+            &envelope.sender,
+            received_at_ms,
+            &envelope.message_id.to_string(),
+            &body,
+        ) {
+            Ok(None) => {
+                debug!(sender = %envelope.sender, "dropped message over the quarantine limits");
+                self.quarantine_dropped += 1;
+                return false;
+            }
+            Ok(Some(entry)) => self.output.note(format_args!(
+                "Quarantined message {} from unknown sender {}; review it with '{}'.",
+                entry.id,
+                envelope.sender,
+                Cli::display_invocation(&crate::cli::Command::Inbox(InboxArgs {
+                    command: InboxCommand::Quarantine(InboxQuarantineArgs {
+                        command: InboxQuarantineCommand::List(InboxQuarantineListArgs),
+                    }),
+                }))
+            )),
+            Err(error) => {
+                warn!(%error, "failed to quarantine message");
+                return false;
+            }
+        }
+        true
+    }
+}
 
-    let _ = api.release_private_route(route_blob.route_id);
-    let _ = router.close_dht_record(identity.record_key.clone()).await;
-    api.shutdown().await;
-    Ok(())
+/// What the sender policy decided for an opened envelope.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+enum Screening {
+    Deliver,
+    Quarantine,
+    Drop,
 }
 
-fn route_update_callback(
+impl InboundHandler<'_> {
+    fn offer_reply_route(&mut self, known_user: &str, reply_route: &RecordKey) {
+        let known = app_state::route_keys_for_known_user(self.profile_home, known_user)
+            .is_ok_and(|keys| keys.contains(reply_route));
+        if known {
+            return;
+        }
+
+        if self.save_reply_routes {
+            match app_state::add_route_key(self.profile_home, known_user, reply_route) {
+                Ok(()) => self.output.note(format_args!(
+                    "Saved reply route {reply_route} for {known_user}."
+                )),
+                Err(error) => warn!(%error, "failed to save reply route"),
+            }
+            return;
+        }
+
+        if self.offered_reply_routes.insert(reply_route.to_string()) {
+            self.output.note(format_args!(
+                "{known_user} attached reply route {reply_route}. Save it with '{}'.",
+                Cli::display_invocation(&crate::cli::Command::KnownUser(KnownUserArgs {
+                    command: KnownUserCommand::Route(KnownUserRouteArgs {
+                        command: KnownUserRouteCommand::Add(KnownUserRouteAddArgs {
+                            known_user: known_user.to_owned(),
+                            record_key: reply_route.to_string(),
+                        }),
+                    }),
+                }))
+            ));
+        }
+    }
+}
+
+pub(crate) fn route_update_callback(
     public_internet_ready: Arc<AtomicBool>,
-    known_user_map: Arc<Mutex<std::collections::HashMap<String, String>>>,
+    inbound_tx: mpsc::UnboundedSender<InboundMessage>,
     dead_routes: Arc<Mutex<HashSet<RouteId>>>,
-    printed_messages: Arc<AtomicUsize>,
 ) -> crate::cli::veilid_runtime::UpdateCallback {
     Arc::new(move |update: VeilidUpdate| match update {
         VeilidUpdate::Attachment(attachment) => {
             public_internet_ready.store(attachment.public_internet_ready, Ordering::Release);
         }
         VeilidUpdate::AppMessage(message) => {
-            let text = String::from_utf8_lossy(message.message()).to_string();
-            if let Some((pubkey, body)) = text.split_once('|') {
-                if let Ok(guard) = known_user_map.lock() {
-                    if let Some(name) = guard.get(pubkey) {
-                        println!("{name}> {body}");
-                    } else {
-                        println!("{pubkey}> {body}");
-                    }
-                } else {
-                    println!("{pubkey}> {body}");
-                }
-            } else {
-                println!("INCOMING> {text}");
-            }
-            printed_messages.fetch_add(1, Ordering::AcqRel);
+            let _ = inbound_tx.send(InboundMessage {
+                payload: message.message().to_vec(),
+                call_id: None,
+                route_id: message.route_id().cloned(),
+                route: None,
+            });
+        }
+        VeilidUpdate::AppCall(call) => {
+            let _ = inbound_tx.send(InboundMessage {
+                payload: call.message().to_vec(),
+                call_id: Some(call.id()),
+                route_id: call.route_id().cloned(),
+                route: None,
+            });
         }
         VeilidUpdate::RouteChange(change) => {
             if let Ok(mut guard) = dead_routes.lock() {
@@ -223,49 +1165,76 @@ fn route_update_callback(
     })
 }
 
+/// Wait until the node reports public internet readiness, which `public_internet_ready`
+/// tracks from attachment updates.
+///
+/// On a private network, where there may be no public internet to reach, local network
+/// readiness is accepted as well.
+///
+/// # Errors
+///
+/// Returns an error if the node state cannot be read or readiness takes over two
+/// minutes.
 pub async fn wait_for_public_internet_ready(
     api: &veilid_core::VeilidAPI,
     public_internet_ready: &AtomicBool,
+    output: EventOutput,
 ) -> Result<()> {
-    if !public_internet_ready.load(Ordering::Acquire) {
-        let state = api.get_state().await?;
-        if state.attachment.public_internet_ready {
-            public_internet_ready.store(true, Ordering::Release);
-        }
+    let private_network = veilid_runtime::on_private_network();
+    if public_internet_ready.load(Ordering::Acquire)
+        || attachment_ready(api, private_network).await?
+    {
+        public_internet_ready.store(true, Ordering::Release);
+        return Ok(());
     }
 
-    if !public_internet_ready.load(Ordering::Acquire) {
-        println!("Waiting for public internet readiness...");
-        let start = Instant::now();
-        let timeout = Duration::from_secs(120);
-        while !public_internet_ready.load(Ordering::Acquire) {
-            if start.elapsed() >= timeout {
-                bail!(
-                    "Timed out waiting for public internet readiness; retry when network attachment improves."
-                );
-            }
-            tokio::time::sleep(Duration::from_millis(250)).await;
+    let readiness = if private_network {
+        "network readiness"
+    } else {
+        "public internet readiness"
+    };
+    output.note(format_args!("Waiting for {readiness}..."));
+    let start = Instant::now();
+    let timeout = Duration::from_secs(120);
+    while !public_internet_ready.load(Ordering::Acquire) {
+        if start.elapsed() >= timeout {
+            bail!("Timed out waiting for {readiness}; retry when network attachment improves.");
+        }
+        tokio::time::sleep(Duration::from_millis(250)).await;
+        // Attachment updates only carry public readiness into the flag.
+        if private_network && attachment_ready(api, true).await? {
+            public_internet_ready.store(true, Ordering::Release);
+            break;
         }
     }
     Ok(())
 }
 
+/// Whether the node's attachment is ready to route, counting local network readiness
+/// when `private_network` is set.
+async fn attachment_ready(api: &veilid_core::VeilidAPI, private_network: bool) -> Result<bool> {
+    let attachment = api.get_state().await?.attachment;
+    Ok(attachment.public_internet_ready || (private_network && attachment.local_network_ready))
+}
+
 async fn allocate_private_route_with_retry(
     api: &veilid_core::VeilidAPI,
+    route_safety: RouteSafety,
     max_attempts: usize,
     retry_delay: Duration,
+    output: EventOutput,
 ) -> Result<RouteBlob> {
     for attempt in 1..=max_attempts {
-        match api.new_private_route().await {
+        match safety::new_private_route(api, route_safety).await {
             Ok(route_blob) => return Ok(route_blob),
             Err(error) => {
                 if is_try_again_route_allocation_error(&error) && attempt < max_attempts {
-                    println!(
+                    output.note(format_args!(
                         "Route allocation attempt {} of {} failed transiently; retrying in {}s...",
                         attempt,
                         max_attempts,
                         retry_delay.as_secs()
-                    );
+                    ));
                     tokio::time::sleep(retry_delay).await;
                     continue;
                 }
@@ -289,11 +1258,20 @@ fn is_try_again_route_allocation_error(error: &veilid_core::VeilidAPIError) -> b
 
 impl ToArgs for RouteListenArgs {
     fn to_args(&self) -> Vec<std::ffi::OsString> {
-        let mut args: Vec<std::ffi::OsString> = vec![self.name.clone().into()];
+        let mut args: Vec<std::ffi::OsString> = Vec::new();
+        if let Some(name) = &self.name {
+            args.push(name.clone().into());
+        }
+        if self.all {
+            args.push("--all".into());
+        }
         if let Some(count) = self.count {
             args.push("--count".into());
             args.push(count.to_string().into());
         }
+        if self.save_reply_routes {
+            args.push("--save-reply-routes".into());
+        }
         args
     }
 }
@@ -1,8 +1,11 @@
 pub(crate) mod add;
+pub(crate) mod limit;
 pub(crate) mod list;
 pub(crate) mod listen;
 pub(crate) mod remove;
+pub(crate) mod rotation;
 mod route_cli;
+pub(crate) mod safety;
 pub(crate) mod show;
 
 pub use route_cli::*;
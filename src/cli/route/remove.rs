@@ -1,6 +1,9 @@
 use crate::cli::InvokeContext;
 use crate::cli::ToArgs;
 use crate::cli::app_state;
+use crate::cli::events::EventOutput;
+use crate::cli::route::listen::wait_for_public_internet_ready;
+use crate::cli::veilid_runtime::shutdown_api;
 use crate::cli::veilid_runtime::start_api_for_profile;
 use arbitrary::Arbitrary;
 use eyre::Result;
@@ -10,8 +13,6 @@ use std::fmt;
 use std::sync::Arc;
 use std::sync::atomic::AtomicBool;
 use std::sync::atomic::Ordering;
-use std::time::Duration;
-use std::time::Instant;
 use veilid_core::VeilidUpdate;
 
 #[derive(Facet, Arbitrary, Debug, PartialEq)]
@@ -54,7 +55,7 @@ impl RouteRemoveArgs {
         };
 
         let api = start_api_for_profile(profile_home, true, callback).await?;
-        wait_for_public_internet_ready(&api, &public_internet_ready).await?;
+        wait_for_public_internet_ready(&api, &public_internet_ready, EventOutput::Text).await?;
 
         let router = api.routing_context()?.with_default_safety()?;
         let opened = router
@@ -69,39 +70,13 @@ impl RouteRemoveArgs {
                 .await?;
         }
 
-        api.shutdown().await;
+        shutdown_api(api).await;
 
         app_state::remove_local_route_identity(profile_home, &self.name)?;
         Ok(RouteRemoveResponse { name: self.name })
     }
 }
 
-async fn wait_for_public_internet_ready(
-    api: &veilid_core::VeilidAPI,
-    public_internet_ready: &AtomicBool,
-) -> Result<()> {
-    if !public_internet_ready.load(Ordering::Acquire) {
-        let state = api.get_state().await?;
-        if state.attachment.public_internet_ready {
-            public_internet_ready.store(true, Ordering::Release);
-        }
-    }
-
-    if !public_internet_ready.load(Ordering::Acquire) {
-        let start = Instant::now();
-        let timeout = Duration::from_secs(120);
-        while !public_internet_ready.load(Ordering::Acquire) {
-            if start.elapsed() >= timeout {
-                eyre::bail!(
-                    "Timed out waiting for public internet readiness; retry when network attachment improves."
-                );
-            }
-            tokio::time::sleep(Duration::from_millis(250)).await;
-        }
-    }
-    Ok(())
-}
-
 impl ToArgs for RouteRemoveArgs {
     fn to_args(&self) -> Vec<std::ffi::OsString> {
         vec![self.name.clone().into()]
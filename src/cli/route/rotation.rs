@@ -0,0 +1,139 @@
+use crate::cli::InvokeContext;
+use crate::cli::ToArgs;
+use crate::cli::app_state;
+use crate::cli::app_state::RouteRotationPolicy;
+use arbitrary::Arbitrary;
+use eyre::Result;
+use eyre::bail;
+use facet::Facet;
+use figue as args;
+use std::fmt;
+use std::time::Duration;
+
+#[derive(Facet, Arbitrary, Debug, PartialEq)]
+pub struct RouteRotationArgs {
+    #[facet(args::positional)]
+    pub name: String,
+
+    /// Replace the private route this often, like 30m; `off` only replaces dead routes.
+    #[facet(args::named)]
+    pub interval: Option<String>,
+
+    /// Replace the private route after this many accepted messages; 0 disables it.
+    #[facet(args::named)]
+    pub after_messages: Option<u32>,
+
+    /// How long a replaced route stays allocated for senders still using it, like 1m.
+    #[facet(args::named)]
+    pub grace: Option<String>,
+}
+
+#[derive(Clone, Debug, PartialEq, Eq, Facet)]
+pub struct RouteRotationResponse {
+    name: String,
+    interval_secs: u64,
+    after_messages: u32,
+    grace_secs: u64,
+    updated: bool,
+}
+
+fn format_secs(secs: u64) -> String {
+    humantime::format_duration(Duration::from_secs(secs)).to_string()
+}
+
+impl fmt::Display for RouteRotationResponse {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        if self.updated {
+            writeln!(f, "Updated rotation policy for route {}.", self.name)?;
+        } else {
+            writeln!(f, "Rotation policy for route {}:", self.name)?;
+        }
+        if self.interval_secs == 0 {
+            writeln!(f, "Interval: off")?;
+        } else {
+            writeln!(f, "Interval: every {}", format_secs(self.interval_secs))?;
+        }
+        if self.after_messages == 0 {
+            writeln!(f, "After messages: off")?;
+        } else {
+            writeln!(f, "After messages: {}", self.after_messages)?;
+        }
+        write!(f, "Grace period: {}", format_secs(self.grace_secs))
+    }
+}
+
+/// Parse a duration flag into whole seconds, with `off` or `0` meaning 0.
+fn parse_secs(flag: &str, value: &str) -> Result<u64> {
+    if matches!(value.trim(), "off" | "0") {
+        return Ok(0);
+    }
+    let duration = humantime::parse_duration(value)
+        .map_err(|err| eyre::eyre!("Invalid {} value '{}': {}", flag, value, err))?;
+    Ok(duration.as_secs())
+}
+
+impl RouteRotationArgs {
+    /// Show or change when a route's private route is replaced while listening.
+    ///
+    /// Settings that are not given keep their current value.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the route does not exist, a duration is invalid, or the
+    /// policy cannot be persisted.
+    #[expect(
+        clippy::unused_async,
+        reason = "command handlers use async invoke signature consistently"
+    )]
+    pub async fn invoke(self, context: &InvokeContext) -> Result<RouteRotationResponse> {
+        let profile_home = context.profile_home();
+        if app_state::local_route_identity(profile_home, &self.name)?.is_none() {
+            bail!("Route '{}' does not exist.", self.name);
+        }
+
+        let current = app_state::route_rotation_policy(profile_home, &self.name)?;
+        let policy = RouteRotationPolicy {
+            interval_secs: match &self.interval {
+                Some(interval) => parse_secs("--interval", interval)?,
+                None => current.interval_secs,
+            },
+            after_messages: self.after_messages.unwrap_or(current.after_messages),
+            grace_secs: match &self.grace {
+                Some(grace) => parse_secs("--grace", grace)?,
+                None => current.grace_secs,
+            },
+        };
+        let updated =
+            self.interval.is_some() || self.after_messages.is_some() || self.grace.is_some();
+        if updated {
+            app_state::set_route_rotation_policy(profile_home, &self.name, policy)?;
+        }
+
+        Ok(RouteRotationResponse {
+            name: self.name,
+            interval_secs: policy.interval_secs,
+            after_messages: policy.after_messages,
+            grace_secs: policy.grace_secs,
+            updated,
+        })
+    }
+}
+
+impl ToArgs for RouteRotationArgs {
+    fn to_args(&self) -> Vec<std::ffi::OsString> {
+        let mut args = vec![self.name.clone().into()];
+        if let Some(interval) = &self.interval {
+            args.push("--interval".into());
+            args.push(interval.clone().into());
+        }
+        if let Some(after_messages) = self.after_messages {
+            args.push("--after-messages".into());
+            args.push(after_messages.to_string().into());
+        }
+        if let Some(grace) = &self.grace {
+            args.push("--grace".into());
+            args.push(grace.clone().into());
+        }
+        args
+    }
+}
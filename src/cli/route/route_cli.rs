@@ -2,9 +2,12 @@ use crate::cli::InvokeContext;
 use crate::cli::ToArgs;
 use crate::cli::response::CliResponse;
 use crate::cli::route::add::RouteAddArgs;
+use crate::cli::route::limit::RouteLimitArgs;
 use crate::cli::route::list::RouteListArgs;
 use crate::cli::route::listen::RouteListenArgs;
 use crate::cli::route::remove::RouteRemoveArgs;
+use crate::cli::route::rotation::RouteRotationArgs;
+use crate::cli::route::safety::RouteSafetyArgs;
 use crate::cli::route::show::RouteShowArgs;
 use arbitrary::Arbitrary;
 use eyre::Result;
@@ -28,6 +31,9 @@ pub enum RouteCommand {
     List(RouteListArgs),
     Show(RouteShowArgs),
     Remove(RouteRemoveArgs),
+    Limit(RouteLimitArgs),
+    Rotation(RouteRotationArgs),
+    Safety(RouteSafetyArgs),
 }
 
 impl RouteArgs {
@@ -46,6 +52,9 @@ impl RouteArgs {
             RouteCommand::List(args) => args.invoke(context).await?.into(),
             RouteCommand::Show(args) => args.invoke(context).await?.into(),
             RouteCommand::Remove(args) => args.invoke(context).await?.into(),
+            RouteCommand::Limit(args) => args.invoke(context).await?.into(),
+            RouteCommand::Rotation(args) => args.invoke(context).await?.into(),
+            RouteCommand::Safety(args) => args.invoke(context).await?.into(),
         })
     }
 }
@@ -82,6 +91,18 @@ impl ToArgs for RouteArgs {
                 args.push("remove".into());
                 args.extend(remove_args.to_args());
             }
+            RouteCommand::Limit(limit_args) => {
+                args.push("limit".into());
+                args.extend(limit_args.to_args());
+            }
+            RouteCommand::Rotation(rotation_args) => {
+                args.push("rotation".into());
+                args.extend(rotation_args.to_args());
+            }
+            RouteCommand::Safety(safety_args) => {
+                args.push("safety".into());
+                args.extend(safety_args.to_args());
+            }
         }
         args
     }
@@ -0,0 +1,130 @@
+use crate::cli::InvokeContext;
+use crate::cli::ToArgs;
+use crate::cli::app_state;
+use crate::cli::app_state::RouteSafety;
+use crate::cli::safety;
+use arbitrary::Arbitrary;
+use eyre::Result;
+use eyre::bail;
+use facet::Facet;
+use figue as args;
+use std::fmt;
+
+#[derive(Facet, Arbitrary, Debug, PartialEq)]
+pub struct RouteSafetyArgs {
+    #[facet(args::positional)]
+    pub name: String,
+
+    /// Hops in the safety route used to publish the route record, 0 uses none; the
+    /// private route listened on keeps the node's default length.
+    #[facet(args::named)]
+    pub hops: Option<u8>,
+
+    /// Prefer `low-latency` or `reliable` routes.
+    #[facet(args::named)]
+    pub stability: Option<String>,
+
+    /// `no-preference`, `prefer-ordered`, or `ensure-ordered` protocols such as TCP.
+    #[facet(args::named)]
+    pub sequencing: Option<String>,
+
+    /// Go back to Veilid's defaults before applying any other settings given.
+    #[facet(args::named, default)]
+    pub reset: bool,
+}
+
+#[derive(Clone, Debug, PartialEq, Eq, Facet)]
+pub struct RouteSafetyResponse {
+    name: String,
+    hop_count: Option<u8>,
+    stability: Option<String>,
+    sequencing: Option<String>,
+    summary: String,
+    updated: bool,
+}
+
+impl fmt::Display for RouteSafetyResponse {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        if self.updated {
+            write!(
+                f,
+                "Updated safety for route {}: {}.",
+                self.name, self.summary
+            )
+        } else {
+            write!(f, "Safety for route {}: {}.", self.name, self.summary)
+        }
+    }
+}
+
+impl RouteSafetyArgs {
+    /// Show or change the safety settings a route listens with.
+    ///
+    /// Settings that are not given keep their current value.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the route does not exist, a setting is invalid, or route
+    /// data cannot be persisted.
+    #[expect(
+        clippy::unused_async,
+        reason = "command handlers use async invoke signature consistently"
+    )]
+    pub async fn invoke(self, context: &InvokeContext) -> Result<RouteSafetyResponse> {
+        let profile_home = context.profile_home();
+        let Some(identity) = app_state::local_route_identity(profile_home, &self.name)? else {
+            bail!("Route '{}' does not exist.", self.name);
+        };
+
+        let overrides = safety::from_flags(
+            self.hops,
+            self.stability.as_deref(),
+            self.sequencing.as_deref(),
+        )?;
+        let base = if self.reset {
+            RouteSafety::default()
+        } else {
+            identity.safety
+        };
+        let settings = base.with_overrides(overrides);
+        let updated = self.reset || !overrides.is_default();
+        if updated {
+            app_state::set_route_safety(profile_home, &self.name, settings)?;
+        }
+
+        Ok(RouteSafetyResponse {
+            name: self.name,
+            hop_count: settings.hop_count,
+            stability: settings
+                .stability
+                .map(|stability| stability.as_str().to_owned()),
+            sequencing: settings
+                .sequencing
+                .map(|sequencing| sequencing.as_str().to_owned()),
+            summary: safety::describe(settings),
+            updated,
+        })
+    }
+}
+
+impl ToArgs for RouteSafetyArgs {
+    fn to_args(&self) -> Vec<std::ffi::OsString> {
+        let mut args = vec![self.name.clone().into()];
+        if let Some(hops) = self.hops {
+            args.push("--hops".into());
+            args.push(hops.to_string().into());
+        }
+        if let Some(stability) = &self.stability {
+            args.push("--stability".into());
+            args.push(stability.clone().into());
+        }
+        if let Some(sequencing) = &self.sequencing {
+            args.push("--sequencing".into());
+            args.push(sequencing.clone().into());
+        }
+        if self.reset {
+            args.push("--reset".into());
+        }
+        args
+    }
+}
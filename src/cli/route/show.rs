@@ -1,6 +1,7 @@
 use crate::cli::InvokeContext;
 use crate::cli::ToArgs;
 use crate::cli::app_state;
+use crate::cli::safety;
 use arbitrary::Arbitrary;
 use eyre::Result;
 use eyre::bail;
@@ -19,13 +20,24 @@ pub struct RouteShowResponse {
     name: String,
     record_key: String,
     public_key: String,
+    mailbox: bool,
+    hop_count: Option<u8>,
+    stability: Option<String>,
+    sequencing: Option<String>,
+    safety: String,
 }
 
 impl fmt::Display for RouteShowResponse {
     fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
         writeln!(f, "Route: {}", self.name)?;
         writeln!(f, "Record key: {}", self.record_key)?;
-        write!(f, "Public key: {}", self.public_key)
+        writeln!(f, "Public key: {}", self.public_key)?;
+        writeln!(
+            f,
+            "Mailbox: {}",
+            if self.mailbox { "enabled" } else { "disabled" }
+        )?;
+        write!(f, "Safety: {}", self.safety)
     }
 }
 
@@ -44,6 +56,17 @@ impl RouteShowArgs {
             name: route.name,
             record_key: route.record_key.to_string(),
             public_key: route.keypair.key().to_string(),
+            mailbox: route.mailbox_writer.is_some(),
+            hop_count: route.safety.hop_count,
+            stability: route
+                .safety
+                .stability
+                .map(|stability| stability.as_str().to_owned()),
+            sequencing: route
+                .safety
+                .sequencing
+                .map(|sequencing| sequencing.as_str().to_owned()),
+            safety: safety::describe(route.safety),
         })
     }
 }
@@ -0,0 +1,129 @@
+//! Veilid routing contexts and private routes built from [`RouteSafety`] settings.
+//!
+//! Settings that are left unset keep Veilid's defaults: one hop, low latency, and a
+//! preference for ordered protocols. Fully default settings use the node's own default
+//! safety selection.
+
+use crate::cli::app_state::RouteSafety;
+use crate::cli::app_state::RouteSequencing;
+use crate::cli::app_state::RouteStability;
+use eyre::Result;
+use eyre::bail;
+use veilid_core::CRYPTO_KIND_VLD0;
+use veilid_core::RouteBlob;
+use veilid_core::RoutingContext;
+use veilid_core::SafetySelection;
+use veilid_core::SafetySpec;
+use veilid_core::Sequencing;
+use veilid_core::Stability;
+use veilid_core::VeilidAPI;
+use veilid_core::VeilidAPIResult;
+
+/// Most hops Veilid allows in a safety or private route.
+pub const MAX_HOP_COUNT: u8 = 4;
+
+/// Veilid's default `network.rpc.default_route_hop_count`.
+const DEFAULT_HOP_COUNT: u8 = 1;
+
+/// Build safety settings from command-line flags.
+///
+/// # Errors
+///
+/// Returns an error if the hop count is over [`MAX_HOP_COUNT`] or a value is not
+/// recognized.
+pub fn from_flags(
+    hops: Option<u8>,
+    stability: Option<&str>,
+    sequencing: Option<&str>,
+) -> Result<RouteSafety> {
+    if let Some(hops) = hops
+        && hops > MAX_HOP_COUNT
+    {
+        bail!("--hops must be at most {}.", MAX_HOP_COUNT);
+    }
+    Ok(RouteSafety {
+        hop_count: hops,
+        stability: stability.map(str::parse).transpose()?,
+        sequencing: sequencing.map(str::parse).transpose()?,
+    })
+}
+
+/// Describe a listening route's settings for display, naming the defaults that apply
+/// to unset fields.
+///
+/// The hop count only shapes the safety route used to publish the route record; the
+/// private route the route listens on always has the node's default length.
+#[must_use]
+pub fn describe(safety: RouteSafety) -> String {
+    let hops = match safety.hop_count {
+        Some(0) => "0 (no safety route)".to_owned(),
+        Some(hops) => hops.to_string(),
+        None => format!("{DEFAULT_HOP_COUNT} (default)"),
+    };
+    let stability = safety
+        .stability
+        .map_or("low-latency (default)", |stability| stability.as_str());
+    let sequencing = safety
+        .sequencing
+        .map_or("prefer-ordered (default)", |sequencing| sequencing.as_str());
+    format!(
+        "DHT publish hops {hops}, private route hops node default, stability {stability}, sequencing {sequencing}"
+    )
+}
+
+/// A routing context that sends with `safety`.
+///
+/// # Errors
+///
+/// Returns an error if Veilid rejects the safety selection.
+pub fn routing_context(api: &VeilidAPI, safety: RouteSafety) -> Result<RoutingContext> {
+    let router = api.routing_context()?;
+    if safety.is_default() {
+        return Ok(router.with_default_safety()?);
+    }
+    let ordering = sequencing(safety.sequencing);
+    let selection = match safety.hop_count.unwrap_or(DEFAULT_HOP_COUNT) {
+        0 => SafetySelection::Unsafe(ordering),
+        hops => SafetySelection::Safe(SafetySpec {
+            preferred_route: None,
+            hop_count: usize::from(hops),
+            stability: stability(safety.stability),
+            sequencing: ordering,
+        }),
+    };
+    Ok(router.with_safety(selection)?)
+}
+
+/// Allocate a private route to receive on with the stability and sequencing in `safety`.
+///
+/// The private route's own length is the node's configured default hop count.
+///
+/// # Errors
+///
+/// Returns an error if Veilid cannot allocate the route.
+pub async fn new_private_route(api: &VeilidAPI, safety: RouteSafety) -> VeilidAPIResult<RouteBlob> {
+    if safety.stability.is_none() && safety.sequencing.is_none() {
+        return api.new_private_route().await;
+    }
+    api.new_custom_private_route(
+        &[CRYPTO_KIND_VLD0],
+        stability(safety.stability),
+        sequencing(safety.sequencing),
+    )
+    .await
+}
+
+fn stability(stability: Option<RouteStability>) -> Stability {
+    match stability {
+        None | Some(RouteStability::LowLatency) => Stability::LowLatency,
+        Some(RouteStability::Reliable) => Stability::Reliable,
+    }
+}
+
+fn sequencing(sequencing: Option<RouteSequencing>) -> Sequencing {
+    match sequencing {
+        Some(RouteSequencing::NoPreference) => Sequencing::NoPreference,
+        None | Some(RouteSequencing::PreferOrdered) => Sequencing::PreferOrdered,
+        Some(RouteSequencing::EnsureOrdered) => Sequencing::EnsureOrdered,
+    }
+}
@@ -0,0 +1,349 @@
+use crate::cli::Cli;
+use crate::cli::InvokeContext;
+use crate::cli::app_state;
+use crate::cli::app_state::ProfileHome;
+use crate::cli::envelope::Extensions;
+use crate::cli::events::EventOutput;
+use crate::cli::key::KeyArgs;
+use crate::cli::key::KeyCommand;
+use crate::cli::key::key_gen::KeyGenArgs;
+use crate::cli::route::listen::wait_for_public_internet_ready;
+use crate::cli::safety;
+use crate::cli::send::chat::ChatSession;
+use crate::cli::send::chat::Delivery;
+use crate::cli::send::chat::SendChatArgs;
+use crate::cli::veilid_runtime::shutdown_api;
+use crate::cli::veilid_runtime::start_api_for_profile;
+use eyre::Result;
+use eyre::bail;
+use facet::Facet;
+use std::fmt;
+use std::sync::Arc;
+use std::sync::atomic::AtomicBool;
+use std::sync::atomic::Ordering;
+use tokio::sync::Mutex;
+use tokio::task::JoinSet;
+use tracing::warn;
+use veilid_core::KeyPair;
+use veilid_core::PublicKey;
+use veilid_core::RecordKey;
+use veilid_core::RoutingContext;
+use veilid_core::VeilidAPI;
+use veilid_core::VeilidUpdate;
+
+/// Selector for every known user.
+const ALL_KNOWN_USERS: &str = "@all";
+
+#[derive(Clone, Debug, PartialEq, Eq, Facet)]
+pub struct BroadcastDeliveryItem {
+    known_user: String,
+    ok: bool,
+    outcome: String,
+}
+
+#[derive(Clone, Debug, PartialEq, Eq, Facet)]
+pub struct SendBroadcastResponse {
+    deliveries: Vec<BroadcastDeliveryItem>,
+}
+
+impl fmt::Display for SendBroadcastResponse {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let ok = self
+            .deliveries
+            .iter()
+            .filter(|delivery| delivery.ok)
+            .count();
+        write!(f, "Sent to {ok} of {} known users:", self.deliveries.len())?;
+        for delivery in &self.deliveries {
+            write!(f, "\n  {}: {}", delivery.known_user, delivery.outcome)?;
+        }
+        Ok(())
+    }
+}
+
+/// Expand a `send` recipient into several known users, or `None` for a single one.
+///
+/// A name that matches a known user exactly is always that one known user. Otherwise
+/// `@all` selects every known user, `@<room>` the members of a room, and a
+/// comma-separated list the known users named.
+///
+/// # Errors
+///
+/// Returns an error if a selected room does not exist, the selection is empty, or a
+/// listed name is not a known user.
+pub(crate) fn resolve_recipients(
+    profile_home: &ProfileHome,
+    selector: &str,
+) -> Result<Option<Vec<String>>> {
+    let known_users = app_state::list_known_users(profile_home)?
+        .into_iter()
+        .map(|entry| entry.name)
+        .collect::<Vec<_>>();
+    if known_users.iter().any(|name| name == selector) {
+        return Ok(None);
+    }
+
+    let recipients = if selector == ALL_KNOWN_USERS {
+        known_users.clone()
+    } else if let Some(room) = selector.strip_prefix('@') {
+        let Some(room) = app_state::room(profile_home, room)? else {
+            bail!("Room '{}' does not exist.", room);
+        };
+        room.members
+    } else if selector.contains(',') {
+        let mut recipients = Vec::<String>::new();
+        for name in selector
+            .split(',')
+            .map(str::trim)
+            .filter(|name| !name.is_empty())
+        {
+            if !recipients.iter().any(|recipient| recipient == name) {
+                recipients.push(name.to_owned());
+            }
+        }
+        recipients
+    } else {
+        return Ok(None);
+    };
+
+    if recipients.is_empty() {
+        bail!("'{}' does not select any known users.", selector);
+    }
+    let unknown = recipients
+        .iter()
+        .filter(|name| !known_users.contains(*name))
+        .cloned()
+        .collect::<Vec<_>>();
+    if !unknown.is_empty() {
+        bail!("Not known users: {}.", unknown.join(", "));
+    }
+    Ok(Some(recipients))
+}
+
+/// Everything a send task needs, owned so it can run on its own task.
+#[derive(Clone)]
+struct BroadcastContext {
+    profile_home: ProfileHome,
+    /// Held while a task reads or writes profile files, which are rewritten whole, so
+    /// only the network sends overlap.
+    state_lock: Arc<Mutex<()>>,
+    api: VeilidAPI,
+    router: RoutingContext,
+    keypair: KeyPair,
+    retry_attempts: usize,
+    acknowledge: bool,
+    queue: bool,
+    /// Where send progress is printed; stderr under a JSON output format.
+    output: EventOutput,
+    extensions: Extensions,
+}
+
+impl SendChatArgs {
+    /// Send one message to several known users from a single Veilid instance.
+    ///
+    /// Each recipient's routes are resolved and sent to concurrently, while sequence
+    /// numbers, chat history, and the outbox are updated one task at a time. A
+    /// recipient that cannot be reached is reported in the response, or queued in the
+    /// outbox with `--queue`, rather than failing the whole send. Progress notes go to
+    /// stderr under a JSON output format so stdout holds only the response.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if `--message` is missing, the key or reply route is missing,
+    /// or Veilid cannot be started.
+    pub async fn broadcast(
+        self,
+        context: &InvokeContext,
+        recipients: Vec<String>,
+    ) -> Result<SendBroadcastResponse> {
+        let retry_attempts = self.retry.unwrap_or(1);
+        if retry_attempts == 0 {
+            bail!("--retry must be greater than 0.");
+        }
+        let route_safety = self.safety()?;
+        let Some(message) = self.message else {
+            bail!("Sending to several known users needs --message.");
+        };
+
+        let profile_home = context.profile_home();
+        let my_keypair = app_state::load_keypair(profile_home)?.ok_or_else(|| {
+            eyre::eyre!(
+                "You have no key. Run '{}' first.",
+                Cli::display_invocation(&crate::cli::Command::Key(KeyArgs {
+                    command: KeyCommand::Gen(KeyGenArgs),
+                }))
+            )
+        })?;
+        let reply_route = match &self.reply_route {
+            Some(route_name) => Some(
+                app_state::local_route_identity(profile_home, route_name)?
+                    .ok_or_else(|| eyre::eyre!("Route '{}' does not exist.", route_name))?
+                    .record_key,
+            ),
+            None => None,
+        };
+        let mut targets = Vec::with_capacity(recipients.len());
+        for known_user in recipients {
+            let Some(recipient) = app_state::known_user_public_key(profile_home, &known_user)?
+            else {
+                bail!("Known user '{}' does not exist.", known_user);
+            };
+            let keys = app_state::route_keys_for_known_user(profile_home, &known_user)?;
+            targets.push((known_user, recipient, keys));
+        }
+
+        let public_internet_ready = Arc::new(AtomicBool::new(false));
+        let callback = {
+            let public_internet_ready = Arc::clone(&public_internet_ready);
+            Arc::new(move |update: VeilidUpdate| {
+                if let VeilidUpdate::Attachment(attachment) = update {
+                    public_internet_ready
+                        .store(attachment.public_internet_ready, Ordering::Release);
+                }
+            }) as crate::cli::veilid_runtime::UpdateCallback
+        };
+
+        let output = EventOutput::for_context(context);
+        let api = start_api_for_profile(profile_home, true, callback).await?;
+        let router = match wait_for_public_internet_ready(&api, &public_internet_ready, output)
+            .await
+            .and_then(|()| safety::routing_context(&api, route_safety))
+        {
+            Ok(router) => router,
+            Err(error) => {
+                shutdown_api(api).await;
+                return Err(error);
+            }
+        };
+
+        let shared = BroadcastContext {
+            profile_home: profile_home.clone(),
+            state_lock: Arc::new(Mutex::new(())),
+            api: api.clone(),
+            router,
+            keypair: my_keypair,
+            retry_attempts,
+            acknowledge: self.ack,
+            queue: self.queue,
+            output,
+            extensions: Extensions {
+                reply_route,
+                ..Extensions::default()
+            },
+        };
+        let order = targets
+            .iter()
+            .map(|(known_user, _, _)| known_user.clone())
+            .collect::<Vec<_>>();
+        let message = Arc::<str>::from(message);
+        let mut join_set = JoinSet::new();
+        for (known_user, recipient, keys) in targets {
+            join_set.spawn(send_to_one(
+                shared.clone(),
+                known_user,
+                recipient,
+                keys,
+                Arc::clone(&message),
+            ));
+        }
+
+        let mut deliveries = Vec::with_capacity(order.len());
+        while let Some(result) = join_set.join_next().await {
+            match result {
+                Ok(delivery) => deliveries.push(delivery),
+                Err(error) => warn!(%error, "broadcast send task failed"),
+            }
+        }
+        // A task that panicked left no item; report its recipient as failed.
+        for known_user in &order {
+            if !deliveries
+                .iter()
+                .any(|delivery| delivery.known_user == *known_user)
+            {
+                deliveries.push(BroadcastDeliveryItem {
+                    known_user: known_user.clone(),
+                    ok: false,
+                    outcome: "failed: the send task stopped unexpectedly".to_owned(),
+                });
+            }
+        }
+        deliveries.sort_by_key(|delivery| {
+            order
+                .iter()
+                .position(|known_user| *known_user == delivery.known_user)
+        });
+
+        shutdown_api(api).await;
+        Ok(SendBroadcastResponse { deliveries })
+    }
+}
+
+/// Resolve one recipient's route and send the message, describing the result.
+async fn send_to_one(
+    shared: BroadcastContext,
+    known_user: String,
+    recipient: PublicKey,
+    keys: Vec<RecordKey>,
+    message: Arc<str>,
+) -> BroadcastDeliveryItem {
+    let result = if keys.is_empty() {
+        Err(eyre::eyre!("no route record keys"))
+    } else {
+        let mut session = ChatSession {
+            profile_home: &shared.profile_home,
+            known_user: known_user.clone(),
+            api: &shared.api,
+            router: &shared.router,
+            keypair: &shared.keypair,
+            recipient,
+            keys,
+            retry_attempts: shared.retry_attempts,
+            acknowledge: shared.acknowledge,
+            extensions: shared.extensions.clone(),
+            cached_route_id: None,
+            output: shared.output,
+        };
+        let result = send_with_lock(&shared.state_lock, &mut session, &message).await;
+        session.release_route();
+        result
+    };
+
+    let (ok, outcome) = match result {
+        Ok(delivery) => (delivery != Delivery::Unconfirmed, delivery.to_string()),
+        Err(error) if shared.queue => {
+            let queued = {
+                let _state = shared.state_lock.lock().await;
+                app_state::enqueue_outbox(&shared.profile_home, &known_user, &message)
+            };
+            match queued {
+                Ok(entry) => (
+                    false,
+                    format!("{error}; queued as outbox message {}.", entry.id),
+                ),
+                Err(queue_error) => (false, format!("{error}; could not queue: {queue_error}")),
+            }
+        }
+        Err(error) => (false, format!("failed: {error}")),
+    };
+    BroadcastDeliveryItem {
+        known_user,
+        ok,
+        outcome,
+    }
+}
+
+/// Seal and record the message while holding `state_lock`, releasing it for the send.
+async fn send_with_lock(
+    state_lock: &Mutex<()>,
+    session: &mut ChatSession<'_>,
+    message: &str,
+) -> Result<Delivery> {
+    let outgoing = {
+        let _state = state_lock.lock().await;
+        session.seal_text(message).await?
+    };
+    let delivery = session.transmit(&outgoing).await?;
+    let _state = state_lock.lock().await;
+    session.record_sent(&outgoing)?;
+    Ok(delivery)
+}
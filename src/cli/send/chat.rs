@@ -2,12 +2,32 @@ use crate::cli::Cli;
 use crate::cli::InvokeContext;
 use crate::cli::ToArgs;
 use crate::cli::app_state;
+use crate::cli::app_state::ChatHistoryEntry;
+use crate::cli::app_state::MessageDirection;
+use crate::cli::app_state::ProfileHome;
+use crate::cli::app_state::RouteSafety;
+use crate::cli::chat::with::spawn_stdin_lines;
+use crate::cli::chat_crypto;
+use crate::cli::chat_crypto::SealedMessage;
+use crate::cli::envelope::Extensions;
+use crate::cli::envelope::FileChunkHeader;
+use crate::cli::envelope::MessageId;
+use crate::cli::envelope::MessageKind;
+use crate::cli::events::EventOutput;
+use crate::cli::events::SendLineInput;
+use crate::cli::events::SendResultEvent;
 use crate::cli::key::KeyArgs;
 use crate::cli::key::KeyCommand;
 use crate::cli::key::key_gen::KeyGenArgs;
 use crate::cli::known_user::KnownUserArgs;
 use crate::cli::known_user::KnownUserCommand;
 use crate::cli::known_user::add::KnownUserAddArgs;
+use crate::cli::mailbox;
+use crate::cli::route::listen::wait_for_public_internet_ready;
+use crate::cli::safety;
+use crate::cli::send::prompt::ChatPrompt;
+use crate::cli::send::prompt::PromptAction;
+use crate::cli::veilid_runtime::shutdown_api;
 use crate::cli::veilid_runtime::start_api_for_profile;
 use arbitrary::Arbitrary;
 use eyre::Context;
@@ -15,15 +35,21 @@ use eyre::Result;
 use eyre::bail;
 use facet::Facet;
 use figue as args;
+use std::fmt;
 use std::io::Write;
 use std::sync::Arc;
 use std::sync::atomic::AtomicBool;
 use std::sync::atomic::Ordering;
 use std::time::Duration;
-use std::time::Instant;
+use tracing::warn;
+use veilid_core::KeyPair;
+use veilid_core::PublicKey;
 use veilid_core::RecordKey;
 use veilid_core::RouteId;
+use veilid_core::RoutingContext;
 use veilid_core::Target;
+use veilid_core::VeilidAPI;
+use veilid_core::VeilidAPIError;
 use veilid_core::VeilidUpdate;
 
 #[derive(Facet, Arbitrary, Debug, PartialEq)]
@@ -32,18 +58,332 @@ pub struct SendChatArgs {
     pub message: Option<String>,
     #[facet(args::named)]
     pub retry: Option<usize>,
+    /// Use `app_call` and wait for a delivery receipt for each message.
+    #[facet(args::named, default)]
+    pub ack: bool,
+    /// Queue messages in the outbox instead of failing when the known user is offline.
+    #[facet(args::named, default)]
+    pub queue: bool,
+    /// Attach the record key of this local route so the recipient can reply without setup.
+    #[facet(args::named)]
+    pub reply_route: Option<String>,
+    /// Hops in the safety route; more hops trade latency for anonymity, 0 uses none.
+    #[facet(args::named)]
+    pub hops: Option<u8>,
+    /// Prefer `low-latency` or `reliable` routes.
+    #[facet(args::named)]
+    pub stability: Option<String>,
+    /// `no-preference`, `prefer-ordered`, or `ensure-ordered` protocols such as TCP.
+    #[facet(args::named)]
+    pub sequencing: Option<String>,
+}
+
+/// Outcome of sending one message.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Delivery {
+    /// Handed to Veilid with `app_message`; no confirmation was requested.
+    Sent,
+    /// The recipient replied with a receipt for the message id.
+    Delivered,
+    /// Every attempt ended without a valid receipt.
+    Unconfirmed,
+    /// No route was reachable, so the message was left in the recipient's mailbox.
+    Mailboxed,
+}
+
+impl fmt::Display for Delivery {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::Sent => f.write_str("Message sent."),
+            Self::Delivered => f.write_str("Message delivered."),
+            Self::Unconfirmed => f.write_str("Message unconfirmed; no delivery receipt arrived."),
+            Self::Mailboxed => f.write_str("Recipient offline; message left in their mailbox."),
+        }
+    }
+}
+
+impl Delivery {
+    /// Short name used in JSON output.
+    #[must_use]
+    pub fn as_str(self) -> &'static str {
+        match self {
+            Self::Sent => "sent",
+            Self::Delivered => "delivered",
+            Self::Unconfirmed => "unconfirmed",
+            Self::Mailboxed => "mailboxed",
+        }
+    }
+}
+
+/// Outcome of `send chat --message`.
+#[derive(Clone, Debug, PartialEq, Eq, Facet)]
+pub struct SendChatResponse {
+    known_user: String,
+    /// `sent`, `delivered`, `unconfirmed`, `mailboxed`, or `queued`.
+    delivery: String,
+    /// Outbox entry holding the message when it was queued.
+    outbox_id: Option<u64>,
+    detail: String,
+}
+
+impl fmt::Display for SendChatResponse {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(&self.detail)
+    }
+}
+
+impl SendChatResponse {
+    fn queued(known_user: &str, outbox_id: u64, detail: String) -> Self {
+        Self {
+            known_user: known_user.to_owned(),
+            delivery: "queued".to_owned(),
+            outbox_id: Some(outbox_id),
+            detail,
+        }
+    }
+}
+
+/// A chat line sealed for one recipient, kept so a retry resends the same envelope.
+#[derive(Clone, Debug)]
+pub(crate) struct OutgoingText {
+    pub text: String,
+    /// The recipient's sequence number carried by the envelope.
+    pub sequence: u64,
+    pub sealed: SealedMessage,
+}
+
+/// Veilid state shared by every message sent to one known user.
+pub(crate) struct ChatSession<'a> {
+    pub profile_home: &'a ProfileHome,
+    pub known_user: String,
+    pub api: &'a VeilidAPI,
+    pub router: &'a RoutingContext,
+    pub keypair: &'a KeyPair,
+    pub recipient: PublicKey,
+    pub keys: Vec<RecordKey>,
+    pub retry_attempts: usize,
+    pub acknowledge: bool,
+    /// Optional envelope fields attached to every message, such as a room id or reply route.
+    pub extensions: Extensions,
+    pub cached_route_id: Option<RouteId>,
+    /// Where send progress is printed.
+    pub output: EventOutput,
+}
+
+impl ChatSession<'_> {
+    /// Seal and send one chat line, waiting for a receipt when acknowledgements are on.
+    ///
+    /// When no route can be used, the message is left in the recipient's mailbox if
+    /// one of their route records has one. Each line carries the next sequence number
+    /// for the recipient, which is only used up once the send succeeds, so a failed
+    /// line that is queued and resent later does not leave a gap. Sent lines are
+    /// appended to the known user's chat history.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if sealing fails, no route or mailbox could be used after all
+    /// attempts, or the sequence number or history cannot be written.
+    pub async fn send_text(&mut self, text: &str) -> Result<Delivery> {
+        let outgoing = self.seal_text(text).await?;
+        self.send_outgoing(&outgoing).await
+    }
+
+    /// Seal one chat line with the next sequence number for the recipient, without
+    /// sending it.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the sequence file cannot be read or sealing fails.
+    pub async fn seal_text(&self, text: &str) -> Result<OutgoingText> {
+        let sequence = app_state::last_sent_sequence(self.profile_home, &self.recipient)? + 1;
+        let sealed = chat_crypto::seal_chat_message(
+            self.api,
+            self.keypair,
+            &self.recipient,
+            text,
+            Extensions {
+                sequence: Some(sequence),
+                ..self.extensions.clone()
+            },
+        )
+        .await?;
+        Ok(OutgoingText {
+            text: text.to_owned(),
+            sequence,
+            sealed,
+        })
+    }
+
+    /// Send a line sealed by [`Self::seal_text`] and record it as sent.
+    ///
+    /// Sending the same [`OutgoingText`] again resends identical bytes, so a copy that
+    /// did arrive is dropped by the recipient as a repeat.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if no route or mailbox could be used after all attempts, or
+    /// the sequence number or history cannot be written.
+    pub async fn send_outgoing(&mut self, outgoing: &OutgoingText) -> Result<Delivery> {
+        let delivery = self.transmit(outgoing).await?;
+        self.record_sent(outgoing)?;
+        Ok(delivery)
+    }
+
+    /// Send a line sealed by [`Self::seal_text`] without touching profile state; the
+    /// caller records it with [`Self::record_sent`].
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if no route or mailbox could be used after all attempts.
+    pub async fn transmit(&mut self, outgoing: &OutgoingText) -> Result<Delivery> {
+        self.send_sealed(&outgoing.sealed, true).await
+    }
+
+    /// Use up the line's sequence number and append it to the chat history, unless an
+    /// earlier attempt to send it already did.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the sequence number or history cannot be written.
+    pub fn record_sent(&self, outgoing: &OutgoingText) -> Result<()> {
+        if outgoing.sequence <= app_state::last_sent_sequence(self.profile_home, &self.recipient)? {
+            return Ok(());
+        }
+        app_state::set_last_sent_sequence(self.profile_home, &self.recipient, outgoing.sequence)?;
+        app_state::append_chat_history(
+            self.profile_home,
+            &self.known_user,
+            &ChatHistoryEntry {
+                timestamp_ms: outgoing.sealed.timestamp_ms,
+                direction: MessageDirection::Outbound,
+                message_id: outgoing.sealed.message_id.to_string(),
+                body: outgoing.text.clone(),
+            },
+        )
+    }
+
+    /// Seal and send one file chunk, waiting for a receipt when acknowledgements are on.
+    ///
+    /// Chunks are never left in a mailbox; they would crowd out chat messages.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if sealing fails or no route could be used after all attempts.
+    pub async fn send_file_chunk(
+        &mut self,
+        header: FileChunkHeader,
+        data: &[u8],
+    ) -> Result<Delivery> {
+        let sealed = chat_crypto::seal_message(
+            self.api,
+            self.keypair,
+            &self.recipient,
+            MessageKind::FileChunk,
+            data,
+            Extensions {
+                file_chunk: Some(header),
+                ..self.extensions.clone()
+            },
+        )
+        .await?;
+        self.send_sealed(&sealed, false).await
+    }
+
+    async fn send_sealed(&mut self, sealed: &SealedMessage, use_mailbox: bool) -> Result<Delivery> {
+        let receipt = self.acknowledge.then_some(ExpectedReceipt {
+            keypair: self.keypair,
+            recipient: &self.recipient,
+            message_id: sealed.message_id,
+        });
+        match send_payload_with_route_retry(
+            self.api,
+            self.router,
+            &self.keys,
+            sealed.payload.clone(),
+            self.retry_attempts,
+            &mut self.cached_route_id,
+            receipt.as_ref(),
+            self.output,
+        )
+        .await
+        {
+            Ok(delivery) => Ok(delivery),
+            Err(error) => {
+                let slot = u16::from(sealed.message_id.0[0]);
+                if use_mailbox
+                    && deposit_in_any_mailbox(self.router, &self.keys, &sealed.payload, slot).await
+                {
+                    return Ok(Delivery::Mailboxed);
+                }
+                Err(error)
+            }
+        }
+    }
+
+    /// Release the imported remote route, if one was acquired.
+    pub fn release_route(&mut self) {
+        if let Some(route_id) = self.cached_route_id.take() {
+            let _ = self.api.release_private_route(route_id);
+        }
+    }
+}
+
+/// The receipt a sender expects back from an acknowledged `app_call`.
+struct ExpectedReceipt<'a> {
+    keypair: &'a KeyPair,
+    recipient: &'a PublicKey,
+    message_id: MessageId,
+}
+
+impl ExpectedReceipt<'_> {
+    /// Whether `reply` is a receipt signed by the recipient for this message id.
+    async fn is_confirmed_by(&self, api: &VeilidAPI, reply: &[u8]) -> bool {
+        let Ok(envelope) = chat_crypto::open_message(api, self.keypair, reply).await else {
+            return false;
+        };
+        envelope.kind == MessageKind::Receipt
+            && envelope.sender == *self.recipient
+            && envelope.body == self.message_id.0
+    }
 }
 
 impl SendChatArgs {
+    /// Safety settings from `--hops`, `--stability`, and `--sequencing`.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if a setting is invalid.
+    pub(crate) fn safety(&self) -> Result<RouteSafety> {
+        safety::from_flags(
+            self.hops,
+            self.stability.as_deref(),
+            self.sequencing.as_deref(),
+        )
+    }
+
+    /// Send `--message`, or each line typed at the chat prompt or read as JSON.
+    ///
+    /// Returns the outcome of `--message` when writing human-readable output; the
+    /// prompt and JSON Lines mode print as they go and return `None`.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the key, known user, or reply route is missing, Veilid cannot
+    /// be started, or `--message` cannot be sent or queued.
     #[expect(
         clippy::too_many_lines,
         reason = "chat flow combines validation, setup, and interactive send loop"
     )]
-    pub async fn invoke(self, context: &InvokeContext, known_user: &str) -> Result<()> {
+    pub async fn invoke(
+        self,
+        context: &InvokeContext,
+        known_user: &str,
+    ) -> Result<Option<SendChatResponse>> {
         let retry_attempts = self.retry.unwrap_or(1);
         if retry_attempts == 0 {
             bail!("--retry must be greater than 0.");
         }
+        let route_safety = self.safety()?;
 
         let profile_home = context.profile_home();
         let my_keypair = app_state::load_keypair(profile_home)?.ok_or_else(|| {
@@ -68,8 +408,47 @@ impl SendChatArgs {
                 )
             })?;
 
+        let reply_route = match &self.reply_route {
+            Some(route_name) => Some(
+                app_state::local_route_identity(profile_home, route_name)?
+                    .ok_or_else(|| eyre::eyre!("Route '{}' does not exist.", route_name))?
+                    .record_key,
+            ),
+            None => None,
+        };
+
+        let output = EventOutput::for_context(context);
         let keys = app_state::route_keys_for_known_user(profile_home, known_user)?;
         if keys.is_empty() {
+            if self.queue
+                && let Some(message) = &self.message
+            {
+                let entry = app_state::enqueue_outbox(profile_home, known_user, message)?;
+                if output.is_json() {
+                    output.event(
+                        &SendResultEvent {
+                            event: "result".to_owned(),
+                            line: None,
+                            ok: false,
+                            delivery: Some("queued".to_owned()),
+                            outbox_id: Some(entry.id),
+                            error: Some(format!(
+                                "No route record keys configured for {known_user}."
+                            )),
+                        },
+                        "",
+                    );
+                    return Ok(None);
+                }
+                return Ok(Some(SendChatResponse::queued(
+                    known_user,
+                    entry.id,
+                    format!(
+                        "No route record keys configured for {known_user}; queued as outbox message {}.",
+                        entry.id
+                    ),
+                )));
+            }
             bail!("No route record keys configured for {}.", known_user);
         }
 
@@ -85,44 +464,69 @@ impl SendChatArgs {
         };
 
         let api = start_api_for_profile(profile_home, true, callback).await?;
-        if !public_internet_ready.load(Ordering::Acquire) {
-            let state = api.get_state().await?;
-            if state.attachment.public_internet_ready {
-                public_internet_ready.store(true, Ordering::Release);
-            }
+        if let Err(error) =
+            wait_for_public_internet_ready(&api, &public_internet_ready, output).await
+        {
+            shutdown_api(api).await;
+            return Err(error);
         }
 
-        if !public_internet_ready.load(Ordering::Acquire) {
-            println!("Waiting for public internet readiness...");
-            let start = Instant::now();
-            let timeout = Duration::from_secs(120);
-            while !public_internet_ready.load(Ordering::Acquire) {
-                if start.elapsed() >= timeout {
-                    api.shutdown().await;
-                    bail!(
-                        "Timed out waiting for public internet readiness; retry when network attachment improves."
-                    );
-                }
-                tokio::time::sleep(Duration::from_millis(250)).await;
-            }
-        }
+        let router = safety::routing_context(&api, route_safety)?;
+        let mut session = ChatSession {
+            profile_home,
+            known_user: known_user.to_owned(),
+            api: &api,
+            router: &router,
+            keypair: &my_keypair,
+            recipient: known_user_key,
+            keys,
+            retry_attempts,
+            acknowledge: self.ack,
+            extensions: Extensions {
+                reply_route,
+                ..Extensions::default()
+            },
+            cached_route_id: None,
+            output,
+        };
 
-        let router = api.routing_context()?.with_default_safety()?;
-        let mut cached_route_id: Option<RouteId> = None;
-
-        if let Some(message) = self.message {
-            let payload = format!("{}|{}", my_keypair.key(), message);
-            send_payload_with_route_retry(
-                &api,
-                &router,
-                &keys,
-                payload.into_bytes(),
-                retry_attempts,
-                &mut cached_route_id,
-            )
-            .await?;
-            println!("Message sent.");
+        let mut response = None;
+        if output.is_json() {
+            match &self.message {
+                Some(message) => send_json_line(&mut session, None, message, self.queue).await?,
+                None => send_json_lines(&mut session, self.queue).await?,
+            }
+        } else if let Some(message) = self.message {
+            response = Some(match session.send_text(&message).await {
+                Ok(delivery) => SendChatResponse {
+                    known_user: known_user.to_owned(),
+                    delivery: delivery.as_str().to_owned(),
+                    outbox_id: None,
+                    detail: delivery.to_string(),
+                },
+                Err(error) if self.queue => {
+                    let entry = app_state::enqueue_outbox(profile_home, known_user, &message)?;
+                    SendChatResponse::queued(
+                        known_user,
+                        entry.id,
+                        format!(
+                            "Could not send to {known_user} ({error}); queued as outbox message {}.",
+                            entry.id
+                        ),
+                    )
+                }
+                Err(error) => {
+                    session.release_route();
+                    shutdown_api(api).await;
+                    return Err(error);
+                }
+            });
         } else {
+            let mut prompt = ChatPrompt {
+                queue: self.queue,
+                ..ChatPrompt::default()
+            };
+            println!("Type /help for commands or /quit to stop.");
             loop {
                 let input_task = tokio::task::spawn_blocking(move || -> std::io::Result<String> {
                     let mut out = std::io::stdout();
@@ -140,54 +544,147 @@ impl SendChatArgs {
                     }
                     line = input_task => {
                         let line = line.wrap_err("failed reading chat input")??;
-                        let text = line.trim_end_matches(['\r', '\n']).to_owned();
+                        let text = line.trim_end_matches(['\r', '\n']);
                         if text.is_empty() {
                             continue;
                         }
-                        let payload = format!("{}|{}", my_keypair.key(), text);
-                        send_payload_with_route_retry(
-                            &api,
-                            &router,
-                            &keys,
-                            payload.into_bytes(),
-                            retry_attempts,
-                            &mut cached_route_id,
-                        )
-                        .await?;
+                        if prompt.handle_line(context, &mut session, text).await?
+                            == PromptAction::Quit
+                        {
+                            break;
+                        }
                     }
                 }
             }
         }
 
-        if let Some(route_id) = cached_route_id {
-            let _ = api.release_private_route(route_id);
-        }
+        session.release_route();
+        shutdown_api(api).await;
+        Ok(response)
+    }
+}
 
-        api.shutdown().await;
-        let _ = known_user_key;
-        Ok(())
+/// Send a JSON object per stdin line, printing one result line for each.
+///
+/// Reading stops at the end of input or Ctrl+C.
+async fn send_json_lines(session: &mut ChatSession<'_>, queue: bool) -> Result<()> {
+    let mut lines = spawn_stdin_lines();
+    let mut line_number = 0;
+    loop {
+        tokio::select! {
+            _ = tokio::signal::ctrl_c() => {
+                break;
+            }
+            line = lines.recv() => {
+                let Some(line) = line else {
+                    break;
+                };
+                line_number += 1;
+                if line.trim().is_empty() {
+                    continue;
+                }
+                match facet_json::from_str::<SendLineInput>(&line) {
+                    Ok(input) => {
+                        send_json_line(session, Some(line_number), &input.message, queue).await?;
+                    }
+                    Err(error) => session.output.event(
+                        &SendResultEvent {
+                            event: "result".to_owned(),
+                            line: Some(line_number),
+                            ok: false,
+                            delivery: None,
+                            outbox_id: None,
+                            error: Some(format!("invalid input line: {error}")),
+                        },
+                        "",
+                    ),
+                }
+            }
+        }
     }
+    Ok(())
 }
 
-async fn acquire_route(
-    api: &veilid_core::VeilidAPI,
+/// Send one message and print its result as a JSON line.
+///
+/// # Errors
+///
+/// Returns an error only if a failed message cannot be queued in the outbox.
+async fn send_json_line(
+    session: &mut ChatSession<'_>,
+    line: Option<usize>,
+    message: &str,
+    queue: bool,
+) -> Result<()> {
+    let event = match session.send_text(message).await {
+        Ok(delivery) => SendResultEvent {
+            event: "result".to_owned(),
+            line,
+            ok: delivery != Delivery::Unconfirmed,
+            delivery: Some(delivery.as_str().to_owned()),
+            outbox_id: None,
+            error: None,
+        },
+        Err(error) if queue => {
+            let entry =
+                app_state::enqueue_outbox(session.profile_home, &session.known_user, message)?;
+            SendResultEvent {
+                event: "result".to_owned(),
+                line,
+                ok: false,
+                delivery: Some("queued".to_owned()),
+                outbox_id: Some(entry.id),
+                error: Some(format!("{error:#}")),
+            }
+        }
+        Err(error) => {
+            // A failed send may have left a stale route behind.
+            session.release_route();
+            SendResultEvent {
+                event: "result".to_owned(),
+                line,
+                ok: false,
+                delivery: None,
+                outbox_id: None,
+                error: Some(format!("{error:#}")),
+            }
+        }
+    };
+    session.output.event(&event, "");
+    Ok(())
+}
+
+/// Read the route blob published under a route record key.
+///
+/// Returns `None` when the record has no value or its owner marked it offline
+/// by publishing empty route data.
+///
+/// # Errors
+///
+/// Returns an error if the record cannot be opened or read.
+pub(crate) async fn read_route_blob(
     router: &veilid_core::RoutingContext,
     key: &RecordKey,
-) -> Result<Option<RouteId>> {
+) -> Result<Option<Vec<u8>>> {
     let _ = router.open_dht_record(key.clone(), None).await?;
     let value = router.get_dht_value(key.clone(), 0, true).await?;
     let _ = router.close_dht_record(key.clone()).await;
 
-    let Some(value) = value else {
-        return Ok(None);
-    };
+    Ok(value
+        .map(|value| value.data().to_vec())
+        .filter(|data| !data.is_empty()))
+}
 
-    let data = value.data();
-    if data.is_empty() {
+async fn acquire_route(
+    api: &veilid_core::VeilidAPI,
+    router: &veilid_core::RoutingContext,
+    key: &RecordKey,
+) -> Result<Option<RouteId>> {
+    let Some(blob) = read_route_blob(router, key).await? else {
         return Ok(None);
-    }
+    };
 
-    let route_id = api.import_remote_private_route(data.to_vec())?;
+    let route_id = api.import_remote_private_route(blob)?;
     Ok(Some(route_id))
 }
 
@@ -195,11 +692,16 @@ async fn acquire_best_route(
     api: &veilid_core::VeilidAPI,
     router: &veilid_core::RoutingContext,
     keys: &[RecordKey],
+    output: EventOutput,
 ) -> Result<RouteId> {
     for (index, key) in keys.iter().enumerate() {
-        println!("Trying route record key {} of {}.", index + 1, keys.len());
+        output.note(format_args!(
+            "Trying route record key {} of {}.",
+            index + 1,
+            keys.len()
+        ));
         if let Some(route_id) = acquire_route(api, router, key).await? {
-            println!("Acquired route information.");
+            output.note("Acquired route information.");
             return Ok(route_id);
         }
     }
@@ -207,6 +709,10 @@ async fn acquire_best_route(
     bail!("Unable to acquire a route from any configured record key.")
 }
 
+#[expect(
+    clippy::too_many_arguments,
+    reason = "route retry threads the session's Veilid handles and cached route separately"
+)]
 async fn send_payload_with_route_retry(
     api: &veilid_core::VeilidAPI,
     router: &veilid_core::RoutingContext,
@@ -214,18 +720,20 @@ async fn send_payload_with_route_retry(
     payload: Vec<u8>,
     max_attempts: usize,
     cached_route_id: &mut Option<RouteId>,
-) -> Result<()> {
+    receipt: Option<&ExpectedReceipt<'_>>,
+    output: EventOutput,
+) -> Result<Delivery> {
     for attempt in 1..=max_attempts {
-        println!("Send attempt {attempt} of {max_attempts}.");
+        output.note(format_args!("Send attempt {attempt} of {max_attempts}."));
 
         if cached_route_id.is_none() {
-            let route_id = match acquire_best_route(api, router, keys).await {
+            let route_id = match acquire_best_route(api, router, keys, output).await {
                 Ok(route_id) => route_id,
                 Err(error) => {
                     if should_retry_route_acquire(&error) && attempt < max_attempts {
-                        println!(
+                        output.note(format_args!(
                             "Route record key unavailable; retrying in 1s (attempt {attempt} of {max_attempts})."
-                        );
+                        ));
                         tokio::time::sleep(Duration::from_secs(1)).await;
                         continue;
                     }
@@ -239,15 +747,42 @@ async fn send_payload_with_route_retry(
             continue;
         };
 
-        match router
-            .app_message(Target::RouteId(route_id.clone()), payload.clone())
-            .await
-        {
-            Ok(()) => return Ok(()),
+        let target = Target::RouteId(route_id.clone());
+        let result = match receipt {
+            None => router
+                .app_message(target, payload.clone())
+                .await
+                .map(|()| Some(Delivery::Sent)),
+            Some(receipt) => match router.app_call(target, payload.clone()).await {
+                Ok(reply) => {
+                    if receipt.is_confirmed_by(api, &reply).await {
+                        Ok(Some(Delivery::Delivered))
+                    } else {
+                        output.note("Reply did not contain a valid delivery receipt.");
+                        Ok(None)
+                    }
+                }
+                Err(VeilidAPIError::Timeout) => {
+                    output.note("Timed out waiting for a delivery receipt.");
+                    Ok(None)
+                }
+                Err(error) => Err(error),
+            },
+        };
+
+        match result {
+            Ok(Some(delivery)) => return Ok(delivery),
+            Ok(None) => {
+                if attempt < max_attempts {
+                    tokio::time::sleep(Duration::from_secs(1)).await;
+                    continue;
+                }
+                return Ok(Delivery::Unconfirmed);
+            }
             Err(error) => {
                 let error = eyre::Report::from(error);
                 if should_reacquire_route_after_send_error(&error) {
-                    println!("Cached route appears stale; reacquiring route information.");
+                    output.note("Cached route appears stale; reacquiring route information.");
                     let _ = api.release_private_route(route_id.clone());
                     *cached_route_id = None;
 
@@ -268,6 +803,53 @@ async fn send_payload_with_route_retry(
     )
 }
 
+/// Leave `payload` in the first mailbox found among `keys`.
+async fn deposit_in_any_mailbox(
+    router: &RoutingContext,
+    keys: &[RecordKey],
+    payload: &[u8],
+    slot: u16,
+) -> bool {
+    for key in keys {
+        match mailbox::deposit(router, key, payload.to_vec(), slot).await {
+            Ok(true) => return true,
+            Ok(false) => {}
+            Err(error) => warn!(%error, %key, "failed to leave message in mailbox"),
+        }
+    }
+    false
+}
+
+/// Queue a message that could not be sent in the outbox and report it to `output`.
+///
+/// # Errors
+///
+/// Returns an error if the outbox cannot be written.
+pub(crate) fn enqueue_after_send_failure(
+    profile_home: &ProfileHome,
+    known_user: &str,
+    text: &str,
+    error: &eyre::Report,
+    output: EventOutput,
+) -> Result<()> {
+    let entry = app_state::enqueue_outbox(profile_home, known_user, text)?;
+    output.event(
+        &SendResultEvent {
+            event: "result".to_owned(),
+            line: None,
+            ok: false,
+            delivery: Some("queued".to_owned()),
+            outbox_id: Some(entry.id),
+            error: Some(format!("{error:#}")),
+        },
+        format_args!(
+            "Could not send to {known_user} ({error}); queued as outbox message {}.",
+            entry.id
+        ),
+    );
+    Ok(())
+}
+
 fn should_retry_route_acquire(error: &eyre::Report) -> bool {
     error
         .chain()
@@ -294,6 +876,28 @@ impl ToArgs for SendChatArgs {
             args.push("--retry".into());
             args.push(retry.to_string().into());
         }
+        if self.ack {
+            args.push("--ack".into());
+        }
+        if self.queue {
+            args.push("--queue".into());
+        }
+        if let Some(reply_route) = &self.reply_route {
+            args.push("--reply-route".into());
+            args.push(reply_route.clone().into());
+        }
+        if let Some(hops) = self.hops {
+            args.push("--hops".into());
+            args.push(hops.to_string().into());
+        }
+        if let Some(stability) = &self.stability {
+            args.push("--stability".into());
+            args.push(stability.clone().into());
+        }
+        if let Some(sequencing) = &self.sequencing {
+            args.push("--sequencing".into());
+            args.push(sequencing.clone().into());
+        }
         args
     }
 }
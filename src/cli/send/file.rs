@@ -0,0 +1,320 @@
+use crate::cli::Cli;
+use crate::cli::InvokeContext;
+use crate::cli::ToArgs;
+use crate::cli::app_state;
+use crate::cli::envelope::Extensions;
+use crate::cli::envelope::FileChunkHeader;
+use crate::cli::events::EventOutput;
+use crate::cli::file_transfer;
+use crate::cli::file_transfer::FILE_CHUNK_SIZE;
+use crate::cli::file_transfer::MAX_FILE_SIZE;
+use crate::cli::key::KeyArgs;
+use crate::cli::key::KeyCommand;
+use crate::cli::key::key_gen::KeyGenArgs;
+use crate::cli::route::listen::wait_for_public_internet_ready;
+use crate::cli::send::chat::ChatSession;
+use crate::cli::send::chat::Delivery;
+use crate::cli::veilid_runtime::shutdown_api;
+use crate::cli::veilid_runtime::start_api_for_profile;
+use arbitrary::Arbitrary;
+use eyre::Result;
+use eyre::bail;
+use facet::Facet;
+use figue as args;
+use std::fmt;
+use std::io::Read;
+use std::io::Seek;
+use std::io::SeekFrom;
+use std::path::Path;
+use std::path::PathBuf;
+use std::sync::Arc;
+use std::sync::atomic::AtomicBool;
+use std::sync::atomic::Ordering;
+use veilid_core::VeilidUpdate;
+
+#[derive(Facet, Arbitrary, Debug, PartialEq)]
+pub struct SendFileArgs {
+    #[facet(args::positional)]
+    pub path: String,
+
+    /// Attempts per chunk before the transfer is paused for a later resume.
+    #[facet(args::named)]
+    pub retry: Option<usize>,
+}
+
+#[derive(Clone, Debug, PartialEq, Eq, Facet)]
+pub struct SendFileResponse {
+    known_user: String,
+    file_name: String,
+    size: u64,
+    chunks: u32,
+    resumed_at_chunk: u32,
+    content_hash: String,
+}
+
+impl fmt::Display for SendFileResponse {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "Sent {} ({} bytes in {} chunks) to {}.",
+            self.file_name, self.size, self.chunks, self.known_user
+        )?;
+        if self.resumed_at_chunk > 0 {
+            write!(f, " Resumed at chunk {}.", self.resumed_at_chunk + 1)?;
+        }
+        Ok(())
+    }
+}
+
+/// A file on disk ready to be sent in chunks; its contents are read one chunk at a time.
+pub(crate) struct OutgoingFile {
+    pub file_name: String,
+    pub path: PathBuf,
+    pub size: u64,
+}
+
+impl OutgoingFile {
+    /// Check a file to send.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if `path` does not name a file, cannot be read, is empty, or is
+    /// over [`MAX_FILE_SIZE`].
+    pub fn read(path: &str) -> Result<Self> {
+        let Some(file_name) = Path::new(path)
+            .file_name()
+            .map(|name| name.to_string_lossy().into_owned())
+        else {
+            bail!("'{}' is not a file.", path);
+        };
+        let metadata = std::fs::metadata(path)?;
+        if !metadata.is_file() {
+            bail!("'{}' is not a file.", path);
+        }
+        let size = metadata.len();
+        if size == 0 {
+            bail!("'{}' is empty.", path);
+        }
+        if size > MAX_FILE_SIZE {
+            bail!("'{}' is over the {} byte limit.", path, MAX_FILE_SIZE);
+        }
+        Ok(Self {
+            file_name,
+            path: PathBuf::from(path),
+            size,
+        })
+    }
+
+    /// Read chunk `index` of the file.
+    fn read_chunk(&self, file: &mut std::fs::File, index: u32) -> Result<Vec<u8>> {
+        let chunk_size = u64::try_from(FILE_CHUNK_SIZE)?;
+        let offset = u64::from(index) * chunk_size;
+        let len = chunk_size.min(self.size.saturating_sub(offset));
+        let mut chunk = vec![0; usize::try_from(len)?];
+        file.seek(SeekFrom::Start(offset))?;
+        file.read_exact(&mut chunk)?;
+        Ok(chunk)
+    }
+}
+
+/// Send a file to the session's known user in acknowledged chunks, resuming an earlier
+/// interrupted transfer of the same content.
+///
+/// Progress is saved after every confirmed chunk, so sending the same file again after
+/// a failure picks up at the first unconfirmed chunk. The last chunk is where the
+/// receiver checks the content hash and discards a bad copy, so when it goes
+/// unconfirmed the saved progress is cleared and the next attempt starts over; chunks
+/// the receiver still holds are acknowledged again without being stored twice. Chunks
+/// are always acknowledged, whatever the session's own setting.
+///
+/// # Errors
+///
+/// Returns an error if the file cannot be read or hashed, or a chunk is not confirmed
+/// after all attempts.
+pub(crate) async fn send_file(
+    session: &mut ChatSession<'_>,
+    file: OutgoingFile,
+) -> Result<SendFileResponse> {
+    let profile_home = session.profile_home;
+    let known_user = session.known_user.clone();
+    let content_hash = file_transfer::content_hash(session.api, &file.path).await?;
+    let count = u32::try_from(file.size.div_ceil(u64::try_from(FILE_CHUNK_SIZE)?))?;
+    let file_name = file.file_name.clone();
+    let resumed_at_chunk =
+        app_state::file_transfer_progress(profile_home, &known_user, &content_hash)?.min(count);
+    if resumed_at_chunk > 0 {
+        session.output.note(format_args!(
+            "Resuming transfer of {file_name} at chunk {} of {count}.",
+            resumed_at_chunk + 1
+        ));
+    }
+
+    let mut reader = std::fs::File::open(&file.path)?;
+    let acknowledge = std::mem::replace(&mut session.acknowledge, true);
+    let mut outcome = Ok(());
+    for index in resumed_at_chunk..count {
+        let chunk = match file.read_chunk(&mut reader, index) {
+            Ok(chunk) => chunk,
+            Err(error) => {
+                outcome = Err(error.wrap_err(format!("Could not read chunk {}", index + 1)));
+                break;
+            }
+        };
+        let header = FileChunkHeader {
+            index,
+            count,
+            chunk_size: u32::try_from(FILE_CHUNK_SIZE)?,
+            total_size: file.size,
+            content_hash: content_hash.clone(),
+            file_name: file_name.clone(),
+        };
+        let last = index + 1 == count;
+        let result = session.send_file_chunk(header, &chunk).await;
+        if last && !matches!(result, Ok(Delivery::Delivered)) {
+            app_state::clear_file_transfer_progress(profile_home, &known_user, &content_hash)?;
+        }
+        match result {
+            Ok(Delivery::Delivered) => {
+                app_state::set_file_transfer_progress(
+                    profile_home,
+                    &known_user,
+                    &content_hash,
+                    index + 1,
+                )?;
+                session.output.note(format_args!(
+                    "Sent chunk {} of {count} ({}%).",
+                    index + 1,
+                    u64::from(index + 1) * 100 / u64::from(count)
+                ));
+            }
+            Ok(_) if last => {
+                outcome = Err(eyre::eyre!(
+                    "The last chunk was not confirmed, so the receiver may have discarded the file; send it again to restart the transfer."
+                ));
+                break;
+            }
+            Ok(_) => {
+                outcome = Err(eyre::eyre!(
+                    "Chunk {} of {count} was not confirmed; send the file again to resume.",
+                    index + 1
+                ));
+                break;
+            }
+            Err(error) => {
+                outcome = Err(error.wrap_err(format!(
+                    "Transfer paused at chunk {} of {count}; send the file again to resume",
+                    index + 1
+                )));
+                break;
+            }
+        }
+    }
+    session.acknowledge = acknowledge;
+    outcome?;
+
+    app_state::clear_file_transfer_progress(profile_home, &known_user, &content_hash)?;
+    Ok(SendFileResponse {
+        known_user,
+        file_name,
+        size: file.size,
+        chunks: count,
+        resumed_at_chunk,
+        content_hash,
+    })
+}
+
+impl SendFileArgs {
+    /// Send a file in acknowledged chunks, resuming an earlier interrupted transfer of
+    /// the same content to the same known user.
+    ///
+    /// Progress is saved after every confirmed chunk, so rerunning the command after a
+    /// failure picks up at the first unconfirmed chunk. Progress notes go to stderr
+    /// under a JSON output format so stdout holds only the response.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the file cannot be read or is empty, the key or known user is
+    /// missing, Veilid cannot be started, or a chunk is not confirmed after all attempts.
+    pub async fn invoke(
+        self,
+        context: &InvokeContext,
+        known_user: &str,
+    ) -> Result<SendFileResponse> {
+        let retry_attempts = self.retry.unwrap_or(3);
+        if retry_attempts == 0 {
+            bail!("--retry must be greater than 0.");
+        }
+
+        let profile_home = context.profile_home();
+        let file = OutgoingFile::read(&self.path)?;
+        let my_keypair = app_state::load_keypair(profile_home)?.ok_or_else(|| {
+            eyre::eyre!(
+                "You have no key. Run '{}' first.",
+                Cli::display_invocation(&crate::cli::Command::Key(KeyArgs {
+                    command: KeyCommand::Gen(KeyGenArgs),
+                }))
+            )
+        })?;
+        let Some(recipient) = app_state::known_user_public_key(profile_home, known_user)? else {
+            bail!("Known user '{}' does not exist.", known_user);
+        };
+        let keys = app_state::route_keys_for_known_user(profile_home, known_user)?;
+        if keys.is_empty() {
+            bail!("No route record keys configured for {}.", known_user);
+        }
+
+        let public_internet_ready = Arc::new(AtomicBool::new(false));
+        let callback = {
+            let public_internet_ready = Arc::clone(&public_internet_ready);
+            Arc::new(move |update: VeilidUpdate| {
+                if let VeilidUpdate::Attachment(attachment) = update {
+                    public_internet_ready
+                        .store(attachment.public_internet_ready, Ordering::Release);
+                }
+            }) as crate::cli::veilid_runtime::UpdateCallback
+        };
+
+        let output = EventOutput::for_context(context);
+        let api = start_api_for_profile(profile_home, true, callback).await?;
+        let router = match wait_for_public_internet_ready(&api, &public_internet_ready, output)
+            .await
+            .and_then(|()| Ok(api.routing_context()?.with_default_safety()?))
+        {
+            Ok(router) => router,
+            Err(error) => {
+                shutdown_api(api).await;
+                return Err(error);
+            }
+        };
+
+        let mut session = ChatSession {
+            profile_home,
+            known_user: known_user.to_owned(),
+            api: &api,
+            router: &router,
+            keypair: &my_keypair,
+            recipient,
+            keys,
+            retry_attempts,
+            acknowledge: true,
+            extensions: Extensions::default(),
+            cached_route_id: None,
+            output,
+        };
+        let outcome = send_file(&mut session, file).await;
+        session.release_route();
+        shutdown_api(api).await;
+        outcome
+    }
+}
+
+impl ToArgs for SendFileArgs {
+    fn to_args(&self) -> Vec<std::ffi::OsString> {
+        let mut args = vec![self.path.clone().into()];
+        if let Some(retry) = self.retry {
+            args.push("--retry".into());
+            args.push(retry.to_string().into());
+        }
+        args
+    }
+}
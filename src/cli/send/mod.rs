@@ -1,4 +1,7 @@
+pub(crate) mod broadcast;
 pub(crate) mod chat;
+pub(crate) mod file;
+pub(crate) mod prompt;
 mod send_cli;
 
 pub use send_cli::*;
@@ -0,0 +1,340 @@
+//! Lines typed at the interactive `CHAT>` prompt.
+//!
+//! A line starting with `/` is a command rather than a message; `//` escapes a
+//! message that should start with a slash.
+
+use crate::cli::InvokeContext;
+use crate::cli::app_state;
+use crate::cli::chat::history::ChatHistoryArgs;
+use crate::cli::send::chat::ChatSession;
+use crate::cli::send::chat::Delivery;
+use crate::cli::send::chat::OutgoingText;
+use crate::cli::send::chat::enqueue_after_send_failure;
+use crate::cli::send::file::OutgoingFile;
+use crate::cli::send::file::send_file;
+use eyre::Result;
+use eyre::bail;
+
+/// Messages shown by `/history`.
+const HISTORY_LIMIT: usize = 20;
+
+const HELP: &str = "\
+Commands:
+  /quit             leave the chat
+  /status           show the route and network attachment state
+  /retry            send the last message that failed again
+  /file <path>      send a file in chunks
+  /switch <name>    chat with another known user
+  /history          show recent messages with this known user
+  /help             show this list
+Start a message with // to send a line beginning with /.";
+
+/// A command typed at the chat prompt.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub(crate) enum SlashCommand {
+    Quit,
+    Status,
+    Retry,
+    File(String),
+    Switch(String),
+    History,
+    Help,
+}
+
+/// One line of chat input.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub(crate) enum ChatInput {
+    Text(String),
+    Command(SlashCommand),
+}
+
+impl ChatInput {
+    /// Split a line into a message or a command.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error for an unknown command or one missing its argument, so the
+    /// line is never sent by mistake.
+    pub fn parse(line: &str) -> Result<Self> {
+        if let Some(text) = line.strip_prefix("//") {
+            return Ok(Self::Text(format!("/{text}")));
+        }
+        let Some(command) = line.strip_prefix('/') else {
+            return Ok(Self::Text(line.to_owned()));
+        };
+
+        let (name, argument) = command
+            .split_once(char::is_whitespace)
+            .map_or((command, ""), |(name, argument)| (name, argument.trim()));
+        let command = match (name, argument) {
+            ("quit", "") => SlashCommand::Quit,
+            ("status", "") => SlashCommand::Status,
+            ("retry", "") => SlashCommand::Retry,
+            ("history", "") => SlashCommand::History,
+            ("help", "") => SlashCommand::Help,
+            ("file", "") => bail!("Usage: /file <path>"),
+            ("file", path) => SlashCommand::File(path.to_owned()),
+            ("switch", "") => bail!("Usage: /switch <known-user>"),
+            ("switch", known_user) => SlashCommand::Switch(known_user.to_owned()),
+            ("quit" | "status" | "retry" | "history" | "help", _) => {
+                bail!("/{name} takes no arguments.")
+            }
+            _ => bail!("Unknown command '/{name}'. Type /help to list commands."),
+        };
+        Ok(Self::Command(command))
+    }
+}
+
+/// Whether the chat loop keeps reading lines.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub(crate) enum PromptAction {
+    Continue,
+    Quit,
+}
+
+/// State kept between lines of an interactive chat.
+#[derive(Debug, Default)]
+pub(crate) struct ChatPrompt {
+    /// Queue messages that cannot be sent in the outbox instead of offering `/retry`.
+    pub queue: bool,
+    /// The last message that failed or went unconfirmed, sealed as it was first sent.
+    unsent: Option<OutgoingText>,
+}
+
+impl ChatPrompt {
+    /// Send a message or run a command for one line typed at the prompt.
+    ///
+    /// Failures are printed rather than returned so the chat carries on.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error only if a failed message cannot be queued in the outbox.
+    pub async fn handle_line(
+        &mut self,
+        context: &InvokeContext,
+        session: &mut ChatSession<'_>,
+        line: &str,
+    ) -> Result<PromptAction> {
+        match ChatInput::parse(line) {
+            Ok(ChatInput::Text(text)) => self.send(session, text).await?,
+            Ok(ChatInput::Command(command)) => {
+                return self.run(context, session, command).await;
+            }
+            Err(error) => println!("{error}"),
+        }
+        Ok(PromptAction::Continue)
+    }
+
+    async fn send(&mut self, session: &mut ChatSession<'_>, text: String) -> Result<()> {
+        match session.seal_text(&text).await {
+            Ok(outgoing) => self.deliver(session, outgoing).await,
+            Err(error) => {
+                println!("Send failed: {error}");
+                Ok(())
+            }
+        }
+    }
+
+    /// Send a sealed message, keeping it for `/retry` when it fails or goes
+    /// unconfirmed; a retry resends the same envelope so the recipient can drop a
+    /// copy that did arrive.
+    async fn deliver(
+        &mut self,
+        session: &mut ChatSession<'_>,
+        outgoing: OutgoingText,
+    ) -> Result<()> {
+        match session.send_outgoing(&outgoing).await {
+            Ok(Delivery::Unconfirmed) => {
+                println!("{}", Delivery::Unconfirmed);
+                println!("Type /retry to send it again.");
+                self.unsent = Some(outgoing);
+            }
+            Ok(delivery) => {
+                if session.acknowledge {
+                    println!("{delivery}");
+                }
+                self.unsent = None;
+            }
+            Err(error) if self.queue => {
+                enqueue_after_send_failure(
+                    session.profile_home,
+                    &session.known_user,
+                    &outgoing.text,
+                    &error,
+                    session.output,
+                )?;
+                self.unsent = None;
+            }
+            Err(error) => {
+                println!("Send failed: {error}");
+                println!("Type /retry to try again.");
+                self.unsent = Some(outgoing);
+            }
+        }
+        Ok(())
+    }
+
+    async fn run(
+        &mut self,
+        context: &InvokeContext,
+        session: &mut ChatSession<'_>,
+        command: SlashCommand,
+    ) -> Result<PromptAction> {
+        match command {
+            SlashCommand::Quit => return Ok(PromptAction::Quit),
+            SlashCommand::Status => print_status(session).await,
+            SlashCommand::Retry => match self.unsent.take() {
+                Some(outgoing) => {
+                    // A failed send may have left a stale route behind.
+                    session.release_route();
+                    self.deliver(session, outgoing).await?;
+                }
+                None => println!("Nothing to retry."),
+            },
+            SlashCommand::File(path) => match OutgoingFile::read(&path) {
+                Ok(file) => match send_file(session, file).await {
+                    Ok(response) => println!("{response}"),
+                    Err(error) => println!("{error:#}"),
+                },
+                Err(error) => println!("{error}"),
+            },
+            SlashCommand::Switch(known_user) => match switch_to(session, &known_user) {
+                Ok(()) => {
+                    self.unsent = None;
+                    println!("Now chatting with {known_user}.");
+                }
+                Err(error) => println!("{error}"),
+            },
+            SlashCommand::History => {
+                let history = ChatHistoryArgs {
+                    known_user: session.known_user.clone(),
+                    since: None,
+                    limit: Some(HISTORY_LIMIT),
+                };
+                match history.invoke(context).await {
+                    Ok(response) => println!("{response}"),
+                    Err(error) => println!("{error}"),
+                }
+            }
+            SlashCommand::Help => println!("{HELP}"),
+        }
+        Ok(PromptAction::Continue)
+    }
+}
+
+async fn print_status(session: &ChatSession<'_>) {
+    println!(
+        "Chatting with {} over {} route record key(s); {}.",
+        session.known_user,
+        session.keys.len(),
+        if session.cached_route_id.is_some() {
+            "their route is imported"
+        } else {
+            "no route imported yet"
+        }
+    );
+    println!(
+        "Delivery receipts: {}; attempts per message: {}.",
+        if session.acknowledge { "on" } else { "off" },
+        session.retry_attempts
+    );
+    match session.api.get_state().await {
+        Ok(state) => println!(
+            "Attachment: {}; public internet {}.",
+            state.attachment.state,
+            if state.attachment.public_internet_ready {
+                "ready"
+            } else {
+                "not ready"
+            }
+        ),
+        Err(error) => println!("Attachment: unknown ({error})."),
+    }
+}
+
+/// Point the session at another known user.
+fn switch_to(session: &mut ChatSession<'_>, known_user: &str) -> Result<()> {
+    let Some(recipient) = app_state::known_user_public_key(session.profile_home, known_user)?
+    else {
+        bail!("Known user '{}' does not exist.", known_user);
+    };
+    let keys = app_state::route_keys_for_known_user(session.profile_home, known_user)?;
+    if keys.is_empty() {
+        bail!("No route record keys configured for {}.", known_user);
+    }
+    session.release_route();
+    session.known_user = known_user.to_owned();
+    session.recipient = recipient;
+    session.keys = keys;
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn command(line: &str) -> Result<SlashCommand> {
+        match ChatInput::parse(line)? {
+            ChatInput::Command(command) => Ok(command),
+            ChatInput::Text(text) => bail!("parsed as text: {text}"),
+        }
+    }
+
+    #[test]
+    fn plain_lines_are_text() -> Result<()> {
+        assert_eq!(
+            ChatInput::parse("hello there")?,
+            ChatInput::Text("hello there".to_owned())
+        );
+        assert_eq!(ChatInput::parse("a/b")?, ChatInput::Text("a/b".to_owned()));
+        Ok(())
+    }
+
+    #[test]
+    fn double_slash_escapes_a_leading_slash() -> Result<()> {
+        assert_eq!(
+            ChatInput::parse("//quit")?,
+            ChatInput::Text("/quit".to_owned())
+        );
+        assert_eq!(ChatInput::parse("//")?, ChatInput::Text("/".to_owned()));
+        Ok(())
+    }
+
+    #[test]
+    fn commands_without_arguments_parse() -> Result<()> {
+        assert_eq!(command("/quit")?, SlashCommand::Quit);
+        assert_eq!(command("/status")?, SlashCommand::Status);
+        assert_eq!(command("/retry")?, SlashCommand::Retry);
+        assert_eq!(command("/history")?, SlashCommand::History);
+        assert_eq!(command("/help")?, SlashCommand::Help);
+        Ok(())
+    }
+
+    #[test]
+    fn command_arguments_are_trimmed() -> Result<()> {
+        assert_eq!(
+            command("/file   notes v2.txt  ")?,
+            SlashCommand::File("notes v2.txt".to_owned())
+        );
+        assert_eq!(
+            command("/switch bob")?,
+            SlashCommand::Switch("bob".to_owned())
+        );
+        Ok(())
+    }
+
+    #[test]
+    fn bad_commands_are_rejected_instead_of_sent() {
+        for line in [
+            "/file",
+            "/file   ",
+            "/switch",
+            "/quit now",
+            "/retry 2",
+            "/nope",
+            "/",
+        ] {
+            let _ = ChatInput::parse(line).expect_err(line);
+        }
+    }
+}
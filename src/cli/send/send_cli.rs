@@ -1,7 +1,9 @@
 use crate::cli::InvokeContext;
 use crate::cli::ToArgs;
 use crate::cli::response::CliResponse;
+use crate::cli::send::broadcast::resolve_recipients;
 use crate::cli::send::chat::SendChatArgs;
+use crate::cli::send::file::SendFileArgs;
 use arbitrary::Arbitrary;
 use eyre::Result;
 use facet::Facet;
@@ -10,6 +12,8 @@ use std::ffi::OsString;
 
 #[derive(Facet, Arbitrary, Debug, PartialEq)]
 pub struct SendArgs {
+    /// A known user, several separated by commas, `@all`, or `@<room>` for a room's
+    /// members; several recipients need `send chat --message`.
     #[facet(args::positional)]
     pub known_user: String,
     #[facet(args::subcommand)]
@@ -20,6 +24,8 @@ pub struct SendArgs {
 #[repr(u8)]
 pub enum SendCommand {
     Chat(SendChatArgs),
+    /// Send a file in chunks, resuming an interrupted transfer.
+    File(SendFileArgs),
 }
 
 impl SendArgs {
@@ -27,10 +33,18 @@ impl SendArgs {
     ///
     /// Returns an error if the selected send subcommand fails.
     pub async fn invoke(self, context: &InvokeContext) -> Result<CliResponse> {
-        match self.command {
-            SendCommand::Chat(args) => args.invoke(context, &self.known_user).await?,
-        }
-        Ok(CliResponse::empty())
+        Ok(match self.command {
+            SendCommand::Chat(args) => {
+                match resolve_recipients(context.profile_home(), &self.known_user)? {
+                    Some(recipients) => args.broadcast(context, recipients).await?.into(),
+                    None => match args.invoke(context, &self.known_user).await? {
+                        Some(response) => response.into(),
+                        None => CliResponse::empty(),
+                    },
+                }
+            }
+            SendCommand::File(args) => args.invoke(context, &self.known_user).await?.into(),
+        })
     }
 }
 
@@ -42,6 +56,10 @@ impl ToArgs for SendArgs {
                 args.push("chat".into());
                 args.extend(chat_args.to_args());
             }
+            SendCommand::File(file_args) => {
+                args.push("file".into());
+                args.extend(file_args.to_args());
+            }
         }
         args
     }
@@ -0,0 +1,225 @@
+//! Duplicate suppression and gap detection for inbound messages.
+//!
+//! A sender can deliver the same envelope twice, for example when an `app_message`
+//! gets through but a stale-route error makes the sender retry it. Recently seen
+//! message ids are remembered so repeats are dropped. Chat messages also carry a
+//! sequence number counted per sender; a jump past the next expected number means
+//! messages went missing, and a number at or below the highest seen arrived late.
+//! Highest sequence numbers that changed are handed out in batches for persisting.
+
+use crate::cli::envelope::MessageId;
+use std::collections::BTreeMap;
+use std::collections::BTreeSet;
+use std::collections::HashSet;
+use std::collections::VecDeque;
+
+/// Message ids remembered for duplicate suppression.
+const DEDUPE_WINDOW: usize = 4096;
+
+/// How an inbound message relates to what was already received from its sender.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Arrival {
+    /// The message id was seen within the dedupe window.
+    Duplicate,
+    /// The next message in sequence, or one without a sequence number.
+    InOrder,
+    /// Arrived after a message with a higher sequence number.
+    Late,
+    /// Sequence numbers `first..=last` were skipped before this message.
+    Gap { first: u64, last: u64 },
+}
+
+/// Tracks recent message ids and the highest sequence number seen per sender.
+#[derive(Clone, Debug, Default)]
+pub struct InboundSequencer {
+    recent: VecDeque<MessageId>,
+    seen: HashSet<MessageId>,
+    highest: BTreeMap<String, u64>,
+    unsaved: BTreeSet<String>,
+}
+
+impl InboundSequencer {
+    /// Start from the highest sequence numbers persisted for each sender, so gaps
+    /// that span listener restarts are still noticed.
+    #[must_use]
+    pub fn new(highest: BTreeMap<String, u64>) -> Self {
+        Self {
+            highest,
+            ..Self::default()
+        }
+    }
+
+    /// Classify a message and remember it.
+    ///
+    /// The first sequence number seen from a sender is taken as the starting point
+    /// rather than reported as a gap.
+    pub fn observe(
+        &mut self,
+        sender: &str,
+        message_id: MessageId,
+        sequence: Option<u64>,
+    ) -> Arrival {
+        if !self.seen.insert(message_id) {
+            return Arrival::Duplicate;
+        }
+        self.recent.push_back(message_id);
+        if self.recent.len() > DEDUPE_WINDOW
+            && let Some(oldest) = self.recent.pop_front()
+        {
+            let _ = self.seen.remove(&oldest);
+        }
+
+        let Some(sequence) = sequence else {
+            return Arrival::InOrder;
+        };
+        let previous = self.highest.get(sender).copied();
+        if previous.is_some_and(|highest| sequence <= highest) {
+            return Arrival::Late;
+        }
+        let _ = self.highest.insert(sender.to_owned(), sequence);
+        let _ = self.unsaved.insert(sender.to_owned());
+        match previous {
+            Some(highest) if sequence > highest + 1 => Arrival::Gap {
+                first: highest + 1,
+                last: sequence - 1,
+            },
+            _ => Arrival::InOrder,
+        }
+    }
+
+    /// Stop treating `message_id` as seen, so a retry of a message that could not be
+    /// handled is not dropped as a duplicate.
+    pub fn forget(&mut self, message_id: MessageId) {
+        if self.seen.remove(&message_id) {
+            self.recent.retain(|recent| *recent != message_id);
+        }
+    }
+
+    /// Number of senders whose highest sequence number changed since the last
+    /// [`Self::take_unsaved`].
+    #[must_use]
+    pub fn unsaved_len(&self) -> usize {
+        self.unsaved.len()
+    }
+
+    /// The highest sequence numbers that changed since the last call, for persisting.
+    pub fn take_unsaved(&mut self) -> BTreeMap<String, u64> {
+        std::mem::take(&mut self.unsaved)
+            .into_iter()
+            .filter_map(|sender| {
+                let sequence = self.highest.get(&sender).copied()?;
+                Some((sender, sequence))
+            })
+            .collect()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn id(byte: u8) -> MessageId {
+        MessageId([byte; 16])
+    }
+
+    #[test]
+    fn in_order_messages_are_accepted() {
+        let mut sequencer = InboundSequencer::default();
+        assert_eq!(sequencer.observe("a", id(1), Some(1)), Arrival::InOrder);
+        assert_eq!(sequencer.observe("a", id(2), Some(2)), Arrival::InOrder);
+        assert_eq!(sequencer.observe("a", id(3), None), Arrival::InOrder);
+    }
+
+    #[test]
+    fn repeated_message_ids_are_duplicates() {
+        let mut sequencer = InboundSequencer::default();
+        assert_eq!(sequencer.observe("a", id(1), Some(1)), Arrival::InOrder);
+        assert_eq!(sequencer.observe("a", id(1), Some(1)), Arrival::Duplicate);
+        assert_eq!(sequencer.observe("a", id(2), None), Arrival::InOrder);
+        assert_eq!(sequencer.observe("a", id(2), None), Arrival::Duplicate);
+    }
+
+    #[test]
+    fn skipped_sequence_numbers_are_a_gap() {
+        let mut sequencer = InboundSequencer::default();
+        assert_eq!(sequencer.observe("a", id(1), Some(1)), Arrival::InOrder);
+        assert_eq!(
+            sequencer.observe("a", id(2), Some(5)),
+            Arrival::Gap { first: 2, last: 4 }
+        );
+    }
+
+    #[test]
+    fn first_sequence_number_is_the_starting_point() {
+        let mut sequencer = InboundSequencer::default();
+        assert_eq!(sequencer.observe("a", id(1), Some(40)), Arrival::InOrder);
+    }
+
+    #[test]
+    fn gaps_span_persisted_state() {
+        let mut sequencer = InboundSequencer::new(BTreeMap::from([("a".to_owned(), 3)]));
+        assert_eq!(
+            sequencer.observe("a", id(1), Some(5)),
+            Arrival::Gap { first: 4, last: 4 }
+        );
+    }
+
+    #[test]
+    fn lower_sequence_numbers_are_late() {
+        let mut sequencer = InboundSequencer::default();
+        assert_eq!(sequencer.observe("a", id(1), Some(1)), Arrival::InOrder);
+        assert_eq!(
+            sequencer.observe("a", id(3), Some(3)),
+            Arrival::Gap { first: 2, last: 2 }
+        );
+        assert_eq!(sequencer.observe("a", id(2), Some(2)), Arrival::Late);
+        assert_eq!(sequencer.observe("a", id(4), Some(4)), Arrival::InOrder);
+    }
+
+    #[test]
+    fn senders_are_sequenced_separately() {
+        let mut sequencer = InboundSequencer::default();
+        assert_eq!(sequencer.observe("a", id(1), Some(7)), Arrival::InOrder);
+        assert_eq!(sequencer.observe("b", id(2), Some(1)), Arrival::InOrder);
+        assert_eq!(sequencer.observe("a", id(3), Some(8)), Arrival::InOrder);
+    }
+
+    #[test]
+    fn forgotten_messages_can_be_retried() {
+        let mut sequencer = InboundSequencer::default();
+        assert_eq!(sequencer.observe("a", id(1), None), Arrival::InOrder);
+        sequencer.forget(id(1));
+        assert_eq!(sequencer.observe("a", id(1), None), Arrival::InOrder);
+    }
+
+    #[test]
+    fn old_message_ids_leave_the_window() {
+        let mut sequencer = InboundSequencer::default();
+        let first = id(0);
+        assert_eq!(sequencer.observe("a", first, None), Arrival::InOrder);
+        for index in 0..DEDUPE_WINDOW {
+            let mut bytes = [1; 16];
+            bytes[..8].copy_from_slice(&index.to_le_bytes());
+            assert_eq!(
+                sequencer.observe("a", MessageId(bytes), None),
+                Arrival::InOrder
+            );
+        }
+        assert_eq!(sequencer.observe("a", first, None), Arrival::InOrder);
+    }
+
+    #[test]
+    fn changed_sequences_are_taken_once() {
+        let mut sequencer = InboundSequencer::default();
+        let _ = sequencer.observe("a", id(1), Some(1));
+        let _ = sequencer.observe("a", id(2), Some(2));
+        let _ = sequencer.observe("b", id(3), None);
+        assert_eq!(sequencer.unsaved_len(), 1);
+        assert_eq!(
+            sequencer.take_unsaved(),
+            BTreeMap::from([("a".to_owned(), 2)])
+        );
+        assert_eq!(sequencer.unsaved_len(), 0);
+        assert!(sequencer.take_unsaved().is_empty());
+    }
+}
@@ -148,6 +148,7 @@ async fn run_e2e_chat() -> Result<()> {
             command: RouteCommand::Add(RouteAddArgs {
                 name: "janet-inbox".to_owned(),
                 listen: false,
+                mailbox: false,
             }),
         }),
     )?;
@@ -183,8 +184,10 @@ async fn run_e2e_chat() -> Result<()> {
 
     let listener_command = CliCommand::Route(RouteArgs {
         command: RouteCommand::Listen(RouteListenArgs {
-            name: "janet-inbox".to_owned(),
+            name: Some("janet-inbox".to_owned()),
+            all: false,
             count: Some(1),
+            save_reply_routes: false,
         }),
     });
     log_typed_command(Some("Janet"), &listener_command);
@@ -199,6 +202,12 @@ async fn run_e2e_chat() -> Result<()> {
         command: SendCommand::Chat(SendChatArgs {
             message: Some("schoolbus".to_owned()),
             retry: Some(20),
+            ack: false,
+            queue: false,
+            reply_route: None,
+            hops: None,
+            stability: None,
+            sequencing: None,
         }),
     });
     log_typed_command(Some("Bob"), &sender_command);
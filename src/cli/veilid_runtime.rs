@@ -1,7 +1,17 @@
+use crate::cli::app_state;
 use crate::cli::app_state::ProfileHome;
+use crate::cli::app_state::ProfileVeilidConfig;
+use crate::cli::daemon::client::DaemonConnection;
 use eyre::Result;
+use eyre::bail;
+use std::path::Path;
 use std::sync::Arc;
+use std::sync::Mutex;
+use std::sync::OnceLock;
+use std::sync::atomic::AtomicBool;
+use std::sync::atomic::Ordering;
 use veilid_core::VeilidAPI;
+use veilid_core::VeilidCapability;
 use veilid_core::VeilidConfig;
 use veilid_core::VeilidConfigProtectedStore;
 use veilid_core::VeilidConfigTableStore;
@@ -9,6 +19,26 @@ use veilid_core::VeilidUpdate;
 
 pub type UpdateCallback = Arc<dyn Fn(VeilidUpdate) + Send + Sync + 'static>;
 
+/// Whether the node started in this process joined a private network.
+static PRIVATE_NETWORK: AtomicBool = AtomicBool::new(false);
+
+/// The node kept attached by `daemon start`, lent to each command the daemon runs.
+static DAEMON_NODE: OnceLock<DaemonNode> = OnceLock::new();
+
+struct DaemonNode {
+    api: VeilidAPI,
+    /// Update callback of the command currently running on the daemon, if any.
+    subscriber: Arc<Mutex<Option<UpdateCallback>>>,
+}
+
+impl DaemonNode {
+    fn set_subscriber(&self, callback: Option<UpdateCallback>) {
+        if let Ok(mut subscriber) = self.subscriber.lock() {
+            *subscriber = callback;
+        }
+    }
+}
+
 #[must_use]
 pub fn printing_update_callback(print_updates: bool) -> UpdateCallback {
     Arc::new(move |update: VeilidUpdate| {
@@ -20,25 +50,138 @@ pub fn printing_update_callback(print_updates: bool) -> UpdateCallback {
 
 /// Start a Veilid API instance for a specific profile.
 ///
+/// Inside a running daemon this returns the daemon's attached node instead, and
+/// `update_callback` receives its updates until [`shutdown_api`] is called.
+///
 /// # Errors
 ///
-/// Returns an error if profile data directories cannot be created,
-/// Veilid startup fails, or attach fails when requested.
+/// Returns an error if the profile has a fixed listen address that its running
+/// daemon already holds, profile data directories cannot be created, the profile's
+/// Veilid configuration is invalid, Veilid startup fails, or attach fails when
+/// requested.
 pub async fn start_api_for_profile(
     profile_home: &ProfileHome,
     attach: bool,
     update_callback: UpdateCallback,
 ) -> Result<VeilidAPI> {
-    let veilid_data_dir = profile_home.profile_veilid_dir();
+    if let Some(node) = DAEMON_NODE.get() {
+        node.set_subscriber(Some(update_callback));
+        return Ok(node.api.clone());
+    }
+
+    let overrides = app_state::veilid_config(profile_home)?;
+    if let Some(listen_address) = &overrides.listen_address
+        && DaemonConnection::connect(profile_home).await?.is_some()
+    {
+        bail!(
+            "The daemon for {} already listens on {}; run `daemon stop` first or clear the listen address with `profile network`.",
+            profile_home.profile(),
+            listen_address
+        );
+    }
+    start_api(
+        &profile_home.profile_veilid_dir(),
+        format!("vetchricore-{}", profile_home.profile()),
+        &overrides,
+        attach,
+        update_callback,
+    )
+    .await
+}
+
+/// Shut down an instance from [`start_api_for_profile`].
+///
+/// Inside a running daemon the node stays attached; only the command's update
+/// callback is dropped.
+pub async fn shutdown_api(api: VeilidAPI) {
+    match DAEMON_NODE.get() {
+        Some(node) => node.set_subscriber(None),
+        None => api.shutdown().await,
+    }
+}
+
+/// Start and attach the daemon's node, which later [`start_api_for_profile`] calls in
+/// this process share.
+///
+/// The daemon keeps its own Veilid data directory so commands run outside it, such as
+/// `route listen`, can start their own node for the profile at the same time, unless
+/// the profile has a fixed listen address, which only one node can bind.
+///
+/// # Errors
+///
+/// Returns an error if a daemon node was already started in this process, the
+/// profile's Veilid configuration is invalid, or Veilid cannot be started and attached.
+pub async fn start_daemon_node(profile_home: &ProfileHome) -> Result<VeilidAPI> {
+    if DAEMON_NODE.get().is_some() {
+        bail!("The daemon node is already running.");
+    }
+
+    let subscriber = Arc::new(Mutex::new(None::<UpdateCallback>));
+    let callback = {
+        let subscriber = Arc::clone(&subscriber);
+        Arc::new(move |update: VeilidUpdate| {
+            let current = subscriber
+                .lock()
+                .ok()
+                .and_then(|subscriber| subscriber.clone());
+            if let Some(callback) = current {
+                callback(update);
+            }
+        }) as UpdateCallback
+    };
+    let overrides = app_state::veilid_config(profile_home)?;
+    let api = start_api(
+        &profile_home.profile_daemon_dir().join("veilid"),
+        format!("vetchricore-{}-daemon", profile_home.profile()),
+        &overrides,
+        true,
+        callback,
+    )
+    .await?;
+
+    if DAEMON_NODE
+        .set(DaemonNode {
+            api: api.clone(),
+            subscriber,
+        })
+        .is_err()
+    {
+        api.shutdown().await;
+        bail!("The daemon node is already running.");
+    }
+    Ok(api)
+}
+
+/// Whether the node started in this process joined a private network, where
+/// public internet readiness may never be reported.
+#[must_use]
+pub fn on_private_network() -> bool {
+    PRIVATE_NETWORK.load(Ordering::Acquire)
+}
+
+/// Drop the update callback left by a daemon command that returned early.
+pub fn end_daemon_command() {
+    if let Some(node) = DAEMON_NODE.get() {
+        node.set_subscriber(None);
+    }
+}
+
+async fn start_api(
+    veilid_data_dir: &Path,
+    namespace: String,
+    overrides: &ProfileVeilidConfig,
+    attach: bool,
+    update_callback: UpdateCallback,
+) -> Result<VeilidAPI> {
     let protected_store_dir = veilid_data_dir.join("protected_store");
     let table_store_dir = veilid_data_dir.join("table_store");
 
     std::fs::create_dir_all(&protected_store_dir)?;
     std::fs::create_dir_all(&table_store_dir)?;
 
-    let config = VeilidConfig {
+    let mut config = VeilidConfig {
         program_name: "vetchricore".to_owned(),
-        namespace: format!("vetchricore-{}", profile_home.profile()),
+        namespace,
         protected_store: VeilidConfigProtectedStore {
             always_use_insecure_storage: true,
             directory: protected_store_dir.to_string_lossy().to_string(),
@@ -50,6 +193,8 @@ pub async fn start_api_for_profile(
         },
         ..Default::default()
     };
+    apply_overrides(&mut config, overrides)?;
+    PRIVATE_NETWORK.store(overrides.is_private_network(), Ordering::Release);
 
     let veilid_api = veilid_core::api_startup(update_callback, config).await?;
     if attach {
@@ -58,3 +203,103 @@ pub async fn start_api_for_profile(
 
     Ok(veilid_api)
 }
+
+/// Apply a profile's configuration file on top of the built-in Veilid configuration.
+fn apply_overrides(config: &mut VeilidConfig, overrides: &ProfileVeilidConfig) -> Result<()> {
+    if !overrides.bootstrap.is_empty() {
+        config
+            .network
+            .routing_table
+            .bootstrap
+            .clone_from(&overrides.bootstrap);
+    }
+    if let Some(listen_address) = &overrides.listen_address {
+        let protocol = &mut config.network.protocol;
+        protocol.udp.listen_address.clone_from(listen_address);
+        protocol.tcp.listen_address.clone_from(listen_address);
+        protocol.ws.listen_address.clone_from(listen_address);
+    }
+    if let Some(network_key) = &overrides.network_key {
+        config.network.network_key_password = Some(network_key.clone());
+    }
+    for code in &overrides.disable_capabilities {
+        let capability = code
+            .parse::<VeilidCapability>()
+            .map_err(|error| eyre::eyre!("Invalid capability '{}': {}", code, error))?;
+        config.capabilities.disable.push(capability);
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn overrides_replace_network_settings() -> Result<()> {
+        let mut config = VeilidConfig::default();
+        let overrides = ProfileVeilidConfig {
+            bootstrap: vec!["ws://127.0.0.1:5150/ws".to_owned()],
+            listen_address: Some("127.0.0.1:5151".to_owned()),
+            network_key: Some("devnet".to_owned()),
+            disable_capabilities: vec!["TUNL".to_owned(), "RLAY".to_owned()],
+        };
+        apply_overrides(&mut config, &overrides)?;
+
+        assert_eq!(
+            config.network.routing_table.bootstrap,
+            vec!["ws://127.0.0.1:5150/ws".to_owned()]
+        );
+        let protocol = &config.network.protocol;
+        assert_eq!(protocol.udp.listen_address, "127.0.0.1:5151");
+        assert_eq!(protocol.tcp.listen_address, "127.0.0.1:5151");
+        assert_eq!(protocol.ws.listen_address, "127.0.0.1:5151");
+        assert_eq!(
+            config.network.network_key_password.as_deref(),
+            Some("devnet")
+        );
+        let disabled: Vec<String> = config
+            .capabilities
+            .disable
+            .iter()
+            .map(ToString::to_string)
+            .collect();
+        assert_eq!(disabled, ["TUNL", "RLAY"]);
+        Ok(())
+    }
+
+    #[test]
+    fn default_overrides_keep_veilid_defaults() -> Result<()> {
+        let mut config = VeilidConfig::default();
+        let defaults = VeilidConfig::default();
+        apply_overrides(&mut config, &ProfileVeilidConfig::default())?;
+        assert_eq!(
+            config.network.routing_table.bootstrap,
+            defaults.network.routing_table.bootstrap
+        );
+        assert_eq!(
+            config.network.protocol.udp.listen_address,
+            defaults.network.protocol.udp.listen_address
+        );
+        assert_eq!(
+            config.network.network_key_password,
+            defaults.network.network_key_password
+        );
+        assert_eq!(
+            config.capabilities.disable.len(),
+            defaults.capabilities.disable.len()
+        );
+        Ok(())
+    }
+
+    #[test]
+    fn malformed_capability_codes_are_rejected() {
+        let mut config = VeilidConfig::default();
+        let overrides = ProfileVeilidConfig {
+            disable_capabilities: vec!["TUNNEL".to_owned()],
+            ..ProfileVeilidConfig::default()
+        };
+        apply_overrides(&mut config, &overrides)
+            .expect_err("a capability code that is not four letters should be rejected");
+    }
+}